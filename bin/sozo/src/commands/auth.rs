@@ -1,25 +1,28 @@
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
 use cainome::cairo_serde::ContractAddress;
 use clap::{Args, Subcommand};
 use colored::Colorize;
-use dojo_utils::Invoker;
+use dojo_utils::{Invoker, TxnConfig};
 use dojo_world::config::ProfileConfig;
 use dojo_world::constants::WORLD;
 use dojo_world::contracts::{ContractInfo, WorldContract};
-use dojo_world::diff::{DiffPermissions, WorldDiff};
+use dojo_world::diff::{DiffPermissions, ResourceDiff, WorldDiff};
+use dojo_world::ResourceType;
 use scarb::core::{Config, Workspace};
+use sozo_ops::auth_audit::{self, AuditPolicy, Grantee, GranteeKind, ResourcePermissions};
 use sozo_ops::migration_ui::MigrationUi;
 use sozo_scarbext::WorkspaceExt;
-use starknet::core::types::Felt;
-use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::JsonRpcClient;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{BlockId, BlockTag, Felt, StarknetError};
+use starknet::providers::{Provider, ProviderError};
 use tracing::trace;
 
 use super::options::account::{AccountOptions, SozoAccount};
-use super::options::starknet::StarknetOptions;
+use super::options::starknet::{StarknetOptions, StarknetProvider};
 use super::options::transaction::TransactionOptions;
 use super::options::world::WorldOptions;
 use crate::utils;
@@ -66,6 +69,23 @@ pub enum AuthCommand {
         #[command(flatten)]
         world: WorldOptions,
     },
+    #[command(about = "Audit the permissions of the world, and check them against a policy.")]
+    Audit {
+        #[arg(long)]
+        #[arg(help = "A TOML file of policy rules. The command fails if the permissions violate \
+                      the policy.")]
+        policy: Option<PathBuf>,
+
+        #[arg(long)]
+        #[arg(help = "Print the findings as JSON.")]
+        json: bool,
+
+        #[command(flatten)]
+        starknet: StarknetOptions,
+
+        #[command(flatten)]
+        world: WorldOptions,
+    },
     #[command(about = "Clone all permissions that one contract has to another.")]
     Clone {
         #[arg(help = "The tag or address of the source contract to clone the permissions from.")]
@@ -86,6 +106,24 @@ pub enum AuthCommand {
         #[arg(global = true)]
         revoke_from: bool,
 
+        #[command(flatten)]
+        common: CommonAuthOptions,
+    },
+    #[command(about = "Transfer the ownership of the world, of its namespaces and of its \
+                       resources from the account to another contract, like a multisig.")]
+    TransferOwnership {
+        #[arg(long)]
+        #[arg(help = "The address of the new owner, which must be a deployed account or contract.")]
+        to: Felt,
+
+        #[arg(long)]
+        #[arg(help = "Only print the resources whose ownership would be transferred.")]
+        dry_run: bool,
+
+        #[arg(long, default_value_t = 50)]
+        #[arg(help = "The maximum number of grant or revoke calls sent in a single transaction.")]
+        batch_size: usize,
+
         #[command(flatten)]
         common: CommonAuthOptions,
     },
@@ -195,6 +233,9 @@ impl AuthArgs {
                 AuthCommand::List { resource, show_address, starknet, world } => {
                     list_permissions(resource, show_address, starknet, world, &ws).await?;
                 }
+                AuthCommand::Audit { policy, json, starknet, world } => {
+                    audit(policy, json, starknet, world, &ws).await?;
+                }
                 AuthCommand::Clone { revoke_from, common, from, to } => {
                     if from == to {
                         anyhow::bail!(
@@ -205,6 +246,13 @@ impl AuthArgs {
 
                     clone_permissions(common, &ws, revoke_from, from, to).await?;
                 }
+                AuthCommand::TransferOwnership { to, dry_run, batch_size, common } => {
+                    if batch_size == 0 {
+                        anyhow::bail!("The batch size must be greater than 0.");
+                    }
+
+                    transfer_ownership(common, &ws, to, dry_run, batch_size).await?;
+                }
             };
 
             Ok(())
@@ -383,6 +431,191 @@ async fn clone_permissions(
     Ok(())
 }
 
+/// A resource, or the world, owned by the account transferring its ownership.
+#[derive(Debug, Clone, PartialEq)]
+struct OwnedResource {
+    selector: Felt,
+    label: String,
+    level: OwnershipLevel,
+}
+
+/// The level of an ownership, an owner of a level being able to revoke the owners of the lower
+/// levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum OwnershipLevel {
+    Resource,
+    Namespace,
+    World,
+}
+
+/// Returns the resources owned by `owner` on chain, including the world and the resources not
+/// managed by the local project.
+///
+/// The resources are sorted by ownership level, the world being last, which is the order in
+/// which the ownership of `owner` can be revoked.
+fn owned_resources(world_diff: &WorldDiff, owner: Felt) -> Vec<OwnedResource> {
+    let mut owned = vec![];
+
+    for (selector, owners) in world_diff.external_owners.iter() {
+        if !owners.contains(&owner) || world_diff.resources.contains_key(selector) {
+            continue;
+        }
+
+        let (label, level) = if *selector == WORLD {
+            ("World".to_string(), OwnershipLevel::World)
+        } else {
+            (format!("{:#066x}", selector), OwnershipLevel::Resource)
+        };
+
+        owned.push(OwnedResource { selector: *selector, label, level });
+    }
+
+    for (selector, resource) in world_diff.resources.iter() {
+        if !world_diff.get_owners(*selector).remote.iter().any(|o| o.address == owner) {
+            continue;
+        }
+
+        let level = if resource.resource_type() == ResourceType::Namespace {
+            OwnershipLevel::Namespace
+        } else {
+            OwnershipLevel::Resource
+        };
+
+        owned.push(OwnedResource { selector: *selector, label: resource.tag(), level });
+    }
+
+    sort_for_revocation(&mut owned);
+    owned
+}
+
+/// Sorts the owned resources by ownership level, then by label for a deterministic output.
+fn sort_for_revocation(owned: &mut [OwnedResource]) {
+    owned.sort_by(|a, b| a.level.cmp(&b.level).then_with(|| a.label.cmp(&b.label)));
+}
+
+/// Transfers the ownership of all the resources owned by the account to the `to` address.
+///
+/// The ownership is first granted to `to`, and only revoked from the account once every grant
+/// is verified on chain, so no resource is left without owner if a transaction fails.
+async fn transfer_ownership(
+    options: CommonAuthOptions,
+    ws: &Workspace<'_>,
+    to: Felt,
+    dry_run: bool,
+    batch_size: usize,
+) -> Result<()> {
+    let mut migration_ui = MigrationUi::new_with_frames(
+        "Gathering permissions from the world...",
+        vec!["🌍", "🔍", "📜"],
+    );
+
+    let (world_diff, account, _) = utils::get_world_diff_and_account(
+        options.account,
+        options.starknet,
+        options.world,
+        ws,
+        &mut Some(&mut migration_ui),
+    )
+    .await?;
+
+    migration_ui.stop();
+
+    let from = account.address();
+    if from == to {
+        anyhow::bail!("The account {:#066x} is already the owner.", from);
+    }
+
+    // An address without contract can't use the ownership, which would be lost once revoked.
+    match account.provider().get_class_hash_at(BlockId::Tag(BlockTag::Pending), to).await {
+        Ok(_) => {}
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+            anyhow::bail!(
+                "No contract is deployed at {:#066x}, the ownership can't be transferred to it.",
+                to
+            );
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let owned = owned_resources(&world_diff, from);
+    if owned.is_empty() {
+        println!("The account {:#066x} doesn't own any resource.", from);
+        return Ok(());
+    }
+
+    println!(
+        "Ownership of the following resources to be transferred from {} to {}:",
+        format!("{:#066x}", from).bright_blue(),
+        format!("{:#066x}", to).bright_blue(),
+    );
+    for resource in &owned {
+        println!("    {}", resource.label.bright_yellow());
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    if !utils::prompt_confirm("\nContinue?")? {
+        return Ok(());
+    }
+
+    // Each batch must be accepted before the ownership is verified.
+    let mut txn_config: TxnConfig = options.transaction.try_into()?;
+    txn_config.wait = true;
+
+    let world = WorldContract::new(world_diff.world_info.address, &account);
+
+    for batch in owned.chunks(batch_size) {
+        let mut invoker = Invoker::new(&account, txn_config);
+        for resource in batch {
+            invoker.add_call(world.grant_owner_getcall(&resource.selector, &ContractAddress(to)));
+        }
+
+        let res = invoker.multicall().await?;
+        println!("{}", res);
+    }
+
+    let mut not_granted = vec![];
+    for resource in &owned {
+        if !world.is_owner(&resource.selector, &ContractAddress(to)).call().await? {
+            not_granted.push(resource.label.clone());
+        }
+    }
+
+    if !not_granted.is_empty() {
+        anyhow::bail!(
+            "The ownership of {} has not been granted to {:#066x}, the account {:#066x} remains \
+             owner of all the resources.",
+            not_granted.join(", "),
+            to,
+            from
+        );
+    }
+
+    // The world comes last, the account keeping the ownership of the world until all the other
+    // ownerships are revoked.
+    for batch in owned.chunks(batch_size) {
+        let mut invoker = Invoker::new(&account, txn_config);
+        for resource in batch {
+            invoker.add_call(
+                world.revoke_owner_getcall(&resource.selector, &ContractAddress(from)),
+            );
+        }
+
+        let res = invoker.multicall().await?;
+        println!("{}", res);
+    }
+
+    println!(
+        "Ownership of {} resource(s) transferred to {}.",
+        owned.len(),
+        format!("{:#066x}", to).bright_blue()
+    );
+
+    Ok(())
+}
+
 /// Resolves the address or tag to an address.
 fn resolve_address_or_tag(address_or_tag: &str, world_diff: &WorldDiff) -> Result<Felt> {
     if address_or_tag.starts_with("0x") {
@@ -498,6 +731,134 @@ async fn list_permissions(
     Ok(())
 }
 
+/// Audits the permissions of the world, failing if they violate the policy.
+async fn audit(
+    policy: Option<PathBuf>,
+    json: bool,
+    starknet: StarknetOptions,
+    world: WorldOptions,
+    ws: &Workspace<'_>,
+) -> Result<()> {
+    let policy = policy.map(|path| AuditPolicy::from_file(&path)).transpose()?;
+
+    let (world_diff, provider, _) = utils::get_world_diff_and_provider(starknet, world, ws).await?;
+
+    let contract_tags = world_diff
+        .get_contracts_addresses()
+        .into_iter()
+        .map(|(selector, address)| (address, world_diff.resources[&selector].tag()))
+        .collect::<HashMap<_, _>>();
+
+    // The remote grantees of the world and of its resources, and whether they are declared.
+    let mut permissions = vec![];
+
+    let world_grantees = |grantees: Option<&HashSet<Felt>>| {
+        let mut grantees =
+            grantees.into_iter().flatten().map(|address| (*address, true)).collect::<Vec<_>>();
+        grantees.sort();
+        grantees
+    };
+    permissions.push((
+        "World".to_string(),
+        None,
+        world_grantees(world_diff.external_writers.get(&WORLD)),
+        world_grantees(world_diff.external_owners.get(&WORLD)),
+    ));
+
+    let mut resources = world_diff
+        .resources
+        .values()
+        .filter(|r| !matches!(r, ResourceDiff::Created(_)))
+        .collect::<Vec<_>>();
+    resources.sort_by_key(|r| r.tag());
+
+    let remote_grantees = |diff: DiffPermissions| {
+        let mut grantees =
+            diff.remote.iter().map(|g| (g.address, diff.local.contains(g))).collect::<Vec<_>>();
+        grantees.sort();
+        grantees
+    };
+
+    for resource in resources {
+        let namespace_resources = (resource.resource_type() == ResourceType::Namespace).then(|| {
+            world_diff
+                .resources
+                .values()
+                .filter(|r| {
+                    r.resource_type() != ResourceType::Namespace
+                        && r.namespace() == resource.name()
+                })
+                .count()
+        });
+
+        permissions.push((
+            resource.tag(),
+            namespace_resources,
+            remote_grantees(world_diff.get_writers(resource.dojo_selector())),
+            remote_grantees(world_diff.get_owners(resource.dojo_selector())),
+        ));
+    }
+
+    let mut kinds = HashMap::new();
+    for (_, _, writers, owners) in &permissions {
+        for (address, _) in writers.iter().chain(owners) {
+            if kinds.contains_key(address) {
+                continue;
+            }
+
+            let kind = match contract_tags.get(address) {
+                Some(tag) => GranteeKind::DojoContract(tag.clone()),
+                None => auth_audit::non_dojo_grantee_kind(&provider, *address).await?,
+            };
+            kinds.insert(*address, kind);
+        }
+    }
+
+    let grantees = |grantees: Vec<(Felt, bool)>| {
+        grantees
+            .into_iter()
+            .map(|(address, declared)| Grantee { address, kind: kinds[&address].clone(), declared })
+            .collect()
+    };
+
+    let resources = permissions
+        .into_iter()
+        .map(|(tag, namespace_resources, writers, owners)| ResourcePermissions {
+            tag,
+            namespace_resources,
+            writers: grantees(writers),
+            owners: grantees(owners),
+        })
+        .collect::<Vec<_>>();
+
+    let findings = auth_audit::audit_permissions(&resources, policy.as_ref());
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+    } else if findings.is_empty() {
+        println!("No finding on the permissions of the world.");
+    } else {
+        for finding in &findings {
+            let level =
+                if finding.violation { "violation".bright_red() } else { "warning".yellow() };
+
+            println!(
+                "{level} {} {}: {}",
+                finding.rule.to_string().bright_black(),
+                finding.resource.bright_blue(),
+                finding.message
+            );
+        }
+    }
+
+    let violations = findings.iter().filter(|f| f.violation).count();
+    if violations > 0 {
+        anyhow::bail!("The permissions of the world violate the policy {violations} time(s).");
+    }
+
+    Ok(())
+}
+
 /// Pretty prints the permissions of a resource.
 fn print_diff_permissions(diff: &DiffPermissions, show_address: bool) {
     if !diff.only_local().is_empty() {
@@ -648,7 +1009,7 @@ async fn get_world_contract(
     contracts: &HashMap<String, ContractInfo>,
     options: &CommonAuthOptions,
     profile_config: &ProfileConfig,
-) -> Result<WorldContract<SozoAccount<JsonRpcClient<HttpTransport>>>> {
+) -> Result<WorldContract<SozoAccount<StarknetProvider>>> {
     let env = profile_config.env.as_ref();
     let (provider, _) = options.starknet.provider(env)?;
     let account = options.account.account(provider, env, &options.starknet, contracts).await?;
@@ -800,4 +1161,28 @@ mod tests {
         assert_eq!(selector, Felt::from_str("0x123").unwrap());
         assert_eq!(address, Felt::from_str("0x456").unwrap());
     }
+
+    #[test]
+    fn test_sort_for_revocation() {
+        let owned = |label: &str, level| OwnedResource {
+            selector: dojo_types::naming::compute_selector_from_tag_or_name(label),
+            label: label.to_string(),
+            level,
+        };
+
+        let mut resources = vec![
+            owned("World", OwnershipLevel::World),
+            owned("ns", OwnershipLevel::Namespace),
+            owned("ns-actions", OwnershipLevel::Resource),
+            owned("abc", OwnershipLevel::Namespace),
+            owned("ns-Position", OwnershipLevel::Resource),
+        ];
+
+        sort_for_revocation(&mut resources);
+
+        assert_eq!(
+            resources.iter().map(|r| r.label.as_str()).collect::<Vec<_>>(),
+            vec!["ns-Position", "ns-actions", "abc", "ns", "World"]
+        );
+    }
 }
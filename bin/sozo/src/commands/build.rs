@@ -15,6 +15,7 @@ use tabled::{Table, Tabled};
 use tracing::debug;
 
 use crate::commands::check_package_dojo_version;
+use crate::utils;
 
 #[derive(Debug, Clone, Args)]
 pub struct BuildArgs {
@@ -42,6 +43,11 @@ pub struct BuildArgs {
     #[arg(help = "Output directory.", default_value = "bindings")]
     pub bindings_output: String,
 
+    #[arg(long)]
+    #[arg(help = "Check that the contracts are writers of the models and events they write, \
+                  as granted by the profile config.")]
+    pub check_writers: bool,
+
     /// Specify the features to activate.
     #[command(flatten)]
     pub features: FeaturesSpec,
@@ -155,6 +161,20 @@ impl BuildArgs {
         // directly during the compilation to get the data we need from it.
         config.tokio_handle().block_on(bindgen.generate(None)).expect("Error generating bindings");
 
+        if self.check_writers {
+            // The build succeeded, a missing profile config or world artifacts only skip the
+            // check.
+            let checked = ws.load_world_local().and_then(|world| {
+                let contracts_dependencies = utils::contracts_dependencies(&ws, &world)?;
+                utils::print_writer_warnings(&contracts_dependencies, &world.profile_config);
+                Ok(())
+            });
+
+            if let Err(e) = checked {
+                println!("{}", format!("warning: writers not checked: {e}").bright_yellow());
+            }
+        }
+
         if self.stats != StatOptions::default() {
             let world = WorldLocal::from_directory(
                 ws.target_dir_profile().to_string(),
@@ -224,6 +244,7 @@ impl Default for BuildArgs {
             unity: false,
             unrealengine: false,
             bindings_output: "bindings".to_string(),
+            check_writers: false,
             stats: StatOptions::default(),
             packages: None,
         }
@@ -13,10 +13,12 @@ use tracing::{error, info, trace};
 use super::build::BuildArgs;
 use super::migrate::MigrateArgs;
 use super::options::account::AccountOptions;
+use super::options::metrics::MetricsOptions;
 use super::options::starknet::StarknetOptions;
 use super::options::transaction::TransactionOptions;
 use super::options::world::WorldOptions;
 use crate::commands::options::ipfs::IpfsOptions;
+use crate::metrics;
 
 #[derive(Debug, Args)]
 pub struct DevArgs {
@@ -32,6 +34,9 @@ pub struct DevArgs {
     #[command(flatten)]
     pub transaction: TransactionOptions,
 
+    #[command(flatten)]
+    pub metrics: MetricsOptions,
+
     #[arg(long)]
     #[arg(help = "Generate Typescript bindings.")]
     pub typescript: bool,
@@ -59,6 +64,8 @@ pub struct DevArgs {
 
 impl DevArgs {
     pub fn run(self, config: &Config) -> Result<()> {
+        self.metrics.start(config.tokio_handle())?;
+
         let (file_tx, file_rx) = channel();
         let (rebuild_tx, rebuild_rx) = channel();
 
@@ -80,7 +87,7 @@ impl DevArgs {
             packages: self.packages,
             ..Default::default()
         };
-        build_args.clone().run(config)?;
+        run_build(&build_args, config)?;
         info!("Initial build completed.");
 
         // As this `dev` command is for development purpose only,
@@ -93,6 +100,8 @@ impl DevArgs {
             account: self.account,
             transaction: self.transaction,
             ipfs: IpfsOptions::default(),
+            fee_report: false,
+            seed: false,
         };
 
         let _ = migrate_args.clone().run(config);
@@ -143,7 +152,7 @@ impl DevArgs {
                 Err(std::sync::mpsc::TryRecvError::Empty) => {
                     if let Some(last_time) = last_event_time {
                         if last_time.elapsed() >= debounce_period {
-                            let _ = build_args.clone().run(config);
+                            let _ = run_build(&build_args, config);
                             let _ = migrate_args.clone().run(config);
                             last_event_time = None;
                         } else {
@@ -160,6 +169,14 @@ impl DevArgs {
     }
 }
 
+/// Runs the build, recording its duration.
+fn run_build(build_args: &BuildArgs, config: &Config) -> Result<()> {
+    let start = Instant::now();
+    let res = build_args.clone().run(config);
+    metrics::record_build(start.elapsed(), res.is_ok());
+    res
+}
+
 #[derive(Debug)]
 struct EventHandler;
 
@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use dojo_utils::provider as provider_utils;
+use dojo_world::config::{metadata_config, ProfileConfig, ResourceConfig, WorldMetadata};
+use dojo_world::contracts::ContractInfo;
+use dojo_world::diff::{ResourceDiff, WorldDiff, WorldStatus};
+use dojo_world::local::ResourceLocal;
+use dojo_world::metadata::MetadataStorage;
+use dojo_world::remote::ResourceRemote;
+use scarb::core::{Config, Workspace};
+use sozo_ops::migrate::fee_report::format_fee;
+use sozo_scarbext::WorkspaceExt;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
+use starknet::macros::{felt, selector};
+use starknet::providers::Provider;
+use tracing::trace;
+
+use super::check_package_dojo_version;
+use super::options::account::AccountOptions;
+use super::options::starknet::StarknetOptions;
+use super::options::world::WorldOptions;
+use crate::utils::{self, RPC_SPEC_VERSION};
+
+/// The address of the STRK fee token, the same on all the Starknet networks and on Katana.
+const STRK_ADDRESS: Felt =
+    felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d");
+
+#[derive(Debug, Args)]
+pub struct DoctorArgs {
+    #[command(flatten)]
+    world: WorldOptions,
+
+    #[command(flatten)]
+    starknet: StarknetOptions,
+
+    #[command(flatten)]
+    account: AccountOptions,
+}
+
+/// The outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// A check of the project, of the provider or of the world.
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    status: CheckStatus,
+    message: String,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.status {
+            CheckStatus::Pass => "pass".bright_green(),
+            CheckStatus::Warn => "warn".yellow(),
+            CheckStatus::Fail => "fail".bright_red(),
+        };
+
+        // The lines of the message are aligned after the name of the check.
+        let message = self.message.replace('\n', &format!("\n{:30}", ""));
+        write!(f, "[{label}] {} {message}", format!("{:<22}", self.name).bold())
+    }
+}
+
+/// The report of all the checks, printed as they are done.
+#[derive(Debug, Default)]
+struct DoctorReport {
+    checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    fn pass(&mut self, name: &'static str, message: impl Into<String>) {
+        self.add(name, CheckStatus::Pass, message.into());
+    }
+
+    fn warn(&mut self, name: &'static str, message: impl Into<String>) {
+        self.add(name, CheckStatus::Warn, message.into());
+    }
+
+    fn fail(&mut self, name: &'static str, message: impl Into<String>) {
+        self.add(name, CheckStatus::Fail, message.into());
+    }
+
+    /// Adds a check, passing if there is no issue and with the given status otherwise.
+    fn issues(
+        &mut self,
+        name: &'static str,
+        status: CheckStatus,
+        issues: Vec<String>,
+        pass_message: &str,
+    ) {
+        if issues.is_empty() {
+            self.pass(name, pass_message);
+        } else {
+            self.add(name, status, issues.join("\n"));
+        }
+    }
+
+    fn add(&mut self, name: &'static str, status: CheckStatus, message: String) {
+        let check = Check { name, status, message };
+        println!("{check}");
+
+        self.checks.push(check);
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+impl DoctorArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+
+        config.tokio_handle().block_on(async {
+            let mut report = DoctorReport::default();
+
+            self.diagnose(&ws, &mut report).await;
+
+            let failures = report.count(CheckStatus::Fail);
+            println!(
+                "\n{} passed, {} warnings, {} failed.",
+                report.count(CheckStatus::Pass),
+                report.count(CheckStatus::Warn),
+                failures
+            );
+
+            if failures > 0 {
+                anyhow::bail!("{failures} check(s) failed.");
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Runs the checks, the checks depending on a failed one being skipped.
+    async fn diagnose(self, ws: &Workspace<'_>, report: &mut DoctorReport) {
+        let has_artifacts = check_project(ws, report);
+
+        let profile_config = match ws.load_profile_config() {
+            Ok(profile_config) => profile_config,
+            Err(e) => {
+                report.fail("Profile config", e.to_string());
+                return;
+            }
+        };
+        let env = profile_config.env.as_ref();
+
+        let provider = match self.starknet.provider(env) {
+            Ok((provider, _)) => Arc::new(provider),
+            Err(e) => {
+                report.fail("Provider", e.to_string());
+                return;
+            }
+        };
+
+        if let Err(e) = provider_utils::health_check_provider(provider.clone()).await {
+            report.fail("Provider", e.to_string());
+            return;
+        }
+        report.pass("Provider", "The provider is reachable.");
+
+        match provider.spec_version().await {
+            Ok(version) => match utils::is_compatible_version(&version, RPC_SPEC_VERSION) {
+                Ok(true) => report.pass("RPC spec version", version),
+                Ok(false) => report.fail(
+                    "RPC spec version",
+                    format!("{version} is not supported, expected {RPC_SPEC_VERSION}."),
+                ),
+                Err(e) => report.fail("RPC spec version", e.to_string()),
+            },
+            Err(e) => report.fail("RPC spec version", e.to_string()),
+        }
+
+        let mut contracts: HashMap<String, ContractInfo> = HashMap::new();
+
+        if has_artifacts {
+            match utils::get_world_diff_and_provider(self.starknet.clone(), self.world, ws).await {
+                Ok((world_diff, provider, _)) => {
+                    contracts = (&world_diff).into();
+
+                    if let Err(e) =
+                        check_world(&world_diff, &provider, &profile_config, report).await
+                    {
+                        report.fail("World", e.to_string());
+                    }
+                }
+                Err(e) => report.fail("World", e.to_string()),
+            }
+        } else {
+            report.warn("World", "Skipped, the project must be built first.");
+        }
+
+        // The provider is shared with the health check, the account gets its own.
+        let provider = match self.starknet.provider(env) {
+            Ok((provider, _)) => provider,
+            Err(e) => {
+                report.fail("Account", e.to_string());
+                return;
+            }
+        };
+
+        match self.account.account(provider, env, &self.starknet, &contracts).await {
+            Ok(account) => {
+                if let Err(e) = check_account(&account, report).await {
+                    report.fail("Account", e.to_string());
+                }
+            }
+            Err(e) => report.warn("Account", format!("No usable account: {e}")),
+        }
+    }
+}
+
+/// Checks the versions and the artifacts of the project.
+///
+/// Returns whether the artifacts of the profile have been generated.
+fn check_project(ws: &Workspace<'_>, report: &mut DoctorReport) -> bool {
+    match utils::verify_cairo_version_compatibility(&ws.manifest_path().to_path_buf()) {
+        Ok(()) => report.pass("Cairo version", scarb::version::get().cairo.version),
+        Err(e) => report.fail("Cairo version", e.to_string()),
+    }
+
+    let dojo_issues = ws
+        .members()
+        .filter_map(|p| check_package_dojo_version(ws, &p).err())
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>();
+    report.issues("Dojo version", CheckStatus::Fail, dojo_issues, env!("CARGO_PKG_VERSION"));
+
+    match ws.ensure_profile_artifacts() {
+        Ok(()) => {
+            report.pass("Artifacts", "The artifacts of the profile are generated.");
+            true
+        }
+        Err(e) => {
+            report.fail("Artifacts", e.to_string());
+            false
+        }
+    }
+}
+
+/// Checks the status of the world and of its resources against the local project.
+async fn check_world<P>(
+    world_diff: &WorldDiff,
+    provider: &P,
+    profile_config: &ProfileConfig,
+    report: &mut DoctorReport,
+) -> Result<()>
+where
+    P: Provider + Send + Sync,
+{
+    let address = world_diff.world_info.address;
+
+    match world_diff.world_info.status {
+        WorldStatus::NotDeployed => report.warn(
+            "World",
+            format!("No world deployed at {:#066x}, run `sozo migrate` to deploy it.", address),
+        ),
+        WorldStatus::NewVersion => report.warn(
+            "World",
+            format!(
+                "The world at {:#066x} has a different class than the local world, run `sozo \
+                 migrate` to upgrade it.",
+                address
+            ),
+        ),
+        WorldStatus::Synced => report.pass("World", format!("Synced at {:#066x}.", address)),
+    }
+
+    if world_diff.world_info.status == WorldStatus::NotDeployed {
+        return Ok(());
+    }
+
+    let mut resources = world_diff.resources.values().collect::<Vec<_>>();
+    resources.sort_by_key(|r| r.tag());
+
+    let mut not_synced = vec![];
+    let mut declared_not_registered = vec![];
+    let mut uninitialized = vec![];
+
+    for resource in &resources {
+        match resource {
+            ResourceDiff::Created(local) => {
+                not_synced.push(format!("{} is not registered.", resource.tag()));
+
+                let tag = resource.tag();
+                if dojo_utils::is_declared(&tag, local.class_hash(), provider).await? {
+                    declared_not_registered.push(format!(
+                        "{tag} is declared ({:#066x}) but not registered, a migration may have \
+                         been interrupted.",
+                        local.class_hash()
+                    ));
+                }
+            }
+            ResourceDiff::Updated(_, _) => {
+                not_synced.push(format!("{} has local changes.", resource.tag()));
+            }
+            ResourceDiff::Synced(_, _) => {}
+        }
+
+        if let ResourceDiff::Updated(ResourceLocal::Contract(_), ResourceRemote::Contract(c))
+        | ResourceDiff::Synced(ResourceLocal::Contract(_), ResourceRemote::Contract(c)) = resource
+        {
+            if !c.is_initialized {
+                uninitialized.push(format!("{} is not initialized.", resource.tag()));
+            }
+        }
+    }
+
+    if !not_synced.is_empty() {
+        not_synced.push("Run `sozo migrate` to migrate them.".to_string());
+    }
+
+    let synced_message = format!("All the {} resources are synced.", resources.len());
+    report.issues("Resources", CheckStatus::Warn, not_synced, &synced_message);
+    report.issues(
+        "Declared classes",
+        CheckStatus::Warn,
+        declared_not_registered,
+        "No class declared without being registered.",
+    );
+    report.issues(
+        "Initialization",
+        CheckStatus::Warn,
+        uninitialized,
+        "All the registered contracts are initialized.",
+    );
+    report.issues(
+        "Metadata",
+        CheckStatus::Warn,
+        outdated_metadata(world_diff, profile_config),
+        "The metadata onchain match the profile.",
+    );
+
+    Ok(())
+}
+
+/// Returns the world and the registered resources whose metadata onchain differ from the
+/// profile configuration.
+fn outdated_metadata(world_diff: &WorldDiff, profile_config: &ProfileConfig) -> Vec<String> {
+    let mut outdated = vec![];
+
+    let world_metadata = WorldMetadata::from(profile_config.world.clone());
+    if world_metadata.metadata_hash() != world_diff.world_info.metadata_hash {
+        outdated.push("The world metadata is outdated.".to_string());
+    }
+
+    let configs = [
+        &profile_config.contracts,
+        &profile_config.libraries,
+        &profile_config.models,
+        &profile_config.events,
+    ];
+
+    for config in configs.into_iter().flatten().flatten() {
+        let selector = dojo_types::naming::compute_selector_from_tag_or_name(&config.tag);
+
+        let Some(resource) = world_diff.resources.get(&selector) else {
+            continue;
+        };
+
+        if let ResourceDiff::Created(_) = resource {
+            continue;
+        }
+
+        if resource_metadata_hash(config) != resource.metadata_hash() {
+            outdated.push(format!("The metadata of {} is outdated.", config.tag));
+        }
+    }
+
+    if !outdated.is_empty() {
+        let has_ipfs_config = profile_config.env.as_ref().is_some_and(|e| e.ipfs_config.is_some());
+
+        outdated.push(if has_ipfs_config {
+            "Run `sozo migrate` to upload them.".to_string()
+        } else {
+            "Run `sozo migrate` with the IPFS options, or with an `ipfs_config` in the profile \
+             environment, to upload them."
+                .to_string()
+        });
+    }
+
+    outdated
+}
+
+/// Returns the hash of the metadata of a resource, as set onchain by the migration.
+fn resource_metadata_hash(config: &ResourceConfig) -> Felt {
+    metadata_config::ResourceMetadata::from(config.clone()).metadata_hash()
+}
+
+/// Checks that the account is deployed and has some STRK to pay the fees.
+async fn check_account<A>(account: &A, report: &mut DoctorReport) -> Result<()>
+where
+    A: ConnectedAccount + Sync,
+{
+    let address = account.address();
+
+    if !dojo_utils::is_deployed(address, &account.provider()).await? {
+        report.fail("Account", format!("No account deployed at {:#066x}.", address));
+        return Ok(());
+    }
+
+    report.pass("Account", format!("Deployed at {:#066x}.", address));
+
+    let balance = account
+        .provider()
+        .call(
+            FunctionCall {
+                contract_address: STRK_ADDRESS,
+                entry_point_selector: selector!("balance_of"),
+                calldata: vec![address],
+            },
+            BlockId::Tag(BlockTag::Pending),
+        )
+        .await?;
+
+    match u256_balance(&balance)? {
+        Some(0) => report.warn("Balance", "The account has no STRK to pay the fees."),
+        Some(balance) => report.pass("Balance", format!("{} STRK", format_fee(balance))),
+        None => report.pass("Balance", "More than 2^128 FRI."),
+    }
+
+    Ok(())
+}
+
+/// Returns the balance serialized as an `u256` by `balance_of`, or `None` if it doesn't fit in
+/// an `u128`.
+fn u256_balance(balance: &[Felt]) -> Result<Option<u128>> {
+    let [low, high] = balance else {
+        anyhow::bail!("Invalid STRK balance {balance:?}, expected an u256.");
+    };
+
+    let low = u128::try_from(*low)
+        .map_err(|_| anyhow::anyhow!("Invalid STRK balance, the low part {low:#x} exceeds u128."))?;
+
+    Ok((*high == Felt::ZERO).then_some(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u256_balance() {
+        assert_eq!(u256_balance(&[Felt::from(42), Felt::ZERO]).unwrap(), Some(42));
+        assert_eq!(u256_balance(&[Felt::from(42), Felt::ONE]).unwrap(), None);
+
+        let too_large = Felt::from(u128::MAX) + Felt::ONE;
+        assert!(u256_balance(&[too_large, Felt::ZERO]).is_err());
+        assert!(u256_balance(&[Felt::from(42)]).is_err());
+    }
+
+    #[test]
+    fn test_report_issues() {
+        let mut report = DoctorReport::default();
+
+        report.issues("Resources", CheckStatus::Warn, vec![], "synced");
+        report.issues("Dojo version", CheckStatus::Fail, vec!["mismatch".to_string()], "ok");
+        report.warn("Account", "No usable account.");
+
+        let statuses = report.checks.iter().map(|c| (c.name, c.status)).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                ("Resources", CheckStatus::Pass),
+                ("Dojo version", CheckStatus::Fail),
+                ("Account", CheckStatus::Warn),
+            ]
+        );
+
+        assert_eq!(report.count(CheckStatus::Fail), 1);
+        assert_eq!(report.checks[0].message, "synced");
+    }
+}
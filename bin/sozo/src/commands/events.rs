@@ -1,18 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Args;
 use colored::Colorize;
 use dojo_world::contracts::abigen::world::{self, Event as WorldEvent};
+use dojo_world::contracts::naming;
 use dojo_world::diff::WorldDiff;
 use scarb::core::Config;
+use sozo_ops::events::WorldEventFetcher;
 use sozo_ops::model;
+use sozo_ops::resource_descriptor::ResourceDescriptor;
 use sozo_scarbext::WorkspaceExt;
-use starknet::core::types::{BlockId, BlockTag, EventFilter, Felt};
+use starknet::core::types::{
+    BlockId, EmittedEvent, Felt, MaybePendingBlockWithTxHashes, ReceiptBlock, StarknetError,
+    TransactionReceipt,
+};
 use starknet::core::utils::starknet_keccak;
-use starknet::macros::felt;
-use starknet::providers::Provider;
+use starknet::providers::{Provider, ProviderError};
+use starknet_crypto::poseidon_hash_many;
 use tracing::trace;
 
 use super::options::starknet::StarknetOptions;
@@ -25,6 +34,30 @@ pub struct EventsArgs {
     #[arg(value_delimiter = ',')]
     pub events: Option<Vec<String>>,
 
+    #[arg(long, value_delimiter = ',')]
+    #[arg(help = "Only show the records of these models, by tag or name.")]
+    pub model: Vec<ResourceDescriptor>,
+
+    #[arg(long, requires = "model")]
+    #[arg(help = "Only show the model records with these keys, `*` matching any key. \
+                  Ex: 0x123,*. With a `*`, the updates and deletions of a record are only \
+                  matched once its keys are set in the fetched blocks: the records set before \
+                  `--from-block` are only shown when all their keys are given.")]
+    pub keys: Option<String>,
+
+    #[arg(long = "event", value_delimiter = ',')]
+    #[arg(help = "Only show these Dojo events, by tag or name.")]
+    pub dojo_events: Vec<ResourceDescriptor>,
+
+    #[arg(long, value_delimiter = ',', conflicts_with = "model")]
+    #[arg(help = "Only show the Dojo events emitted by these contracts, by tag, name or \
+                  address.")]
+    pub contract: Vec<ResourceDescriptor>,
+
+    #[arg(long, conflicts_with_all = ["from_block", "to_block", "follow"])]
+    #[arg(help = "Only show the events emitted by this transaction.")]
+    pub tx: Option<Felt>,
+
     #[arg(short, long)]
     #[arg(help = "Block number from where to look for events")]
     pub from_block: Option<u64>,
@@ -48,6 +81,17 @@ pub struct EventsArgs {
     #[arg(help = "Print values as raw json")]
     pub json: bool,
 
+    #[arg(long, conflicts_with = "to_block")]
+    #[arg(help = "Keep printing the world events of the new blocks as they are emitted, until \
+                  interrupted.")]
+    pub follow: bool,
+
+    #[arg(long, requires = "follow")]
+    #[arg(help = "Interval in milliseconds between two polls of the new blocks when following \
+                  the events.")]
+    #[arg(default_value_t = 1000)]
+    pub poll_interval: u64,
+
     #[command(flatten)]
     pub world: WorldOptions,
 
@@ -62,7 +106,8 @@ impl EventsArgs {
             let profile_config = ws.load_profile_config()?;
 
             let (world_diff, provider, _) =
-                utils::get_world_diff_and_provider(self.starknet, self.world, &ws).await?;
+                utils::get_world_diff_and_provider(self.starknet.clone(), self.world.clone(), &ws)
+                    .await?;
             let provider = Arc::new(provider);
 
             let latest_block = provider.block_number().await?;
@@ -75,87 +120,460 @@ impl EventsArgs {
             };
             let to_block = self.to_block.unwrap_or(latest_block);
 
-            let chain_id = provider.chain_id().await?;
-            // Katana if it's not `SN_SEPOLIA` or `SN_MAIN`.
-            let is_katana = chain_id != felt!("0x534e5f5345504f4c4941")
-                && chain_id != felt!("0x534e5f4d41494e");
-
-            let mut current_from = from_block;
-            let mut events = Vec::new();
-
-            while current_from <= to_block {
-                let current_to = std::cmp::min(current_from + self.max_block_range - 1, to_block);
-
-                let filter = EventFilter {
-                    from_block: Some(BlockId::Number(current_from)),
-                    to_block: Some(BlockId::Number(current_to)),
-                    address: Some(world_diff.world_info.address),
-                    keys: self.events.as_ref().map(|e| {
-                        vec![e.iter().map(|event| starknet_keccak(event.as_bytes())).collect()]
-                    }),
-                };
+            let mut filter =
+                self.world_event_filter(&world_diff, &profile_config.namespace.default)?;
 
-                trace!(
-                    world_address = format!("{:#066x}", world_diff.world_info.address),
-                    self.chunk_size,
-                    ?filter,
-                    "Fetching remote world events for block range {}-{}.",
-                    current_from,
-                    current_to
-                );
-
-                let mut continuation_token = None;
-                loop {
-                    let page = provider
-                        .get_events(filter.clone(), continuation_token, self.chunk_size)
-                        .await?;
-
-                    if is_katana && page.events.is_empty() {
-                        break;
-                    }
-
-                    events.extend(page.events);
-
-                    continuation_token = page.continuation_token;
-                    if continuation_token.is_none() {
-                        break;
-                    }
-                }
+            let world_address = world_diff.world_info.address;
+            let fetcher = WorldEventFetcher::new(
+                &provider,
+                world_address,
+                self.chunk_size,
+                self.max_block_range,
+            )
+            .await?;
 
-                current_from = current_to + 1;
-            }
+            let events = if let Some(transaction_hash) = self.tx {
+                transaction_events(&provider, transaction_hash, world_address).await?
+            } else {
+                fetcher.fetch_range(filter.rpc_keys(), from_block, to_block).await?
+            };
 
             trace!(
                 events_count = events.len(),
-                world_address = format!("{:#066x}", world_diff.world_info.address),
+                world_address = format!("{:#066x}", world_address),
                 "Fetched events for world."
             );
 
             for event in &events {
-                match world::Event::try_from(event) {
-                    Ok(ev) => {
-                        trace!(?ev, "Processing world event.");
-                        match_event(
-                            &ev,
-                            &world_diff,
-                            event.block_number,
-                            event.transaction_hash,
-                            &provider,
-                        )
-                        .await?;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            ?e,
-                            "Failed to parse remote world event which is supposed to be valid."
-                        );
-                    }
+                if filter.matches(event) {
+                    print_event(event, &world_diff, &provider).await?;
                 }
             }
 
+            if self.follow {
+                self.follow_events(&world_diff, &fetcher, &mut filter, to_block + 1).await?;
+            }
+
             Ok(())
         })
     }
+
+    /// Resolves the filters of the world events given to the command.
+    fn world_event_filter(
+        &self,
+        world_diff: &WorldDiff,
+        default_namespace: &str,
+    ) -> Result<WorldEventFilter> {
+        let selector = |descriptor: &ResourceDescriptor| {
+            match descriptor.clone().ensure_namespace(default_namespace) {
+                ResourceDescriptor::Address(selector) => selector,
+                ResourceDescriptor::Tag(tag) | ResourceDescriptor::Name(tag) => {
+                    naming::compute_selector_from_tag(&tag)
+                }
+            }
+        };
+
+        let contract_addresses = world_diff.get_contracts_addresses();
+        let contracts = self
+            .contract
+            .iter()
+            .map(|descriptor| match descriptor {
+                ResourceDescriptor::Address(address) => Ok(*address),
+                _ => contract_addresses
+                    .get(&selector(descriptor))
+                    .copied()
+                    .ok_or_else(|| anyhow!("Contract `{descriptor:?}` not found in the world.")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(WorldEventFilter {
+            names: self
+                .events
+                .iter()
+                .flatten()
+                .map(|event| starknet_keccak(event.as_bytes()))
+                .collect(),
+            models: self.model.iter().map(selector).collect(),
+            keys: self.keys.as_deref().map(parse_keys_pattern).transpose()?,
+            events: self.dojo_events.iter().map(selector).collect(),
+            contracts,
+            matched_entities: HashSet::new(),
+        })
+    }
+
+    /// Prints the world events of the new blocks as they are emitted, until interrupted.
+    ///
+    /// The events of the pending block are printed as soon as they are emitted, and are not
+    /// printed again once their block is accepted. When one of the processed blocks is no longer
+    /// in the chain, the events of the new canonical blocks are printed again.
+    async fn follow_events<P: Provider + Send + Sync>(
+        &self,
+        world_diff: &WorldDiff,
+        fetcher: &WorldEventFetcher<'_, P>,
+        filter: &mut WorldEventFilter,
+        from_block: u64,
+    ) -> Result<()> {
+        let signals = dojo_utils::signal::wait_signals();
+        tokio::pin!(signals);
+
+        let message =
+            format!("Following world events from block {from_block}, press Ctrl-C to stop.");
+        println!("{}\n", message.bright_black());
+
+        let mut state = FollowState { next_block: from_block, ..Default::default() };
+
+        loop {
+            tokio::select! {
+                _ = &mut signals => break,
+                res = poll_events(world_diff, fetcher, filter, &mut state) => res?,
+            }
+
+            tokio::select! {
+                _ = &mut signals => break,
+                _ = tokio::time::sleep(Duration::from_millis(self.poll_interval)) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the world events emitted since the last poll.
+async fn poll_events<P: Provider + Send + Sync>(
+    world_diff: &WorldDiff,
+    fetcher: &WorldEventFetcher<'_, P>,
+    filter: &mut WorldEventFilter,
+    state: &mut FollowState,
+) -> Result<()> {
+    let provider = fetcher.provider();
+
+    let fork_block = find_reorg(&state.block_hashes, |number| block_hash(provider, number)).await?;
+    if let Some(fork_block) = fork_block {
+        let message =
+            format!("Chain reorganization detected, replaying events from block {fork_block}.");
+        println!("{}\n", message.yellow());
+
+        state.block_hashes.retain(|number, _| *number < fork_block);
+        state.next_block = state.next_block.min(fork_block);
+    }
+
+    let latest = provider.block_hash_and_number().await?;
+
+    if latest.block_number >= state.next_block {
+        let events =
+            fetcher.fetch_range(filter.rpc_keys(), state.next_block, latest.block_number).await?;
+
+        for (id, event) in event_ids(&events).into_iter().zip(&events) {
+            // Already printed from the pending block.
+            if state.accept(&id) || !filter.matches(event) {
+                continue;
+            }
+
+            print_event(event, world_diff, provider).await?;
+        }
+
+        state.block_hashes.insert(latest.block_number, latest.block_hash);
+        while state.block_hashes.len() > FOLLOW_REORG_DEPTH {
+            state.block_hashes.pop_first();
+        }
+
+        state.next_block = latest.block_number + 1;
+    }
+
+    let pending = fetcher.fetch_pending(filter.rpc_keys()).await?;
+
+    let new = state.track_pending(&event_ids(&pending));
+
+    for (is_new, event) in new.into_iter().zip(&pending) {
+        if is_new && filter.matches(event) {
+            print_event(event, world_diff, provider).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The names of the world events about the model records, whose second key is the model selector
+/// and third key the entity id.
+const STORE_EVENTS: [&str; 4] =
+    ["StoreSetRecord", "StoreUpdateRecord", "StoreUpdateMember", "StoreDelRecord"];
+
+/// The name of the world event emitting a Dojo event, whose second key is the event selector and
+/// third key the address of the emitting contract.
+const EVENT_EMITTED: &str = "EventEmitted";
+
+/// The filters of the world events, resolved from the command arguments.
+///
+/// The kinds of events (raw names, models and Dojo events) are combined with `OR`, and the other
+/// filters with `AND`. The filters are pushed into the keys of the RPC filter when all the
+/// requested events share the same keys, and are checked again on each fetched event.
+#[derive(Debug, Default)]
+struct WorldEventFilter {
+    /// The selectors of the events given by name, matching their first key.
+    names: Vec<Felt>,
+    /// The selectors of the models whose records are shown.
+    models: Vec<Felt>,
+    /// The keys of the model records shown, `None` matching any key.
+    keys: Option<Vec<Option<Felt>>>,
+    /// The selectors of the Dojo events shown.
+    events: Vec<Felt>,
+    /// The addresses of the contracts whose Dojo events are shown.
+    contracts: Vec<Felt>,
+    /// The entities whose keys match `keys`, from the records already shown.
+    matched_entities: HashSet<Felt>,
+}
+
+impl WorldEventFilter {
+    /// Returns the keys of the RPC filter, `None` matching all the events.
+    fn rpc_keys(&self) -> Option<Vec<Vec<Felt>>> {
+        let mut first = self.names.clone();
+        if !self.models.is_empty() {
+            first.extend(STORE_EVENTS.iter().map(|name| starknet_keccak(name.as_bytes())));
+        }
+        if !self.events.is_empty() || !self.contracts.is_empty() {
+            first.push(starknet_keccak(EVENT_EMITTED.as_bytes()));
+        }
+
+        if first.is_empty() {
+            return None;
+        }
+
+        let mut keys = vec![first];
+
+        // The next keys are only shared by the events of the same kind.
+        if self.names.is_empty() && self.events.is_empty() && self.contracts.is_empty() {
+            keys.push(self.models.clone());
+            if let Some(entity_id) = self.entity_id() {
+                keys.push(vec![entity_id]);
+            }
+        } else if self.names.is_empty() && self.models.is_empty() {
+            keys.push(self.events.clone());
+            if !self.contracts.is_empty() {
+                keys.push(self.contracts.clone());
+            }
+        }
+
+        Some(keys)
+    }
+
+    /// Returns the entity id of the model records shown, if all their keys are given.
+    fn entity_id(&self) -> Option<Felt> {
+        let keys = self.keys.as_ref()?.iter().copied().collect::<Option<Vec<_>>>()?;
+        Some(poseidon_hash_many(&keys))
+    }
+
+    /// Returns true if the event must be shown.
+    fn matches(&mut self, event: &EmittedEvent) -> bool {
+        let Some(&selector) = event.keys.first() else {
+            return false;
+        };
+
+        let is_store = STORE_EVENTS.iter().any(|name| starknet_keccak(name.as_bytes()) == selector);
+        let is_emitted = starknet_keccak(EVENT_EMITTED.as_bytes()) == selector;
+        let resource = event.keys.get(1);
+
+        let any_kind = self.names.is_empty() && self.models.is_empty() && self.events.is_empty();
+        let kind_matches = any_kind
+            || self.names.contains(&selector)
+            || (is_store && resource.is_some_and(|r| self.models.contains(r)))
+            || (is_emitted && resource.is_some_and(|r| self.events.contains(r)));
+
+        if !kind_matches {
+            return false;
+        }
+
+        if !self.contracts.is_empty() {
+            let contract = event.keys.get(2).filter(|_| is_emitted);
+            if !contract.is_some_and(|a| self.contracts.contains(a)) {
+                return false;
+            }
+        }
+
+        let Some(pattern) = &self.keys else {
+            return true;
+        };
+
+        let Some(&entity_id) = event.keys.get(2).filter(|_| is_store) else {
+            return false;
+        };
+
+        if self.matched_entities.contains(&entity_id) || self.entity_id() == Some(entity_id) {
+            return true;
+        }
+
+        // Only the set records hold the keys of the entity, the updates of the records set before
+        // the fetched blocks are only matched by their entity id when all the keys are given.
+        let keys_match = match WorldEvent::try_from(event) {
+            Ok(WorldEvent::StoreSetRecord(e)) => {
+                e.keys.len() == pattern.len()
+                    && e.keys.iter().zip(pattern).all(|(k, p)| p.is_none_or(|key| key == *k))
+            }
+            _ => false,
+        };
+
+        if keys_match {
+            self.matched_entities.insert(entity_id);
+        }
+
+        keys_match
+    }
+}
+
+/// Parses the keys of the `--keys` option, `*` matching any key.
+fn parse_keys_pattern(keys: &str) -> Result<Vec<Option<Felt>>> {
+    keys.split(',')
+        .map(|key| match key.trim() {
+            "*" => Ok(None),
+            key => Felt::from_str(key)
+                .map(Some)
+                .map_err(|_| anyhow!("Invalid key `{key}`, expected a felt or `*`.")),
+        })
+        .collect()
+}
+
+/// Returns the world events emitted by a transaction, from its receipt.
+async fn transaction_events<P: Provider + Send + Sync>(
+    provider: &P,
+    transaction_hash: Felt,
+    world_address: Felt,
+) -> Result<Vec<EmittedEvent>> {
+    let receipt = provider.get_transaction_receipt(transaction_hash).await?;
+
+    let (block_hash, block_number) = match receipt.block {
+        ReceiptBlock::Block { block_hash, block_number } => (Some(block_hash), Some(block_number)),
+        ReceiptBlock::Pending => (None, None),
+    };
+
+    let events = match receipt.receipt {
+        TransactionReceipt::Invoke(r) => r.events,
+        TransactionReceipt::L1Handler(r) => r.events,
+        TransactionReceipt::Declare(r) => r.events,
+        TransactionReceipt::Deploy(r) => r.events,
+        TransactionReceipt::DeployAccount(r) => r.events,
+    };
+
+    Ok(events
+        .into_iter()
+        .filter(|e| e.from_address == world_address)
+        .map(|e| EmittedEvent {
+            from_address: e.from_address,
+            keys: e.keys,
+            data: e.data,
+            block_hash,
+            block_number,
+            transaction_hash,
+        })
+        .collect())
+}
+
+/// The number of processed blocks whose hash is kept to detect the reorganizations of the chain.
+const FOLLOW_REORG_DEPTH: usize = 64;
+
+/// An event identified by its transaction hash and its position among the events of the
+/// transaction.
+type EventId = (Felt, usize);
+
+/// The state of `sozo events --follow` between two polls.
+#[derive(Debug, Default)]
+struct FollowState {
+    /// The first block whose events are not printed yet.
+    next_block: u64,
+    /// The hashes of the last processed blocks, by block number.
+    block_hashes: BTreeMap<u64, Felt>,
+    /// The events of the pending block already printed, with the number of their pending block.
+    pending_events: HashMap<EventId, u64>,
+}
+
+impl FollowState {
+    /// Removes an event printed from the pending block once its block is accepted, returning
+    /// true if it was already printed.
+    fn accept(&mut self, id: &EventId) -> bool {
+        self.pending_events.remove(id).is_some()
+    }
+
+    /// Records the events of the pending block, returning for each of them if it's not printed
+    /// yet.
+    ///
+    /// The events printed from a previous pending block and no longer in the pending block are
+    /// forgotten, their transaction being dropped or included in another block already fetched.
+    fn track_pending(&mut self, ids: &[EventId]) -> Vec<bool> {
+        let next_block = self.next_block;
+        let new =
+            ids.iter().map(|id| self.pending_events.insert(*id, next_block).is_none()).collect();
+
+        self.pending_events.retain(|_, block| *block >= next_block);
+        new
+    }
+}
+
+/// Returns the first block to replay if some of the processed blocks are no longer in the chain,
+/// `block_hash` returning the hash of a block of the chain or `None` if it's not found.
+async fn find_reorg<F, Fut>(
+    block_hashes: &BTreeMap<u64, Felt>,
+    mut block_hash: F,
+) -> Result<Option<u64>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<Option<Felt>>>,
+{
+    let mut fork_block = None;
+
+    for (&number, &hash) in block_hashes.iter().rev() {
+        if block_hash(number).await? == Some(hash) {
+            return Ok(fork_block.map(|_| number + 1));
+        }
+
+        fork_block = Some(number);
+    }
+
+    Ok(fork_block)
+}
+
+/// Returns the hash of a block of the chain, or `None` if it's not found.
+async fn block_hash<P: Provider + Send + Sync>(provider: &P, number: u64) -> Result<Option<Felt>> {
+    match provider.get_block_with_tx_hashes(BlockId::Number(number)).await {
+        Ok(MaybePendingBlockWithTxHashes::Block(block)) => Ok(Some(block.block_hash)),
+        Ok(_) | Err(ProviderError::StarknetError(StarknetError::BlockNotFound)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Identifies the events by their transaction hash and their position in the transaction.
+fn event_ids(events: &[EmittedEvent]) -> Vec<EventId> {
+    let mut counts: HashMap<Felt, usize> = HashMap::new();
+
+    events
+        .iter()
+        .map(|e| {
+            let count = counts.entry(e.transaction_hash).or_default();
+            let id = (e.transaction_hash, *count);
+            *count += 1;
+            id
+        })
+        .collect()
+}
+
+/// Decodes a world event and prints it.
+async fn print_event<P: Provider + Send + Sync>(
+    event: &EmittedEvent,
+    world_diff: &WorldDiff,
+    provider: &P,
+) -> Result<()> {
+    match world::Event::try_from(event) {
+        Ok(ev) => {
+            trace!(?ev, "Processing world event.");
+            match_event(&ev, world_diff, event.block_number, event.transaction_hash, provider)
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!(
+                ?e,
+                "Failed to parse remote world event which is supposed to be valid."
+            );
+        }
+    }
+
+    Ok(())
 }
 
 /// Matches the event and prints it's content.
@@ -423,3 +841,165 @@ async fn match_event<P: Provider + Send + Sync>(
 fn get_tag(selector: Felt, tags: &HashMap<&Felt, String>) -> String {
     tags.get(&selector).unwrap_or(&format!("external-{:#066x}", selector)).to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser, Debug)]
+    struct TestCommand {
+        #[command(flatten)]
+        args: EventsArgs,
+    }
+
+    #[test]
+    fn test_follow_argument_parsing() {
+        let cmd = TestCommand::parse_from(["events", "--follow", "--poll-interval", "500"]);
+        assert!(cmd.args.follow);
+        assert_eq!(cmd.args.poll_interval, 500);
+
+        assert!(TestCommand::try_parse_from(["events", "--follow", "--to-block", "10"]).is_err());
+        assert!(TestCommand::try_parse_from(["events", "--poll-interval", "500"]).is_err());
+    }
+
+    #[test]
+    fn test_event_ids() {
+        let event = |tx: u64| EmittedEvent {
+            from_address: Felt::ONE,
+            keys: vec![],
+            data: vec![],
+            block_hash: None,
+            block_number: None,
+            transaction_hash: Felt::from(tx),
+        };
+
+        let events = vec![event(1), event(1), event(2), event(1)];
+        assert_eq!(
+            event_ids(&events),
+            vec![(Felt::from(1), 0), (Felt::from(1), 1), (Felt::from(2), 0), (Felt::from(1), 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_reorg() {
+        let processed = BTreeMap::from([(10, Felt::from(10)), (11, Felt::from(11))]);
+
+        let chain = HashMap::from([(10, Felt::from(10)), (11, Felt::from(11))]);
+        let reorg = find_reorg(&processed, |n| std::future::ready(Ok(chain.get(&n).copied())));
+        assert_eq!(reorg.await.unwrap(), None);
+
+        // The block 11 is replaced.
+        let chain = HashMap::from([(10, Felt::from(10)), (11, Felt::from(111))]);
+        let reorg = find_reorg(&processed, |n| std::future::ready(Ok(chain.get(&n).copied())));
+        assert_eq!(reorg.await.unwrap(), Some(11));
+
+        // The chain is shorter than the processed blocks, and none of them is kept.
+        let chain = HashMap::from([(10, Felt::from(110))]);
+        let reorg = find_reorg(&processed, |n| std::future::ready(Ok(chain.get(&n).copied())));
+        assert_eq!(reorg.await.unwrap(), Some(10));
+
+        let reorg = find_reorg(&BTreeMap::new(), |_| std::future::ready(Ok(None)));
+        assert_eq!(reorg.await.unwrap(), None);
+
+        let reorg = find_reorg(&processed, |_| std::future::ready(Err(anyhow!("offline"))));
+        assert!(reorg.await.is_err());
+    }
+
+    #[test]
+    fn test_follow_state_pending_events() {
+        let (a, b, c) = ((Felt::ONE, 0), (Felt::TWO, 0), (Felt::THREE, 0));
+        let mut state = FollowState { next_block: 5, ..Default::default() };
+
+        assert_eq!(state.track_pending(&[a, b]), vec![true, true]);
+        assert_eq!(state.track_pending(&[a, b, c]), vec![false, false, true]);
+
+        // The block 5 is accepted with `a` and `c`, `b` being dropped.
+        assert!(state.accept(&a));
+        assert!(state.accept(&c));
+        state.next_block = 6;
+
+        assert_eq!(state.track_pending(&[]), Vec::<bool>::new());
+        assert!(state.pending_events.is_empty());
+        assert!(!state.accept(&b));
+
+        // An event still pending after its pending block is accepted isn't printed again.
+        assert_eq!(state.track_pending(&[a]), vec![true]);
+        state.next_block = 7;
+        assert_eq!(state.track_pending(&[a]), vec![false]);
+        assert_eq!(state.pending_events, HashMap::from([(a, 7)]));
+    }
+
+    #[test]
+    fn test_world_event_filter_rpc_keys() {
+        let set_record = starknet_keccak(b"StoreSetRecord");
+        let event_emitted = starknet_keccak(b"EventEmitted");
+
+        assert_eq!(WorldEventFilter::default().rpc_keys(), None);
+
+        let filter = WorldEventFilter {
+            models: vec![Felt::ONE],
+            keys: Some(vec![Felt::TWO.into(), Felt::THREE.into()]),
+            ..Default::default()
+        };
+        let keys = filter.rpc_keys().unwrap();
+        assert!(keys[0].contains(&set_record) && keys[0].len() == 4);
+        assert_eq!(keys[1], vec![Felt::ONE]);
+        assert_eq!(keys[2], vec![poseidon_hash_many(&[Felt::TWO, Felt::THREE])]);
+
+        let filter = WorldEventFilter { contracts: vec![Felt::TWO], ..Default::default() };
+        assert_eq!(filter.rpc_keys(), Some(vec![vec![event_emitted], vec![], vec![Felt::TWO]]));
+
+        // Mixed kinds of events only share the first key.
+        let filter = WorldEventFilter {
+            models: vec![Felt::ONE],
+            events: vec![Felt::TWO],
+            ..Default::default()
+        };
+        assert_eq!(filter.rpc_keys().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_world_event_filter_matches_keys() {
+        let event = |name: &str, keys: Vec<Felt>, data: Vec<Felt>| EmittedEvent {
+            from_address: Felt::ONE,
+            keys: [vec![starknet_keccak(name.as_bytes())], keys].concat(),
+            data,
+            block_hash: None,
+            block_number: None,
+            transaction_hash: Felt::ONE,
+        };
+
+        let mut filter = WorldEventFilter {
+            models: vec![Felt::ONE],
+            keys: Some(vec![Felt::TWO.into(), None]),
+            ..Default::default()
+        };
+
+        // keys: [0x2, 0x5], values: [0x7]
+        let set_record = |entity_id: Felt, first_key: Felt| {
+            event(
+                "StoreSetRecord",
+                vec![Felt::ONE, entity_id],
+                vec![Felt::TWO, first_key, Felt::from(5), Felt::ONE, Felt::from(7)],
+            )
+        };
+        let del_record =
+            |entity_id: Felt| event("StoreDelRecord", vec![Felt::ONE, entity_id], vec![]);
+
+        assert!(!filter.matches(&del_record(Felt::from(42))));
+        assert!(filter.matches(&set_record(Felt::from(42), Felt::TWO)));
+        assert!(filter.matches(&del_record(Felt::from(42))));
+        assert!(!filter.matches(&set_record(Felt::from(43), Felt::THREE)));
+
+        let emitted = event("EventEmitted", vec![Felt::ONE, Felt::TWO], vec![]);
+        assert!(!filter.matches(&emitted));
+    }
+
+    #[test]
+    fn test_parse_keys_pattern() {
+        assert_eq!(parse_keys_pattern("0x123,*").unwrap(), vec![Some(Felt::from(0x123)), None]);
+        assert!(parse_keys_pattern("0x123,abc").is_err());
+    }
+}
@@ -1,17 +1,23 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use anyhow::{anyhow, bail, Result};
 use clap::Args;
-use dojo_utils::{Invoker, TxnConfig};
+use dojo_utils::{Invoker, SignedOutsideExecution, TxnConfig};
 use dojo_world::config::calldata_decoder;
 use scarb::core::Config;
 use sozo_ops::resource_descriptor::ResourceDescriptor;
 use sozo_scarbext::WorkspaceExt;
 #[cfg(feature = "walnut")]
 use sozo_walnut::WalnutDebugger;
+use starknet::accounts::Account;
 use starknet::core::types::Call;
 use starknet::core::utils as snutils;
 use tracing::trace;
 
 use super::options::account::AccountOptions;
+use super::options::paymaster::PaymasterOptions;
 use super::options::starknet::StarknetOptions;
 use super::options::transaction::TransactionOptions;
 use super::options::world::WorldOptions;
@@ -21,7 +27,7 @@ use crate::utils::{self, CALLDATA_DOC};
 #[command(about = "Execute one or several systems with the given calldata.")]
 pub struct ExecuteArgs {
     #[arg(num_args = 1..)]
-    #[arg(required = true)]
+    #[arg(required_unless_present = "relay")]
     #[arg(help = format!("A list of calls to execute, separated by a /.
 
 A call is made up of a <TAG_OR_ADDRESS>, an <ENTRYPOINT> and an optional <CALLDATA>:
@@ -44,6 +50,13 @@ Executes the run function of the contract at the address 0x1234 without calldata
 and the move function of the ns-Actions contract, with the calldata [1,2]."))]
     pub calls: Vec<String>,
 
+    #[arg(long)]
+    #[arg(value_name = "PATH")]
+    #[arg(conflicts_with_all = ["calls", "output", "paymaster_private_key"])]
+    #[arg(help = "Relay the signed outside execution read from the given file. The account \
+                  acts as the paymaster and pays the fees.")]
+    pub relay: Option<PathBuf>,
+
     #[arg(long)]
     #[arg(help = "If true, sozo will compute the diff of the world from the chain to translate \
                   tags to addresses.")]
@@ -60,6 +73,9 @@ and the move function of the ns-Actions contract, with the calldata [1,2]."))]
 
     #[command(flatten)]
     pub transaction: TransactionOptions,
+
+    #[command(flatten)]
+    pub paymaster: PaymasterOptions,
 }
 
 impl ExecuteArgs {
@@ -97,6 +113,21 @@ impl ExecuteArgs {
 
             let mut invoker = Invoker::new(&account, txn_config);
 
+            if let Some(path) = &self.relay {
+                let outside_execution: SignedOutsideExecution =
+                    serde_json::from_str(&fs::read_to_string(path)?)?;
+
+                let tx_result = invoker.relay(&outside_execution).await?;
+
+                #[cfg(feature = "walnut")]
+                if let Some(walnut_debugger) = walnut_debugger {
+                    walnut_debugger.debug_transaction(&config.ui(), &tx_result)?;
+                }
+
+                println!("{}", tx_result);
+                return Ok(());
+            }
+
             let mut arg_iter = self.calls.into_iter();
 
             while let Some(arg) = arg_iter.next() {
@@ -169,7 +200,38 @@ impl ExecuteArgs {
                 });
             }
 
-            let tx_result = invoker.multicall().await?;
+            let tx_result = if self.paymaster.is_outside_execution() {
+                #[cfg(feature = "controller")]
+                if self.account.controller {
+                    bail!("Outside execution can't be signed with a Controller account.");
+                }
+
+                let signer = self.account.signer.signer(profile_config.env.as_ref(), false)?;
+                let signed = invoker
+                    .outside_execution(self.paymaster.caller(), self.paymaster.validity())
+                    .sign(&signer, account.address(), account.chain_id())
+                    .await?;
+
+                if let Some(output) = &self.paymaster.output {
+                    fs::write(output, serde_json::to_string_pretty(&signed)?)?;
+                    println!("Outside execution written to {}", output.display());
+                    return Ok(());
+                }
+
+                let (provider, _) = self.starknet.provider(profile_config.env.as_ref())?;
+                let paymaster = self
+                    .paymaster
+                    .paymaster(Arc::new(provider))
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow!("The paymaster private key is required to relay the outside \
+                                 execution.")
+                    })?;
+
+                Invoker::new(&paymaster, txn_config).relay(&signed).await?
+            } else {
+                invoker.multicall().await?
+            };
 
             #[cfg(feature = "walnut")]
             if let Some(walnut_debugger) = walnut_debugger {
@@ -0,0 +1,168 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use dojo_world::contracts::model::{ModelRPCReader, ModelReader};
+use dojo_world::contracts::WorldContractReader;
+use dojo_world::diff::{ResourceDiff, WorldDiff};
+use dojo_world::ResourceType;
+use scarb::core::Config;
+use sozo_ops::events::WorldEventFetcher;
+use sozo_ops::export::{ExportSink, NdjsonSink, SqliteSink, WorldExporter};
+use sozo_scarbext::WorkspaceExt;
+use starknet::core::types::{BlockId, BlockTag};
+use starknet::providers::Provider;
+use tracing::trace;
+
+use super::options::starknet::StarknetOptions;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// A SQLite database, with one table per model and per event.
+    Sqlite,
+    /// A directory of newline-delimited JSON files, one file per model and per event.
+    Ndjson,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[arg(help = "The SQLite database, or the directory of NDJSON files, to export to. An \
+                  existing export is resumed from its last exported block.")]
+    pub output: PathBuf,
+
+    #[arg(long, value_enum, default_value = "sqlite")]
+    #[arg(help = "The format of the export.")]
+    pub format: ExportFormat,
+
+    #[arg(short, long)]
+    #[arg(help = "Block number from where to export the events, when starting a new export.")]
+    pub from_block: Option<u64>,
+
+    #[arg(short, long)]
+    #[arg(help = "Block number until where to export the events. Defaults to the latest block.")]
+    pub to_block: Option<u64>,
+
+    #[arg(short, long)]
+    #[arg(help = "Number of events to fetch per page")]
+    #[arg(default_value_t = 100)]
+    pub chunk_size: u64,
+
+    #[arg(short, long)]
+    #[arg(help = "Maximum number of blocks fetched at once. A too high value will cause the \
+                  event fetching to fail.")]
+    #[arg(default_value_t = 200_000)]
+    pub max_block_range: u64,
+
+    #[command(flatten)]
+    pub world: WorldOptions,
+
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
+}
+
+impl ExportArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        config.tokio_handle().block_on(async {
+            let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+            let profile_config = ws.load_profile_config()?;
+
+            let (world_diff, provider, _) =
+                utils::get_world_diff_and_provider(self.starknet.clone(), self.world.clone(), &ws)
+                    .await?;
+
+            let (models, events) = resource_schemas(&world_diff, &provider).await?;
+
+            let sink: Box<dyn ExportSink> = match self.format {
+                ExportFormat::Sqlite => Box::new(SqliteSink::open(&self.output).await?),
+                ExportFormat::Ndjson => Box::new(NdjsonSink::open(&self.output)?),
+            };
+
+            let mut exporter = WorldExporter::new(sink, models, events).await?;
+
+            let from_block = match (exporter.last_block(), self.from_block) {
+                (Some(last_block), Some(_)) => {
+                    anyhow::bail!(
+                        "`{}` is already exported up to block {last_block}, it can only be \
+                         resumed from the next block.",
+                        self.output.display()
+                    );
+                }
+                (Some(last_block), None) => last_block + 1,
+                (None, Some(from_block)) => from_block,
+                (None, None) => {
+                    profile_config.env.as_ref().and_then(|e| e.world_block).unwrap_or(0)
+                }
+            };
+            let to_block = match self.to_block {
+                Some(to_block) => to_block,
+                None => provider.block_number().await?,
+            };
+
+            if from_block > to_block {
+                println!("{}", "The export is up to date.".bright_black());
+                return Ok(());
+            }
+
+            let fetcher = WorldEventFetcher::new(
+                &provider,
+                world_diff.world_info.address,
+                self.chunk_size,
+                self.max_block_range,
+            )
+            .await?;
+
+            let events = fetcher.fetch_range(None, from_block, to_block).await?;
+
+            trace!(events_count = events.len(), from_block, to_block, "Fetched world events.");
+
+            exporter.export(&events, to_block).await?;
+
+            println!(
+                "Exported {} world events from blocks {from_block} to {to_block} to `{}`.",
+                events.len(),
+                self.output.display()
+            );
+
+            Ok(())
+        })
+    }
+}
+
+/// The tags and schemas of a set of resources.
+type ResourceSchemas = Vec<(String, dojo_types::schema::Ty)>;
+
+/// Returns the tags and schemas of the models and of the events registered in the world.
+async fn resource_schemas<P: Provider + Send + Sync>(
+    world_diff: &WorldDiff,
+    provider: &P,
+) -> Result<(ResourceSchemas, ResourceSchemas)> {
+    let mut world_reader = WorldContractReader::new(world_diff.world_info.address, provider);
+    world_reader.set_block(BlockId::Tag(BlockTag::Pending));
+
+    let mut models = vec![];
+    let mut events = vec![];
+
+    for resource in world_diff.resources.values() {
+        // Only the resources registered in the world can be exported.
+        if let ResourceDiff::Created(_) = resource {
+            continue;
+        }
+
+        let schemas = match resource.resource_type() {
+            ResourceType::Model => &mut models,
+            ResourceType::Event => &mut events,
+            _ => continue,
+        };
+
+        let reader =
+            ModelRPCReader::new_from_world(&resource.namespace(), &resource.name(), &world_reader)
+                .await?;
+
+        schemas.push((resource.tag(), reader.schema().await?));
+    }
+
+    Ok((models, events))
+}
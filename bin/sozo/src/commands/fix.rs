@@ -0,0 +1,49 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use scarb::core::Config;
+use sozo_ops::fix;
+
+#[derive(Debug, Args)]
+pub struct FixArgs {
+    #[arg(long)]
+    #[arg(help = "Only list the files to fix, without rewriting them.")]
+    pub dry_run: bool,
+}
+
+impl FixArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+
+        // The targets of a package usually share the same source root.
+        let sources = ws
+            .members()
+            .flat_map(|p| {
+                p.manifest.targets.iter().map(|t| t.source_root().to_path_buf()).collect::<Vec<_>>()
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let fixed = fix::fix_sources(&sources, self.dry_run)?;
+
+        if fixed.is_empty() {
+            println!("No legacy macro to rewrite.");
+            return Ok(());
+        }
+
+        let action = if self.dry_run { "To fix" } else { "Fixed" };
+
+        for file in &fixed {
+            println!(
+                "{} {} ({} rewrites)",
+                action.bright_green(),
+                file.path.display(),
+                file.rewrites
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -21,6 +21,10 @@ pub struct InspectArgs {
                   not provided, a world summary will be displayed.")]
     element: Option<String>,
 
+    #[arg(long)]
+    #[arg(help = "Inspect the remote world as of this block instead of the latest block.")]
+    at_block: Option<u64>,
+
     #[command(flatten)]
     world: WorldOptions,
 
@@ -33,11 +37,16 @@ impl InspectArgs {
         trace!(args = ?self);
         let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
 
-        let InspectArgs { world, starknet, element } = self;
+        let InspectArgs { world, starknet, element, at_block } = self;
 
         config.tokio_handle().block_on(async {
             let (world_diff, _, _) =
-                utils::get_world_diff_and_provider(starknet.clone(), world, &ws).await?;
+                utils::get_world_diff_and_provider_at_block(starknet.clone(), world, &ws, at_block)
+                    .await?;
+
+            if let Some(at_block) = at_block {
+                println!("{}", format!("Remote world as of block {at_block}.").bright_black());
+            }
 
             if let Some(element) = element {
                 inspect_element(&element, &world_diff)?;
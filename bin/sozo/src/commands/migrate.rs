@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{anyhow, Context, Result};
 use clap::Args;
@@ -7,7 +8,8 @@ use dojo_utils::{self, provider as provider_utils, TxnConfig};
 use dojo_world::contracts::WorldContract;
 use dojo_world::services::IpfsService;
 use scarb::core::{Config, Workspace};
-use sozo_ops::migrate::{Migration, MigrationResult};
+use sozo_ops::migrate::fee_report::{format_fee, FeeReportJson};
+use sozo_ops::migrate::{FeeReport, Migration, MigrationError, MigrationResult};
 use sozo_ops::migration_ui::MigrationUi;
 use sozo_scarbext::WorkspaceExt;
 use starknet::core::utils::parse_cairo_short_string;
@@ -22,7 +24,7 @@ use super::options::starknet::StarknetOptions;
 use super::options::transaction::TransactionOptions;
 use super::options::world::WorldOptions;
 use crate::commands::LOG_TARGET;
-use crate::utils;
+use crate::{metrics, utils};
 
 #[derive(Debug, Clone, Args)]
 pub struct MigrateArgs {
@@ -40,6 +42,14 @@ pub struct MigrateArgs {
 
     #[command(flatten)]
     pub ipfs: IpfsOptions,
+
+    #[arg(long)]
+    #[arg(help = "Save the fee report of the migration as JSON, next to the manifest.")]
+    pub fee_report: bool,
+
+    #[arg(long)]
+    #[arg(help = "Apply the [[seed]] blocks of the profile config after the migration.")]
+    pub seed: bool,
 }
 
 impl MigrateArgs {
@@ -51,11 +61,18 @@ impl MigrateArgs {
         ws.profile_check()?;
         ws.ensure_profile_artifacts()?;
 
-        let MigrateArgs { world, starknet, account, ipfs, .. } = self;
+        let MigrateArgs {
+            world, starknet, account, ipfs, fee_report: save_fee_report, seed, ..
+        } = self;
 
         config.tokio_handle().block_on(async {
             print_banner(&ws, &starknet).await?;
 
+            let profile_config = ws.load_profile_config()?;
+            let contracts_dependencies =
+                utils::contracts_dependencies(&ws, &ws.load_world_local()?)?;
+            utils::print_writer_warnings(&contracts_dependencies, &profile_config);
+
             let mut spinner = MigrationUi::new(Some("Evaluating world diff..."));
 
             let is_guest = world.guest;
@@ -69,8 +86,9 @@ impl MigrateArgs {
             )
             .await?;
 
+            metrics::record_world_diff(&world_diff);
+
             let world_address = world_diff.world_info.address;
-            let profile_config = ws.load_profile_config()?;
 
             let mut txn_config: TxnConfig = self.transaction.try_into()?;
             txn_config.wait = true;
@@ -84,8 +102,22 @@ impl MigrateArgs {
                 is_guest,
             );
 
-            let MigrationResult { manifest, has_changes } =
-                migration.migrate(&mut spinner).await.context("Migration failed.")?;
+            let start = Instant::now();
+
+            let MigrationResult { mut manifest, has_changes, mut fee_report } =
+                match migration.migrate(&mut spinner).await {
+                    Ok(result) => result,
+                    Err(e) => {
+                        let transaction_failed = matches!(
+                            e,
+                            MigrationError::TransactionError(_)
+                                | MigrationError::DeclareClassError(_)
+                        );
+                        metrics::record_migration_failure(start.elapsed(), transaction_failed);
+
+                        return Err(anyhow!(e).context("Migration failed."));
+                    }
+                };
 
             let ipfs_config =
                 ipfs.config().or(profile_config.env.map(|env| env.ipfs_config).unwrap_or(None));
@@ -93,10 +125,12 @@ impl MigrateArgs {
             if let Some(config) = ipfs_config {
                 let mut metadata_service = IpfsService::new(config)?;
 
-                migration
+                let metadata_fee_report = migration
                     .upload_metadata(&mut spinner, &mut metadata_service)
                     .await
                     .context("Metadata upload failed.")?;
+
+                fee_report.extend(metadata_fee_report);
             } else {
                 println!();
                 println!(
@@ -105,9 +139,30 @@ impl MigrateArgs {
                 );
             };
 
+            let seeds_applied = if seed {
+                let (seeds_applied, seed_fee_report) =
+                    migration.seed(&mut spinner).await.context("Seeding failed.")?;
+
+                fee_report.extend(seed_fee_report);
+                seeds_applied
+            } else {
+                0
+            };
+
+            metrics::record_migration(start.elapsed(), &fee_report);
+
             spinner.update_text("Writing manifest...");
+            for contract in contracts_dependencies {
+                manifest.set_contract_dependencies(&contract.tag, contract.dependencies);
+            }
             ws.write_manifest_profile(manifest).context("🪦 Failed to write manifest.")?;
 
+            if save_fee_report {
+                spinner.update_text("Writing fee report...");
+                ws.write_fee_report_profile(FeeReportJson::from(&fee_report))
+                    .context("🪦 Failed to write fee report.")?;
+            }
+
             let colored_address = format!("{:#066x}", world_address).green();
 
             let (symbol, end_text) = if has_changes {
@@ -130,11 +185,70 @@ impl MigrateArgs {
 
             spinner.stop_and_persist_boxed(symbol, end_text);
 
+            if seed {
+                println!("{seeds_applied} seeds applied.");
+            }
+
+            print_fee_report(&fee_report);
+
             Ok(())
         })
     }
 }
 
+#[derive(Debug, Tabled)]
+pub struct FeeReportRow {
+    #[tabled(rename = "Step")]
+    pub step: String,
+    #[tabled(rename = "Txs")]
+    pub transactions: usize,
+    #[tabled(rename = "L1 gas")]
+    pub l1_gas: u64,
+    #[tabled(rename = "L1 data gas")]
+    pub l1_data_gas: u64,
+    #[tabled(rename = "L2 gas")]
+    pub l2_gas: u64,
+    #[tabled(rename = "Fee (STRK)")]
+    pub fee_strk: String,
+}
+
+/// Prints the fees paid per step of the migration, and the totals.
+fn print_fee_report(fee_report: &FeeReport) {
+    if fee_report.is_empty() {
+        return;
+    }
+
+    let mut rows = fee_report
+        .steps()
+        .into_iter()
+        .map(|(step, totals)| FeeReportRow {
+            step: step.to_string(),
+            transactions: totals.transactions,
+            l1_gas: totals.l1_gas,
+            l1_data_gas: totals.l1_data_gas,
+            l2_gas: totals.l2_gas,
+            fee_strk: totals.fee_strk(),
+        })
+        .collect::<Vec<_>>();
+
+    let totals = fee_report.totals();
+    rows.push(FeeReportRow {
+        step: "total".bold().to_string(),
+        transactions: totals.transactions,
+        l1_gas: totals.l1_gas,
+        l1_data_gas: totals.l1_data_gas,
+        l2_gas: totals.l2_gas,
+        fee_strk: totals.fee_strk().bold().to_string(),
+    });
+
+    println!();
+    println!("{}", Table::new(rows).with(Style::psql()));
+
+    if totals.fee_wei > 0 {
+        println!("Fees paid in ETH (legacy transactions): {} ETH", format_fee(totals.fee_wei));
+    }
+}
+
 #[derive(Debug, Tabled)]
 pub struct Banner {
     pub profile: String,
@@ -13,29 +13,39 @@ pub(crate) mod build;
 pub(crate) mod call;
 pub(crate) mod clean;
 pub(crate) mod dev;
+pub(crate) mod doctor;
 pub(crate) mod events;
 pub(crate) mod execute;
+pub(crate) mod export;
+pub(crate) mod fix;
 pub(crate) mod hash;
 pub(crate) mod init;
 pub(crate) mod inspect;
 pub(crate) mod migrate;
 pub(crate) mod model;
 pub(crate) mod options;
+pub(crate) mod run;
 pub(crate) mod test;
+pub(crate) mod world;
 
 use build::BuildArgs;
 use call::CallArgs;
 use clean::CleanArgs;
 use dev::DevArgs;
+use doctor::DoctorArgs;
 use execute::ExecuteArgs;
+use export::ExportArgs;
+use fix::FixArgs;
 use hash::HashArgs;
 use init::InitArgs;
 use inspect::InspectArgs;
 use migrate::MigrateArgs;
 use model::ModelArgs;
+use run::RunArgs;
 #[cfg(feature = "walnut")]
 use sozo_walnut::walnut::WalnutArgs;
 use test::TestArgs;
+use world::WorldArgs;
 
 pub(crate) const LOG_TARGET: &str = "sozo::cli";
 
@@ -56,6 +66,8 @@ pub enum Commands {
     Inspect(Box<InspectArgs>),
     #[command(about = "Clean the build directory")]
     Clean(Box<CleanArgs>),
+    #[command(about = "Rewrite the removed world macros to the WorldStorage API")]
+    Fix(Box<FixArgs>),
     #[command(about = "Call a contract")]
     Call(Box<CallArgs>),
     #[command(about = "Runs cairo tests")]
@@ -68,6 +80,14 @@ pub enum Commands {
     Model(Box<ModelArgs>),
     #[command(about = "Inspect events emitted by the world")]
     Events(Box<EventsArgs>),
+    #[command(about = "Export the world events and the model records for offline analysis")]
+    Export(Box<ExportArgs>),
+    #[command(about = "Inspect the changes of the world over time")]
+    World(Box<WorldArgs>),
+    #[command(about = "Diagnose the project, the provider, the world and the account")]
+    Doctor(Box<DoctorArgs>),
+    #[command(about = "Run a scenario of steps against the world")]
+    Run(Box<RunArgs>),
     #[cfg(feature = "walnut")]
     #[command(about = "Interact with walnut.dev - transactions debugger and simulator")]
     Walnut(Box<WalnutArgs>),
@@ -79,6 +99,7 @@ impl fmt::Display for Commands {
             Commands::Auth(_) => write!(f, "Auth"),
             Commands::Build(_) => write!(f, "Build"),
             Commands::Clean(_) => write!(f, "Clean"),
+            Commands::Fix(_) => write!(f, "Fix"),
             Commands::Dev(_) => write!(f, "Dev"),
             Commands::Execute(_) => write!(f, "Execute"),
             Commands::Inspect(_) => write!(f, "Inspect"),
@@ -89,6 +110,10 @@ impl fmt::Display for Commands {
             Commands::Init(_) => write!(f, "Init"),
             Commands::Model(_) => write!(f, "Model"),
             Commands::Events(_) => write!(f, "Events"),
+            Commands::Export(_) => write!(f, "Export"),
+            Commands::World(_) => write!(f, "World"),
+            Commands::Doctor(_) => write!(f, "Doctor"),
+            Commands::Run(_) => write!(f, "Run"),
             #[cfg(feature = "walnut")]
             Commands::Walnut(_) => write!(f, "WalnutVerify"),
         }
@@ -111,12 +136,17 @@ pub fn run(command: Commands, config: &Config) -> Result<()> {
         Commands::Execute(args) => args.run(config),
         Commands::Inspect(args) => args.run(config),
         Commands::Clean(args) => args.run(config),
+        Commands::Fix(args) => args.run(config),
         Commands::Call(args) => args.run(config),
         Commands::Test(args) => args.run(config),
         Commands::Hash(args) => args.run(config).map(|_| ()),
         Commands::Init(args) => args.run(config),
         Commands::Model(args) => args.run(config),
         Commands::Events(args) => args.run(config),
+        Commands::Export(args) => args.run(config),
+        Commands::World(args) => args.run(config),
+        Commands::Doctor(args) => args.run(config),
+        Commands::Run(args) => args.run(config),
         #[cfg(feature = "walnut")]
         Commands::Walnut(args) => args.run(config),
     }
@@ -1,17 +1,26 @@
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
+use colored::Colorize;
 use dojo_world::config::calldata_decoder;
+use dojo_world::contracts::{ContractInfo, WorldContract};
 use scarb::core::Config;
-use sozo_ops::model;
+use sozo_ops::model_blame::{RecordBlame, RecordWrite};
 use sozo_ops::resource_descriptor::ResourceDescriptor;
+use sozo_ops::{model, model_blame, model_migration};
 use sozo_scarbext::WorkspaceExt;
 use starknet::core::types::{BlockId, BlockTag, Felt};
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
 use tracing::trace;
 
+use super::options::account::AccountOptions;
 use super::options::starknet::StarknetOptions;
+use super::options::transaction::TransactionOptions;
 use super::options::world::WorldOptions;
 use crate::utils;
-use crate::utils::CALLDATA_DOC;
+use crate::utils::{CALLDATA_DOC, MAX_BLOCK_RANGE};
 
 #[derive(Debug, Args)]
 pub struct ModelArgs {
@@ -127,6 +136,96 @@ hashes, called 'hash' in the following documentation.
         #[arg(help = "Block number at which to retrieve the model data (pending block by default)")]
         block: Option<u64>,
     },
+
+    #[command(about = "List the storage slots occupied by a model record.\n
+The slots are computed with the same scheme as described in `sozo model layout --help`, the
+lengths of the arrays and the variants of the enums being read from the world storage.")]
+    StorageSlots {
+        #[arg(help = "The tag or name of the model")]
+        tag_or_name: ResourceDescriptor,
+
+        #[arg(value_name = "KEYS")]
+        #[arg(num_args = 1..)]
+        #[arg(required = true)]
+        #[arg(
+            help = format!("List of values representing the serialized keys of the model.\n{CALLDATA_DOC}")
+        )]
+        keys: Vec<String>,
+
+        #[arg(long)]
+        #[arg(help = "Display the raw value stored in each slot.")]
+        values: bool,
+
+        #[command(flatten)]
+        world: WorldOptions,
+
+        #[command(flatten)]
+        starknet: StarknetOptions,
+
+        #[arg(short, long)]
+        #[arg(help = "Block number at which to read the storage (pending block by default)")]
+        block: Option<u64>,
+    },
+
+    #[command(about = "Show the lineage of a model record.\n
+Every write of the record is attributed to its transaction, to the account which sent it and
+to the Dojo systems it called. The last write of each member is displayed first, then the full
+history of the record.")]
+    Blame {
+        #[arg(help = "The tag or name of the model")]
+        tag_or_name: ResourceDescriptor,
+
+        #[arg(value_name = "KEYS")]
+        #[arg(num_args = 1..)]
+        #[arg(required = true)]
+        #[arg(
+            help = format!("List of values representing the serialized keys of the model.\n{CALLDATA_DOC}")
+        )]
+        keys: Vec<String>,
+
+        #[arg(long)]
+        #[arg(help = "Block number from where to look for the writes of the record. Defaults to \
+                      the `world_block` of the profile configuration.")]
+        from_block: Option<u64>,
+
+        #[command(flatten)]
+        world: WorldOptions,
+
+        #[command(flatten)]
+        starknet: StarknetOptions,
+    },
+
+    #[command(about = "Migrate the records of a versioned model after its upgrade.\n
+The records written before the last upgrade of the model are read with the layout of the
+version they were written with, transformed by the `ModelMigration` implementation of the model
+and written back in batched transactions. An interrupted migration is resumed when the command is
+run again, from the progress saved into `target/model_migrations/<PROFILE>`.")]
+    Migrate {
+        #[arg(help = "The tag or name of the model")]
+        tag_or_name: ResourceDescriptor,
+
+        #[arg(long)]
+        #[arg(default_value_t = 50)]
+        #[arg(help = "The number of records migrated in each transaction.")]
+        batch_size: usize,
+
+        #[arg(long)]
+        #[arg(help = "Block number from where to look for the records of the model. Defaults to \
+                      the `world_block` of the profile configuration.")]
+        from_block: Option<u64>,
+
+        #[command(flatten)]
+        world: WorldOptions,
+
+        #[command(flatten)]
+        starknet: StarknetOptions,
+
+        #[command(flatten)]
+        account: AccountOptions,
+
+        #[command(flatten)]
+        transaction: TransactionOptions,
+    },
 }
 
 impl ModelArgs {
@@ -221,6 +320,107 @@ impl ModelArgs {
 
                     println!("{}", record);
 
+                    Ok(())
+                }
+                ModelCommand::StorageSlots {
+                    tag_or_name,
+                    keys,
+                    values,
+                    block,
+                    starknet,
+                    world,
+                } => {
+                    let tag = tag_or_name.ensure_namespace(&default_ns);
+                    let block_id =
+                        block.map(BlockId::Number).unwrap_or(BlockId::Tag(BlockTag::Pending));
+
+                    let (world_diff, provider, _) =
+                        utils::get_world_diff_and_provider(starknet, world, &ws).await?;
+
+                    model::model_storage_slots(
+                        tag.to_string(),
+                        parse_keys(&keys)?,
+                        world_diff.world_info.address,
+                        &provider,
+                        block_id,
+                        values,
+                    )
+                    .await?;
+
+                    Ok(())
+                }
+                ModelCommand::Blame { tag_or_name, keys, from_block, world, starknet } => {
+                    let tag = tag_or_name.ensure_namespace(&default_ns);
+                    let env = profile_config.env.as_ref();
+
+                    let from_block =
+                        from_block.or(env.and_then(|e| e.world_block)).unwrap_or_default();
+                    let max_block_range =
+                        env.and_then(|e| e.max_block_range).unwrap_or(MAX_BLOCK_RANGE);
+
+                    let (world_diff, provider, _) =
+                        utils::get_world_diff_and_provider(starknet, world, &ws).await?;
+                    let contracts: HashMap<String, ContractInfo> = (&world_diff).into();
+
+                    let blame = model_blame::model_blame(
+                        &tag.to_string(),
+                        &parse_keys(&keys)?,
+                        world_diff.world_info.address,
+                        &provider,
+                        &contracts,
+                        from_block,
+                        max_block_range,
+                    )
+                    .await?;
+
+                    print_blame(&tag.to_string(), &blame);
+
+                    Ok(())
+                }
+                ModelCommand::Migrate {
+                    tag_or_name,
+                    batch_size,
+                    from_block,
+                    world,
+                    starknet,
+                    account,
+                    transaction,
+                } => {
+                    let tag = tag_or_name.ensure_namespace(&default_ns);
+                    let env = profile_config.env.as_ref();
+
+                    let from_block =
+                        from_block.or(env.and_then(|e| e.world_block)).unwrap_or_default();
+                    let max_block_range =
+                        env.and_then(|e| e.max_block_range).unwrap_or(MAX_BLOCK_RANGE);
+
+                    let (world_diff, account, _) =
+                        utils::get_world_diff_and_account(account, starknet, world, &ws, &mut None)
+                            .await?;
+                    let world = WorldContract::new(world_diff.world_info.address, &account);
+
+                    // Under the target directory of the profile, but outside of the artifacts
+                    // directory which is cleaned by the build.
+                    let progress_path = ws
+                        .target_dir()
+                        .path_unchecked()
+                        .join("model_migrations")
+                        .join(ws.current_profile()?.as_str())
+                        .join(format!("{tag}.json"));
+
+                    let migrated = model_migration::migrate_model_records(
+                        &tag.to_string(),
+                        &world,
+                        transaction.try_into()?,
+                        from_block,
+                        max_block_range,
+                        batch_size,
+                        progress_path.as_std_path(),
+                    )
+                    .await?;
+
+                    println!("{migrated} records of {tag} migrated.");
+
                     Ok(())
                 }
             }
@@ -228,6 +428,92 @@ impl ModelArgs {
     }
 }
 
+#[derive(Debug, Tabled)]
+struct MemberBlameDisplay {
+    #[tabled(rename = "Member")]
+    member: String,
+    #[tabled(rename = "Value")]
+    value: String,
+    #[tabled(rename = "Block")]
+    block: String,
+    #[tabled(rename = "Transaction")]
+    transaction: String,
+    #[tabled(rename = "Sender")]
+    sender: String,
+    #[tabled(rename = "Systems")]
+    systems: String,
+}
+
+/// Prints the last write of each member of a record, then the history of its writes.
+fn print_blame(tag: &str, blame: &RecordBlame) {
+    if blame.writes.is_empty() {
+        println!("No write found for this record of {tag}.");
+        return;
+    }
+
+    let origin = |write: &RecordWrite| {
+        let origin = blame.origins.get(&write.transaction_hash);
+        let sender = origin
+            .and_then(|o| o.sender)
+            .map(|s| format!("{:#066x}", s))
+            .unwrap_or_else(|| "-".to_string());
+        let systems = origin.map(|o| o.calls.join(", ")).unwrap_or_default();
+
+        (sender, systems)
+    };
+
+    let block = |write: &RecordWrite| {
+        write.block_number.map(|b| b.to_string()).unwrap_or_else(|| "pending".to_string())
+    };
+
+    let rows = blame.last_writes().into_iter().map(|(member, last)| match last {
+        Some((write, value)) => {
+            let (sender, systems) = origin(write);
+
+            MemberBlameDisplay {
+                member: member.to_string(),
+                value: value.clone().unwrap_or_else(|| "(deleted)".to_string()),
+                block: block(write),
+                transaction: format!("{:#066x}", write.transaction_hash),
+                sender,
+                systems,
+            }
+        }
+        None => MemberBlameDisplay {
+            member: member.to_string(),
+            value: "(never written)".to_string(),
+            block: "-".to_string(),
+            transaction: "-".to_string(),
+            sender: "-".to_string(),
+            systems: "-".to_string(),
+        },
+    });
+
+    let mut table = Table::new(rows);
+    table.with(Style::psql());
+    println!("{table}\n");
+
+    let history = format!("History of the record ({} writes):", blame.writes.len());
+    println!("{}", history.bright_black());
+
+    for write in &blame.writes {
+        let (sender, systems) = origin(write);
+        let members = write
+            .members
+            .iter()
+            .map(|(m, v)| format!("{m}: {}", v.as_deref().unwrap_or("(deleted)")))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        println!(
+            "block {} {:#066x} {:?} by {sender} via {systems}\n    {members}",
+            block(write),
+            write.transaction_hash,
+            write.kind,
+        );
+    }
+}
+
 /// Parses the keys from the command line into a vector of Felt representing the serialized keys of
 /// the model.
 fn parse_keys(keys: &[String]) -> Result<Vec<Felt>> {
@@ -259,6 +545,64 @@ mod tests {
         command: ModelCommand,
     }
 
+    #[test]
+    fn test_model_storage_slots_argument_parsing() {
+        let args = TestCommand::parse_from([
+            "model",
+            "storage-slots",
+            "ns-Position",
+            "0x1",
+            "u256:0x2",
+            "--values",
+            "--block",
+            "10",
+        ]);
+
+        if let ModelCommand::StorageSlots { tag_or_name, keys, values, block, .. } = args.command {
+            assert_eq!(tag_or_name.to_string(), "ns-Position");
+            assert_eq!(parse_keys(&keys).unwrap(), vec![Felt::ONE, Felt::TWO, Felt::ZERO]);
+            assert!(values);
+            assert_eq!(block, Some(10));
+        } else {
+            panic!("Expected StorageSlots command");
+        }
+    }
+
+    #[test]
+    fn test_model_blame_argument_parsing() {
+        let args = TestCommand::parse_from([
+            "model",
+            "blame",
+            "ns-Position",
+            "0x1",
+            "0x2",
+            "--from-block",
+            "5",
+        ]);
+
+        if let ModelCommand::Blame { tag_or_name, keys, from_block, .. } = args.command {
+            assert_eq!(tag_or_name.to_string(), "ns-Position");
+            assert_eq!(parse_keys(&keys).unwrap(), vec![Felt::ONE, Felt::TWO]);
+            assert_eq!(from_block, Some(5));
+        } else {
+            panic!("Expected Blame command");
+        }
+    }
+
+    #[test]
+    fn test_model_migrate_argument_parsing() {
+        let args =
+            TestCommand::parse_from(["model", "migrate", "ns-Position", "--batch-size", "10"]);
+
+        if let ModelCommand::Migrate { tag_or_name, batch_size, from_block, .. } = args.command {
+            assert_eq!(tag_or_name.to_string(), "ns-Position");
+            assert_eq!(batch_size, 10);
+            assert_eq!(from_block, None);
+        } else {
+            panic!("Expected Migrate command");
+        }
+    }
+
     #[test]
     fn test_model_get_argument_parsing() {
         // Test parsing with hex
@@ -0,0 +1,70 @@
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use clap::Args;
+use dojo_metrics::exporters::prometheus::PrometheusRecorder;
+use dojo_metrics::Server;
+use tokio::runtime::Handle;
+use tracing::{error, info};
+
+use crate::metrics;
+
+#[derive(Debug, Default, Args, Clone)]
+#[command(next_help_heading = "Metrics options")]
+pub struct MetricsOptions {
+    #[arg(long = "metrics")]
+    #[arg(value_name = "ADDRESS")]
+    #[arg(help = "Enable the Prometheus metrics endpoint at the given address (e.g. \
+                  127.0.0.1:9200).")]
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+impl MetricsOptions {
+    /// Installs the Prometheus recorder and starts the metrics server in the background,
+    /// if the metrics are enabled.
+    pub fn start(&self, runtime: &Handle) -> Result<()> {
+        let Some(addr) = self.metrics_addr else {
+            return Ok(());
+        };
+
+        let prometheus_handle = PrometheusRecorder::install("sozo")?;
+        metrics::describe();
+
+        let server = Server::new(prometheus_handle).with_process_metrics();
+
+        runtime.spawn(async move {
+            if let Err(error) = server.start(addr).await {
+                error!(?error, "Metrics server stopped.");
+            }
+        });
+
+        info!(%addr, "Metrics server started.");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::MetricsOptions;
+
+    #[derive(clap::Parser)]
+    struct Command {
+        #[clap(flatten)]
+        options: MetricsOptions,
+    }
+
+    #[test]
+    fn metrics_disabled_by_default() {
+        let cmd = Command::parse_from([""]);
+        assert!(cmd.options.metrics_addr.is_none());
+    }
+
+    #[test]
+    fn metrics_address_from_args() {
+        let cmd = Command::parse_from(["sozo", "--metrics", "127.0.0.1:9200"]);
+        assert_eq!(cmd.options.metrics_addr, Some("127.0.0.1:9200".parse().unwrap()));
+    }
+}
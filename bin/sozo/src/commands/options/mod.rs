@@ -1,5 +1,7 @@
 pub mod account;
 pub mod ipfs;
+pub mod metrics;
+pub mod paymaster;
 pub mod signer;
 pub mod starknet;
 pub mod transaction;
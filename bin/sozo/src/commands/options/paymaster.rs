@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use clap::Args;
+use dojo_utils::env::{DOJO_PAYMASTER_ADDRESS_ENV_VAR, DOJO_PAYMASTER_PRIVATE_KEY_ENV_VAR};
+use dojo_utils::ANY_CALLER;
+use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::providers::Provider;
+use starknet::signers::{LocalWallet, SigningKey};
+use tracing::trace;
+
+/// Options to have the calls sponsored by a relayer account, using SNIP-9 outside execution.
+///
+/// The account configured with the account options signs the calls, and the paymaster
+/// account submits them and pays the fees.
+#[derive(Debug, Args, Clone, Default)]
+#[command(next_help_heading = "Paymaster options")]
+pub struct PaymasterOptions {
+    #[arg(long = "paymaster.address", env = DOJO_PAYMASTER_ADDRESS_ENV_VAR)]
+    #[arg(value_name = "ADDRESS")]
+    #[arg(help = "The address of the paymaster account relaying the outside execution. It is \
+                  only used with either the paymaster private key to relay it, or an output \
+                  file in which case the paymaster is only used as the allowed caller.")]
+    pub paymaster_address: Option<Felt>,
+
+    #[arg(long = "paymaster.private-key", env = DOJO_PAYMASTER_PRIVATE_KEY_ENV_VAR)]
+    #[arg(value_name = "PRIVATE_KEY")]
+    #[arg(help = "The private key of the paymaster account. If set, the signed outside \
+                  execution is directly relayed by the paymaster account.")]
+    pub paymaster_private_key: Option<String>,
+
+    #[arg(long = "outside-execution.output")]
+    #[arg(value_name = "PATH")]
+    #[arg(conflicts_with = "paymaster_private_key")]
+    #[arg(help = "Sign the calls as an outside execution and write the payload to the given \
+                  file instead of sending a transaction. The payload can then be relayed with \
+                  `sozo execute --relay <PATH>`.")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long = "outside-execution.validity")]
+    #[arg(value_name = "SECONDS")]
+    #[arg(default_value_t = dojo_utils::DEFAULT_VALIDITY.as_secs())]
+    #[arg(help = "How long the signed outside execution remains valid, in seconds.")]
+    pub validity: u64,
+}
+
+impl PaymasterOptions {
+    /// Returns true if the calls have to be signed as an outside execution instead of being
+    /// sent by the account.
+    pub fn is_outside_execution(&self) -> bool {
+        self.paymaster_private_key.is_some() || self.output.is_some()
+    }
+
+    /// The only address allowed to submit the outside execution.
+    pub fn caller(&self) -> Felt {
+        self.paymaster_address.unwrap_or(ANY_CALLER)
+    }
+
+    pub fn validity(&self) -> Duration {
+        Duration::from_secs(self.validity)
+    }
+
+    /// Creates the paymaster account, if the paymaster private key is provided.
+    ///
+    /// The address and the private key may come from the environment, hence they are checked
+    /// together here rather than when parsing the arguments.
+    pub async fn paymaster<P>(
+        &self,
+        provider: Arc<P>,
+    ) -> Result<Option<SingleOwnerAccount<Arc<P>, LocalWallet>>>
+    where
+        P: Provider + Send + Sync,
+    {
+        let Some(private_key) = self.paymaster_private_key.as_ref() else {
+            return Ok(None);
+        };
+
+        let Some(address) = self.paymaster_address else {
+            bail!("The paymaster address is required to relay the outside execution.");
+        };
+
+        let signer =
+            LocalWallet::from_signing_key(SigningKey::from_secret_scalar(Felt::from_str(
+                private_key,
+            )?));
+
+        let chain_id = provider.chain_id().await?;
+        trace!(address = format!("{:#066x}", address), "Creating paymaster account.");
+
+        let mut account =
+            SingleOwnerAccount::new(provider, signer, address, chain_id, ExecutionEncoding::New);
+        account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+        Ok(Some(account))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clap::Parser;
+    use starknet::core::types::Felt;
+    use starknet::providers::jsonrpc::HttpTransport;
+    use starknet::providers::JsonRpcClient;
+    use url::Url;
+
+    use super::{PaymasterOptions, ANY_CALLER};
+
+    #[derive(clap::Parser)]
+    struct Command {
+        #[clap(flatten)]
+        options: PaymasterOptions,
+    }
+
+    #[test]
+    fn no_paymaster_by_default() {
+        let cmd = Command::parse_from([""]);
+        assert!(!cmd.options.is_outside_execution());
+        assert_eq!(cmd.options.caller(), ANY_CALLER);
+        assert_eq!(cmd.options.validity, dojo_utils::DEFAULT_VALIDITY.as_secs());
+    }
+
+    #[test]
+    fn paymaster_relays_outside_execution() {
+        let cmd = Command::parse_from([
+            "sozo",
+            "--paymaster.address",
+            "0x2",
+            "--paymaster.private-key",
+            "0x3",
+        ]);
+        assert!(cmd.options.is_outside_execution());
+        assert_eq!(cmd.options.caller(), Felt::TWO);
+    }
+
+    #[test]
+    fn address_is_only_used_for_outside_execution() {
+        // The address may be exported in the environment for the other commands.
+        let cmd = Command::parse_from(["sozo", "--paymaster.address", "0x2"]);
+        assert!(!cmd.options.is_outside_execution());
+
+        let cmd = Command::parse_from([
+            "sozo",
+            "--paymaster.address",
+            "0x2",
+            "--outside-execution.output",
+            "payload.json",
+        ]);
+        assert!(cmd.options.is_outside_execution());
+        assert_eq!(cmd.options.caller(), Felt::TWO);
+    }
+
+    #[tokio::test]
+    async fn private_key_requires_address() {
+        let cmd = Command::parse_from(["sozo", "--paymaster.private-key", "0x3"]);
+        assert!(cmd.options.is_outside_execution());
+
+        let provider = JsonRpcClient::new(HttpTransport::new(
+            Url::parse("http://localhost:5050").unwrap(),
+        ));
+        assert!(cmd.options.paymaster(Arc::new(provider)).await.is_err());
+    }
+
+    #[test]
+    fn output_conflicts_with_private_key() {
+        assert!(Command::try_parse_from([
+            "sozo",
+            "--paymaster.address",
+            "0x2",
+            "--paymaster.private-key",
+            "0x3",
+            "--outside-execution.output",
+            "payload.json",
+        ])
+        .is_err());
+    }
+}
@@ -1,15 +1,21 @@
+use std::num::NonZeroU32;
 use std::time::Duration;
 
 use anyhow::Result;
 use clap::Args;
 use dojo_utils::env::STARKNET_RPC_URL_ENV_VAR;
+use dojo_utils::provider::FailoverTransport;
 use dojo_world::config::Environment;
 use reqwest::ClientBuilder;
-use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 use tracing::trace;
 use url::Url;
 
+use crate::metrics::MetricsTransport;
+
+/// The provider used by sozo commands, recording the RPC requests metrics.
+pub type StarknetProvider = JsonRpcClient<MetricsTransport<FailoverTransport>>;
+
 #[derive(Debug, Args, Clone)]
 #[command(next_help_heading = "Starknet options")]
 pub struct StarknetOptions {
@@ -18,6 +24,27 @@ pub struct StarknetOptions {
     #[arg(help = "The Starknet RPC endpoint.")]
     #[arg(global = true)]
     pub rpc_url: Option<Url>,
+
+    #[arg(long = "rpc.fallback-url")]
+    #[arg(value_name = "URL")]
+    #[arg(value_delimiter = ',')]
+    #[arg(help = "RPC endpoints to fail over to when the main endpoint errors or throttles \
+                  the requests.")]
+    #[arg(global = true)]
+    pub rpc_fallback_urls: Vec<Url>,
+
+    #[arg(long = "rpc.max-retries")]
+    #[arg(value_name = "COUNT")]
+    #[arg(help = "Number of retries of the read requests once all the endpoints failed.")]
+    #[arg(default_value_t = FailoverTransport::DEFAULT_MAX_RETRIES)]
+    #[arg(global = true)]
+    pub rpc_max_retries: u32,
+
+    #[arg(long = "rpc.requests-per-second")]
+    #[arg(value_name = "COUNT")]
+    #[arg(help = "Maximum number of RPC requests sent per second, over all the endpoints.")]
+    #[arg(global = true)]
+    pub rpc_requests_per_second: Option<NonZeroU32>,
 }
 
 impl StarknetOptions {
@@ -28,17 +55,29 @@ impl StarknetOptions {
 
     /// Returns a [`JsonRpcClient`] and the rpc url.
     ///
+    /// The requests are sent to the rpc url, and fail over to the fallback urls from the
+    /// command line or the environment metadata.
+    ///
     /// It would be convenient to have the rpc url retrievable from the Provider trait instead.
     pub fn provider(
         &self,
         env_metadata: Option<&Environment>,
-    ) -> Result<(JsonRpcClient<HttpTransport>, String)> {
+    ) -> Result<(StarknetProvider, String)> {
         let url = self.url(env_metadata)?;
 
         let client =
             ClientBuilder::default().timeout(Self::DEFAULT_REQUEST_TIMEOUT).build().unwrap();
 
-        let mut transport = HttpTransport::new_with_client(url.clone(), client);
+        let mut transport = FailoverTransport::new(url.clone(), client)
+            .with_max_retries(self.rpc_max_retries);
+
+        for fallback_url in self.fallback_urls(env_metadata)? {
+            transport = transport.with_fallback(fallback_url);
+        }
+
+        if let Some(requests_per_second) = self.rpc_requests_per_second {
+            transport = transport.with_rate_limit(requests_per_second);
+        }
 
         if let Some(headers) = env_metadata.and_then(|env| env.http_headers.as_ref()) {
             for header in headers.iter() {
@@ -46,7 +85,7 @@ impl StarknetOptions {
             }
         }
 
-        Ok((JsonRpcClient::new(transport), url.to_string()))
+        Ok((JsonRpcClient::new(MetricsTransport::new(transport)), url.to_string()))
     }
 
     // We dont check the env var because that would be handled by `clap`.
@@ -65,6 +104,19 @@ impl StarknetOptions {
             Ok(Url::parse("http://localhost:5050").unwrap())
         }
     }
+
+    /// Returns the urls to fail over to, the command line ones taking precedence over the
+    /// environment metadata ones.
+    pub fn fallback_urls(&self, env_metadata: Option<&Environment>) -> Result<Vec<Url>> {
+        if !self.rpc_fallback_urls.is_empty() {
+            return Ok(self.rpc_fallback_urls.clone());
+        }
+
+        env_metadata
+            .and_then(|env| env.rpc_fallback_urls.as_ref())
+            .map(|urls| urls.iter().map(|url| Ok(Url::parse(url)?)).collect())
+            .unwrap_or(Ok(vec![]))
+    }
 }
 
 #[cfg(test)]
@@ -128,4 +180,38 @@ mod tests {
         let cmd = Command::parse_from([""]);
         assert_eq!(cmd.options.url(Some(&env_metadata)).unwrap().as_str(), DEFAULT_RPC);
     }
+
+    #[test]
+    fn fallback_urls_from_args_override_env() {
+        let env_metadata = dojo_world::config::Environment {
+            rpc_fallback_urls: Some(vec![METADATA_RPC.into()]),
+            ..Default::default()
+        };
+
+        let cmd = Command::parse_from([""]);
+        let urls = cmd.options.fallback_urls(Some(&env_metadata)).unwrap();
+        assert_eq!(urls.iter().map(|u| u.as_str()).collect::<Vec<_>>(), [METADATA_RPC]);
+
+        let fallback_urls = format!("{ENV_RPC},{DEFAULT_RPC}");
+        let cmd = Command::parse_from(["sozo", "--rpc.fallback-url", &fallback_urls]);
+        let urls = cmd.options.fallback_urls(Some(&env_metadata)).unwrap();
+        assert_eq!(urls.iter().map(|u| u.as_str()).collect::<Vec<_>>(), [ENV_RPC, DEFAULT_RPC]);
+    }
+
+    #[test]
+    fn rpc_limits_from_args() {
+        let cmd = Command::parse_from([""]);
+        assert_eq!(cmd.options.rpc_max_retries, 3);
+        assert!(cmd.options.rpc_requests_per_second.is_none());
+
+        let cmd = Command::parse_from([
+            "sozo",
+            "--rpc.max-retries",
+            "5",
+            "--rpc.requests-per-second",
+            "10",
+        ]);
+        assert_eq!(cmd.options.rpc_max_retries, 5);
+        assert_eq!(cmd.options.rpc_requests_per_second.unwrap().get(), 10);
+    }
 }
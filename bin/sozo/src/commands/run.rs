@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use dojo_utils::TxnConfig;
+use scarb::core::Config;
+use sozo_ops::scenario::{self, Scenario};
+use sozo_scarbext::WorkspaceExt;
+use tracing::trace;
+
+use super::options::account::AccountOptions;
+use super::options::starknet::StarknetOptions;
+use super::options::transaction::TransactionOptions;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+#[derive(Debug, Args)]
+#[command(about = "Run a scenario of steps against the world, stopping at the first failure.")]
+pub struct RunArgs {
+    #[arg(help = "The path of the TOML scenario. The steps execute systems, call views, read \
+                  model records, assert on the values read, capture them into variables, switch \
+                  accounts and wait for blocks.")]
+    pub script: PathBuf,
+
+    #[arg(long)]
+    #[arg(help = "If true, sozo will compute the diff of the world from the chain to translate \
+                  tags to addresses.")]
+    pub diff: bool,
+
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
+
+    #[command(flatten)]
+    pub account: AccountOptions,
+
+    #[command(flatten)]
+    pub world: WorldOptions,
+
+    #[command(flatten)]
+    pub transaction: TransactionOptions,
+}
+
+impl RunArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let scenario = Scenario::load(&self.script)?;
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let profile_config = ws.load_profile_config()?;
+
+        let txn_config: TxnConfig = self.transaction.try_into()?;
+
+        config.tokio_handle().block_on(async {
+            let (provider, _) = self.starknet.provider(profile_config.env.as_ref())?;
+
+            let contracts = utils::contracts_from_manifest_or_diff(
+                self.account.clone(),
+                self.starknet.clone(),
+                self.world,
+                &ws,
+                self.diff,
+            )
+            .await?;
+
+            let world_address = contracts
+                .get("world")
+                .map(|c| c.address)
+                .ok_or_else(|| anyhow!("Unable to find the world address."))?;
+
+            let account = self
+                .account
+                .account(provider, profile_config.env.as_ref(), &self.starknet, &contracts)
+                .await?;
+
+            scenario::run_scenario(&scenario, &account, world_address, &contracts, txn_config)
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser, Debug)]
+    struct TestCommand {
+        #[command(flatten)]
+        args: RunArgs,
+    }
+
+    #[test]
+    fn test_run_argument_parsing() {
+        let cmd = TestCommand::parse_from(["run", "scripts/smoke.toml", "--diff"]);
+        assert_eq!(cmd.args.script, PathBuf::from("scripts/smoke.toml"));
+        assert!(cmd.args.diff);
+
+        assert!(TestCommand::try_parse_from(["run"]).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use dojo_world::remote::{Permission, WorldChange, WorldRemote};
+use scarb::core::Config;
+use serde_json::{json, Value as JsonValue};
+use sozo_scarbext::WorkspaceExt;
+use starknet::core::types::Felt;
+use starknet::providers::Provider;
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
+use tracing::trace;
+
+use super::options::starknet::StarknetOptions;
+use super::options::world::WorldOptions;
+use crate::utils::{self, MAX_BLOCK_RANGE};
+
+#[derive(Debug, Args)]
+pub struct WorldArgs {
+    #[command(subcommand)]
+    command: WorldCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WorldCommand {
+    #[command(about = "Show what changed on the world between two blocks: registered and \
+                       upgraded resources, permissions and metadata.")]
+    Diff {
+        #[arg(long)]
+        #[arg(help = "The block of the world to compare from.")]
+        from: u64,
+
+        #[arg(long)]
+        #[arg(help = "The block of the world to compare to. Defaults to the latest block.")]
+        to: Option<u64>,
+
+        #[arg(long)]
+        #[arg(help = "Print the changes as JSON.")]
+        json: bool,
+
+        #[command(flatten)]
+        world: WorldOptions,
+
+        #[command(flatten)]
+        starknet: StarknetOptions,
+    },
+}
+
+impl WorldArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+
+        match self.command {
+            WorldCommand::Diff { from, to, json, world, starknet } => {
+                config.tokio_handle().block_on(async {
+                    let world_local = ws.load_world_local()?;
+                    let profile_config = ws.load_profile_config()?;
+                    let env = profile_config.env.as_ref();
+
+                    let world_address =
+                        utils::get_world_address(&profile_config, &world, &world_local)?;
+                    let (provider, _) = starknet.provider(env)?;
+
+                    let to = match to {
+                        Some(to) => to,
+                        None => provider.block_number().await?,
+                    };
+
+                    if from > to {
+                        anyhow::bail!("The `--from` block {from} is after the `--to` block {to}.");
+                    }
+
+                    let namespaces =
+                        if world.namespaces.is_empty() { None } else { Some(world.namespaces) };
+
+                    // The events are fetched once up to `to`, and replayed up to each block.
+                    let snapshots = WorldRemote::from_events_at_blocks(
+                        world_address,
+                        &provider,
+                        env.and_then(|e| e.world_block),
+                        &[from, to],
+                        env.and_then(|e| e.max_block_range).unwrap_or(MAX_BLOCK_RANGE),
+                        namespaces,
+                    )
+                    .await?;
+
+                    let (previous, current) = (&snapshots[0], &snapshots[1]);
+                    let changes = current.changes_since(previous);
+
+                    if json {
+                        let changes = changes.iter().map(change_json).collect::<Vec<_>>();
+                        println!("{}", serde_json::to_string_pretty(&changes)?);
+                    } else if changes.is_empty() {
+                        println!("No changes on the world between blocks {from} and {to}.");
+                    } else {
+                        println!(
+                            "{}",
+                            format!("World changes between blocks {from} and {to}.").bright_black()
+                        );
+
+                        let rows = changes.iter().map(|c| change_display(c, current));
+                        let mut table = Table::new(rows);
+                        table.with(Style::psql());
+                        println!("{table}");
+                    }
+
+                    Ok(())
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+struct ChangeDisplay {
+    #[tabled(rename = "Change")]
+    change: String,
+    #[tabled(rename = "Resource")]
+    resource: String,
+    #[tabled(rename = "Details")]
+    details: String,
+}
+
+/// Returns the row of a world change, the contracts of the permission changes being labelled
+/// with their tag when they are resources of the world.
+fn change_display(change: &WorldChange, world: &WorldRemote) -> ChangeDisplay {
+    let hashes = |from: &Felt, to: &Felt| format!("{:#066x} -> {:#066x}", from, to);
+
+    match change {
+        WorldChange::WorldUpgraded { from_class_hash, to_class_hash } => ChangeDisplay {
+            change: "Upgraded".to_string(),
+            resource: "World".to_string(),
+            details: format!("Class hash {}", hashes(from_class_hash, to_class_hash)),
+        },
+        WorldChange::ResourceRegistered { tag, resource_type, class_hash, .. } => ChangeDisplay {
+            change: "Registered".to_string(),
+            resource: tag.clone(),
+            details: format!("{:?} with class hash {:#066x}", resource_type, class_hash),
+        },
+        WorldChange::ResourceUpgraded { tag, from_class_hash, to_class_hash, .. } => {
+            ChangeDisplay {
+                change: "Upgraded".to_string(),
+                resource: tag.clone(),
+                details: format!("Class hash {}", hashes(from_class_hash, to_class_hash)),
+            }
+        }
+        WorldChange::MetadataUpdated { tag, from_hash, to_hash, .. } => ChangeDisplay {
+            change: "Metadata updated".to_string(),
+            resource: tag.clone(),
+            details: format!("Metadata hash {}", hashes(from_hash, to_hash)),
+        },
+        WorldChange::PermissionUpdated { tag, permission, contract, granted, .. } => {
+            let grantee = world
+                .resources
+                .values()
+                .find(|r| r.address() == *contract && *contract != Felt::ZERO)
+                .map(|r| format!("{} ({:#066x})", r.tag(), contract))
+                .unwrap_or_else(|| format!("{:#066x}", contract));
+            let action = if *granted { "granted" } else { "revoked" };
+
+            ChangeDisplay {
+                change: format!("{:?} {action}", permission),
+                resource: tag.clone(),
+                details: grantee,
+            }
+        }
+    }
+}
+
+/// Returns the JSON object of a world change.
+fn change_json(change: &WorldChange) -> JsonValue {
+    let felt = |f: &Felt| format!("{:#066x}", f);
+
+    match change {
+        WorldChange::WorldUpgraded { from_class_hash, to_class_hash } => json!({
+            "change": "world_upgraded",
+            "from_class_hash": felt(from_class_hash),
+            "to_class_hash": felt(to_class_hash),
+        }),
+        WorldChange::ResourceRegistered { selector, tag, resource_type, class_hash } => json!({
+            "change": "resource_registered",
+            "resource": tag,
+            "selector": felt(selector),
+            "resource_type": format!("{:?}", resource_type),
+            "class_hash": felt(class_hash),
+        }),
+        WorldChange::ResourceUpgraded { selector, tag, from_class_hash, to_class_hash } => json!({
+            "change": "resource_upgraded",
+            "resource": tag,
+            "selector": felt(selector),
+            "from_class_hash": felt(from_class_hash),
+            "to_class_hash": felt(to_class_hash),
+        }),
+        WorldChange::MetadataUpdated { selector, tag, from_hash, to_hash } => json!({
+            "change": "metadata_updated",
+            "resource": tag,
+            "selector": felt(selector),
+            "from_hash": felt(from_hash),
+            "to_hash": felt(to_hash),
+        }),
+        WorldChange::PermissionUpdated { selector, tag, permission, contract, granted } => json!({
+            "change": match permission {
+                Permission::Writer => "writer_updated",
+                Permission::Owner => "owner_updated",
+            },
+            "resource": tag,
+            "selector": felt(selector),
+            "contract": felt(contract),
+            "granted": granted,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_json() {
+        let change = WorldChange::PermissionUpdated {
+            selector: Felt::ZERO,
+            tag: "World".to_string(),
+            permission: Permission::Owner,
+            contract: Felt::ONE,
+            granted: false,
+        };
+
+        assert_eq!(
+            change_json(&change),
+            json!({
+                "change": "owner_updated",
+                "resource": "World",
+                "selector": format!("{:#066x}", 0),
+                "contract": format!("{:#066x}", 1),
+                "granted": false,
+            })
+        );
+
+        let row = change_display(&change, &WorldRemote::default());
+        assert_eq!(row.change, "Owner revoked");
+        assert_eq!(row.details, format!("{:#066x}", 1));
+    }
+}
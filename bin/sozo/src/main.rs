@@ -13,6 +13,7 @@ use scarb_ui::{OutputFormat, Ui};
 use tracing::trace;
 mod args;
 mod commands;
+mod metrics;
 mod utils;
 
 fn main() {
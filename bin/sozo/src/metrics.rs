@@ -0,0 +1,194 @@
+//! Metrics of sozo long-running modes.
+//!
+//! The metrics are recorded through the [`metrics`] facade, hence recording is a no-op
+//! until a recorder is installed with `--metrics`. All the metrics names are prefixed with
+//! `sozo_` by the recorder.
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use dojo_metrics::metrics::{
+    counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Unit,
+};
+use dojo_world::diff::{ResourceDiff, WorldDiff};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sozo_ops::migrate::{FeeReport, MIGRATION_STAGE_DURATION};
+use starknet::providers::jsonrpc::{JsonRpcMethod, JsonRpcResponse, JsonRpcTransport};
+use starknet::providers::ProviderRequestData;
+
+const BUILD_DURATION: &str = "build_duration_seconds";
+const BUILDS_FAILED: &str = "builds_failed_total";
+const MIGRATION_DURATION: &str = "migration_duration_seconds";
+const MIGRATIONS_FAILED: &str = "migrations_failed_total";
+const TRANSACTIONS_SENT: &str = "transactions_sent_total";
+const TRANSACTIONS_FAILED: &str = "transactions_failed_total";
+const FEES_SPENT: &str = "fees_spent_gfri_total";
+const RPC_REQUEST_DURATION: &str = "rpc_request_duration_seconds";
+const RPC_REQUESTS_FAILED: &str = "rpc_requests_failed_total";
+const RESOURCES: &str = "resources";
+
+/// The number of FRI in a GFRI, the unit of the fees counter.
+const GFRI: u128 = 1_000_000_000;
+
+/// Describes all the metrics recorded by sozo.
+pub fn describe() {
+    describe_histogram!(BUILD_DURATION, Unit::Seconds, "Duration of the builds.");
+    describe_counter!(BUILDS_FAILED, "Number of failed builds.");
+    describe_histogram!(MIGRATION_DURATION, Unit::Seconds, "Duration of the migrations.");
+    describe_histogram!(
+        MIGRATION_STAGE_DURATION,
+        Unit::Seconds,
+        "Duration of each stage of the migrations."
+    );
+    describe_counter!(MIGRATIONS_FAILED, "Number of failed migrations.");
+    describe_counter!(TRANSACTIONS_SENT, "Number of transactions sent by the migrations.");
+    describe_counter!(TRANSACTIONS_FAILED, "Number of transactions rejected or reverted.");
+    describe_counter!(FEES_SPENT, "Fees spent by the migrations, in GFRI (10^-9 STRK).");
+    describe_histogram!(RPC_REQUEST_DURATION, Unit::Seconds, "Duration of the RPC requests.");
+    describe_counter!(RPC_REQUESTS_FAILED, "Number of failed RPC requests.");
+    describe_gauge!(RESOURCES, "Number of resources of the world diff, by status.");
+}
+
+/// Records the outcome of a build.
+pub fn record_build(duration: Duration, success: bool) {
+    histogram!(BUILD_DURATION).record(duration.as_secs_f64());
+
+    if !success {
+        counter!(BUILDS_FAILED).increment(1);
+    }
+}
+
+/// Records a successful migration, with the transactions sent and the fees paid.
+pub fn record_migration(duration: Duration, fee_report: &FeeReport) {
+    histogram!(MIGRATION_DURATION).record(duration.as_secs_f64());
+
+    let totals = fee_report.totals();
+    counter!(TRANSACTIONS_SENT).increment(totals.transactions as u64);
+    // Counters are integers, and a FRI counter would overflow after 18 STRK.
+    counter!(FEES_SPENT).increment(u64::try_from(totals.fee_fri / GFRI).unwrap_or(u64::MAX));
+}
+
+/// Records a failed migration. `transaction_failed` is true if the migration failed
+/// because a transaction was rejected or reverted.
+pub fn record_migration_failure(duration: Duration, transaction_failed: bool) {
+    histogram!(MIGRATION_DURATION).record(duration.as_secs_f64());
+    counter!(MIGRATIONS_FAILED).increment(1);
+
+    if transaction_failed {
+        counter!(TRANSACTIONS_FAILED).increment(1);
+    }
+}
+
+/// Records the number of resources by [`ResourceDiff`] status.
+pub fn record_world_diff(diff: &WorldDiff) {
+    let (mut created, mut updated, mut synced) = (0, 0, 0);
+
+    for resource in diff.resources.values() {
+        match resource {
+            ResourceDiff::Created(_) => created += 1,
+            ResourceDiff::Updated(_, _) => updated += 1,
+            ResourceDiff::Synced(_, _) => synced += 1,
+        }
+    }
+
+    gauge!(RESOURCES, "status" => "created").set(created as f64);
+    gauge!(RESOURCES, "status" => "updated").set(updated as f64);
+    gauge!(RESOURCES, "status" => "synced").set(synced as f64);
+}
+
+/// A [`JsonRpcTransport`] recording the latency and the errors of the RPC requests, by method.
+#[derive(Debug, Clone)]
+pub struct MetricsTransport<T> {
+    inner: T,
+}
+
+impl<T> MetricsTransport<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+/// Returns the name of the RPC method, as sent on the wire.
+fn method_name(method: &JsonRpcMethod) -> String {
+    serde_json::to_value(method)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| format!("{:?}", method))
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for MetricsTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync,
+{
+    type Error = T::Error;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let method_name = method_name(&method);
+        let start = Instant::now();
+
+        let res = self.inner.send_request(method, params).await;
+
+        histogram!(RPC_REQUEST_DURATION, "method" => method_name.clone())
+            .record(start.elapsed().as_secs_f64());
+
+        if !matches!(res, Ok(JsonRpcResponse::Success { .. })) {
+            counter!(RPC_REQUESTS_FAILED, "method" => method_name).increment(1);
+        }
+
+        res
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        let start = Instant::now();
+
+        let res = self.inner.send_requests(requests).await;
+
+        histogram!(RPC_REQUEST_DURATION, "method" => "batch")
+            .record(start.elapsed().as_secs_f64());
+
+        match &res {
+            Ok(responses) => {
+                let n_errors = responses
+                    .iter()
+                    .filter(|r| !matches!(r, JsonRpcResponse::Success { .. }))
+                    .count();
+
+                if n_errors > 0 {
+                    counter!(RPC_REQUESTS_FAILED, "method" => "batch").increment(n_errors as u64);
+                }
+            }
+            Err(_) => counter!(RPC_REQUESTS_FAILED, "method" => "batch").increment(1),
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::providers::jsonrpc::JsonRpcMethod;
+
+    use super::method_name;
+
+    #[test]
+    fn method_name_is_the_rpc_method() {
+        assert_eq!(method_name(&JsonRpcMethod::ChainId), "starknet_chainId");
+        assert_eq!(method_name(&JsonRpcMethod::GetEvents), "starknet_getEvents");
+    }
+}
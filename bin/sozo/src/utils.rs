@@ -1,29 +1,36 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
 use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_filesystem::db::{CrateConfiguration, FilesGroup, FilesGroupEx};
+use cairo_lang_filesystem::ids::{CrateLongId, Directory};
 use camino::Utf8PathBuf;
 use colored::*;
+use dojo_lang::aux_data::DojoAuxData;
+use dojo_lang::dojo_plugin_suite;
 use dojo_utils::provider as provider_utils;
 use dojo_world::config::ProfileConfig;
 use dojo_world::contracts::ContractInfo;
 use dojo_world::diff::WorldDiff;
 use dojo_world::local::WorldLocal;
-use scarb::core::{TomlManifest, Workspace};
+use scarb::compiler::CompilationUnit;
+use scarb::core::{TargetKind, TomlManifest, Workspace};
+use scarb::ops::{CompilationUnitsOpts, FeaturesOpts, FeaturesSelector};
 use semver::Version;
+use sozo_ops::dependencies::{self, ContractDependencies};
 use sozo_ops::migration_ui::MigrationUi;
 use sozo_scarbext::WorkspaceExt;
 use starknet::accounts::{Account, ConnectedAccount};
 use starknet::core::types::Felt;
 use starknet::core::utils as snutils;
-use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::{JsonRpcClient, Provider};
+use starknet::providers::Provider;
 use tracing::{trace, warn};
 
 use crate::commands::options::account::{AccountOptions, SozoAccount};
-use crate::commands::options::starknet::StarknetOptions;
+use crate::commands::options::starknet::{StarknetOptions, StarknetProvider};
 use crate::commands::options::world::WorldOptions;
 use crate::commands::LOG_TARGET;
 
@@ -129,7 +136,18 @@ pub async fn get_world_diff_and_provider(
     starknet: StarknetOptions,
     world: WorldOptions,
     ws: &Workspace<'_>,
-) -> Result<(WorldDiff, JsonRpcClient<HttpTransport>, String)> {
+) -> Result<(WorldDiff, StarknetProvider, String)> {
+    get_world_diff_and_provider_at_block(starknet, world, ws, None).await
+}
+
+/// Same as [`get_world_diff_and_provider`], but the remote world is the one as of `at_block` if
+/// provided.
+pub async fn get_world_diff_and_provider_at_block(
+    starknet: StarknetOptions,
+    world: WorldOptions,
+    ws: &Workspace<'_>,
+    at_block: Option<u64>,
+) -> Result<(WorldDiff, StarknetProvider, String)> {
     let world_local = ws.load_world_local()?;
     let profile_config = ws.load_profile_config()?;
 
@@ -167,11 +185,12 @@ pub async fn get_world_diff_and_provider(
         .with_context(|| "Cannot parse chain_id as string")?;
     trace!(chain_id);
 
-    let world_diff = WorldDiff::new_from_chain(
+    let world_diff = WorldDiff::new_from_chain_at(
         world_address,
         world_local,
         &provider,
         env.and_then(|e| e.world_block),
+        at_block,
         env.and_then(|e| e.max_block_range).unwrap_or(MAX_BLOCK_RANGE),
         &world.namespaces,
     )
@@ -190,7 +209,7 @@ pub async fn get_world_diff_and_account(
     world: WorldOptions,
     ws: &Workspace<'_>,
     ui: &mut Option<&mut MigrationUi>,
-) -> Result<(WorldDiff, SozoAccount<JsonRpcClient<HttpTransport>>, String)> {
+) -> Result<(WorldDiff, SozoAccount<StarknetProvider>, String)> {
     let profile_config = ws.load_profile_config()?;
     let env = profile_config.env.as_ref();
 
@@ -230,7 +249,10 @@ pub async fn get_world_diff_and_account(
 ///
 /// * `Result<bool>` - Returns `true` if the provided version is compatible with the expected
 ///   version, `false` otherwise.
-fn is_compatible_version(provided_version: &str, expected_version: &str) -> Result<bool> {
+pub(crate) fn is_compatible_version(
+    provided_version: &str,
+    expected_version: &str,
+) -> Result<bool> {
     use semver::{Version, VersionReq};
 
     let provided_ver = Version::parse(provided_version)
@@ -286,6 +308,75 @@ pub fn prompt_confirm(prompt: &str) -> Result<bool> {
     Ok(input.trim().to_lowercase() == "y")
 }
 
+/// Expands the workspace members with the Dojo plugin to collect the aux data of their contracts,
+/// models and events, and returns the models and events read and written by each local contract.
+///
+/// All the features are enabled, to account for every contract that may be built.
+pub fn contracts_dependencies(
+    ws: &Workspace<'_>,
+    world_local: &WorldLocal,
+) -> Result<Vec<ContractDependencies>> {
+    let resolve = scarb::ops::resolve_workspace(ws)?;
+    let features_opts =
+        FeaturesOpts { features: FeaturesSelector::AllFeatures, no_default_features: false };
+    let cu_opts = CompilationUnitsOpts { ignore_cairo_version: false, load_prebuilt_macros: false };
+    let compilation_units =
+        scarb::ops::generate_compilation_units(&resolve, &features_opts, ws, cu_opts)?;
+
+    let members = ws.members().map(|p| p.id).collect::<HashSet<_>>();
+    let mut expanded = HashSet::new();
+    let mut aux_data = DojoAuxData::default();
+
+    for unit in compilation_units {
+        let CompilationUnit::Cairo(unit) = unit else {
+            continue;
+        };
+
+        let main = unit.main_component();
+
+        // A package has a compilation unit per target, the tests have no contract to check.
+        if !members.contains(&main.package.id)
+            || main.targets[0].kind == TargetKind::TEST
+            || !expanded.insert(main.package.id)
+        {
+            continue;
+        }
+
+        // The aux data are attached to the files generated by the plugin, hence only the macro
+        // expansion is done and the crate is not compiled again.
+        let mut db = RootDatabase::builder()
+            .with_cfg(unit.cfg_set.clone())
+            .with_plugin_suite(dojo_plugin_suite())
+            .build()?;
+
+        let crate_id = db.intern_crate(CrateLongId::Real {
+            name: main.cairo_package_name(),
+            discriminator: main.id.to_discriminator(),
+        });
+        let root = Directory::Real(main.targets[0].source_root().into());
+        db.set_crate_config(crate_id, Some(CrateConfiguration::default_for_root(root)));
+
+        aux_data.extend_from_crate(&db, crate_id);
+    }
+
+    Ok(dependencies::resolve(world_local, &dependencies::from_aux_data(&aux_data)))
+}
+
+/// Prints the mismatches between the writers of the profile config and the resources written by
+/// the contracts.
+pub fn print_writer_warnings(contracts: &[ContractDependencies], profile_config: &ProfileConfig) {
+    let warnings = dependencies::check_writers(contracts, profile_config);
+
+    if warnings.is_empty() {
+        return;
+    }
+
+    println!();
+    for warning in warnings {
+        println!("{}", format!("warning: {warning}").bright_yellow());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,7 +4,7 @@ use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
 
 use async_trait::async_trait;
-use cainome::parser::tokens::{Composite, Function};
+use cainome::parser::tokens::{Composite, Function, Token};
 
 use crate::error::BindgenResult;
 use crate::{DojoContract, DojoData};
@@ -178,10 +178,37 @@ pub trait BindgenContractGenerator: Sync {
     ) -> BindgenResult<String>;
 }
 
+/// Returns the item token and the size of a Cairo fixed-size array (`[T; N]`),
+/// or `None` if the token is not a fixed-size array.
+pub(crate) fn fixed_size_array(token: &Token) -> Option<(Token, u32)> {
+    let type_path = token.type_path();
+    let inner = type_path.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (item, size) = inner.rsplit_once(';')?;
+
+    let size = size.trim().parse::<u32>().ok()?;
+    let item = Token::parse(item.trim()).ok()?;
+
+    Some((item, size))
+}
+
 #[cfg(test)]
 mod tests {
+    use cainome::parser::tokens::CoreBasic;
+
     use super::*;
 
+    #[test]
+    fn test_fixed_size_array() {
+        let token = Token::CoreBasic(CoreBasic { type_path: "[core::integer::u8; 8]".to_string() });
+        let (item, size) = fixed_size_array(&token).unwrap();
+
+        assert_eq!(item.type_name(), "u8");
+        assert_eq!(size, 8);
+
+        let token = Token::CoreBasic(CoreBasic { type_path: "core::integer::u8".to_string() });
+        assert!(fixed_size_array(&token).is_none());
+    }
+
     #[test]
     fn test_buffer_get_first_after() {
         let mut buff = Buffer::new();
@@ -6,7 +6,7 @@ use cainome::parser::tokens::{Composite, CompositeType, Function, Token};
 use dojo_world::contracts::naming;
 
 use crate::error::BindgenResult;
-use crate::plugins::BuiltinPlugin;
+use crate::plugins::{fixed_size_array, BuiltinPlugin};
 use crate::{compare_tokens_by_type_name, DojoContract, DojoData, DojoModel};
 
 #[cfg(test)]
@@ -21,6 +21,15 @@ impl TypescriptRecsPlugin {
 
     // Maps cairo types to C#/Unity SDK defined types
     fn map_type(token: &Token) -> String {
+        if let Some((item, _)) = fixed_size_array(token) {
+            let mut mapped = TypescriptRecsPlugin::map_type(&item);
+            if mapped == item.type_name() {
+                mapped = "RecsType.String".to_string();
+            }
+
+            return format!("{}Array", mapped);
+        }
+
         match token.type_name().as_str() {
             "bool" => "RecsType.Boolean".to_string(),
             "i8" => "RecsType.Number".to_string(),
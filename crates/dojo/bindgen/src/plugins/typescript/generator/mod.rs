@@ -7,6 +7,7 @@ use constants::{
 };
 use convert_case::{Case, Casing};
 
+use crate::plugins::fixed_size_array;
 use crate::plugins::typescript::generator::constants::CAIRO_OPTION_TYPE_PATH;
 
 pub(crate) mod constants;
@@ -102,6 +103,11 @@ impl From<&str> for JsPrimitiveType {
 
 impl From<&Token> for JsPrimitiveType {
     fn from(value: &Token) -> Self {
+        if let Some((item, _)) = fixed_size_array(value) {
+            let item = JsPrimitiveType::from(&item);
+            return JsPrimitiveType::from(format!("Array<{}>", item).as_str());
+        }
+
         match value {
             Token::Array(a) => JsPrimitiveType::from(
                 format!("Array<{}>", JsPrimitiveType::from(&*a.inner)).as_str(),
@@ -183,6 +189,11 @@ impl From<&str> for JsPrimitiveInputType {
 
 impl From<&Token> for JsPrimitiveInputType {
     fn from(value: &Token) -> Self {
+        if let Some((item, _)) = fixed_size_array(value) {
+            let item = JsPrimitiveInputType::from(&item);
+            return JsPrimitiveInputType::from(format!("Array<{}>", item).as_str());
+        }
+
         match value {
             Token::Array(a) => JsPrimitiveInputType::from(
                 format!("Array<{}>", JsPrimitiveInputType::from(&*a.inner)).as_str(),
@@ -341,6 +352,11 @@ fn build_struct_default_value(token: &Composite) -> String {
 
 impl From<&Token> for JsPrimitiveDefaultValue {
     fn from(value: &Token) -> Self {
+        if let Some((item, size)) = fixed_size_array(value) {
+            let item = JsPrimitiveDefaultValue::from(&item).to_string();
+            return JsPrimitiveDefaultValue(format!("[{}]", vec![item; size as usize].join(", ")));
+        }
+
         match value {
             Token::Array(a) => JsPrimitiveDefaultValue::from(
                 format!("[{}]", JsPrimitiveDefaultValue::from(&*a.inner)).as_str(),
@@ -7,7 +7,7 @@ use convert_case::Casing;
 use dojo_world::contracts::naming;
 
 use crate::error::BindgenResult;
-use crate::plugins::BuiltinPlugin;
+use crate::plugins::{fixed_size_array, BuiltinPlugin};
 use crate::{DojoContract, DojoData, DojoModel};
 
 pub struct TypeScriptV2Plugin {}
@@ -19,6 +19,10 @@ impl TypeScriptV2Plugin {
 
     // Maps cairo types to TypeScript defined types
     fn map_type(token: &Token) -> String {
+        if let Some((item, _)) = fixed_size_array(token) {
+            return format!("{}[]", TypeScriptV2Plugin::map_type(&item));
+        }
+
         match token.type_name().as_str() {
             "bool" => "boolean".to_string(),
             "u8" => "number".to_string(),
@@ -6,7 +6,7 @@ use cainome::parser::tokens::{Composite, CompositeType, Function, FunctionOutput
 use dojo_world::contracts::naming::{self, get_namespace_from_tag};
 
 use crate::error::BindgenResult;
-use crate::plugins::BuiltinPlugin;
+use crate::plugins::{fixed_size_array, BuiltinPlugin};
 use crate::{compare_tokens_by_type_name, DojoContract, DojoData, DojoModel};
 
 #[derive(Debug)]
@@ -19,6 +19,10 @@ impl UnityPlugin {
 
     // Maps cairo types to C#/Unity SDK defined types
     fn map_type(token: &Token) -> String {
+        if let Some((item, _)) = fixed_size_array(token) {
+            return format!("{}[]", UnityPlugin::map_type(&item));
+        }
+
         match token.type_name().as_str() {
             "i8" => "sbyte".to_string(),
             "i16" => "short".to_string(),
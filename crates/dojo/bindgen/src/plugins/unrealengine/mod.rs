@@ -6,7 +6,7 @@ use cainome::parser::tokens::{Composite, CompositeType, FunctionOutputKind, Toke
 use dojo_world::contracts::naming::{get_name_from_tag, get_namespace_from_tag};
 
 use crate::error::BindgenResult;
-use crate::plugins::BuiltinPlugin;
+use crate::plugins::{fixed_size_array, BuiltinPlugin};
 use crate::{compare_tokens_by_type_name, DojoContract, DojoData, DojoModel};
 
 #[derive(Debug)]
@@ -19,6 +19,10 @@ impl UnrealEnginePlugin {
 
     // Maps cairo types to cpp/UnrealEngine SDK defined types
     fn map_type(token: &Token) -> String {
+        if let Some((item, _)) = fixed_size_array(token) {
+            return format!("TArray<{}>", UnrealEnginePlugin::map_type(&item));
+        }
+
         match token.type_name().as_str() {
             "i8" => "int".to_string(),
             "i16" => "int".to_string(),
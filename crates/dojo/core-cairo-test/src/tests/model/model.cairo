@@ -95,6 +95,30 @@ struct ModelWithUnitType {
     a: ((), (u8, ())),
 }
 
+#[derive(Copy, Drop, Serde, Debug, PartialEq)]
+#[dojo::model]
+struct ModelWithPackedMembers {
+    #[key]
+    k: u8,
+    #[packed]
+    alive: bool,
+    #[packed]
+    level: u8,
+    #[packed]
+    xp: u32,
+    name: felt252,
+}
+
+#[derive(Copy, Drop, Serde, Debug, PartialEq)]
+#[dojo::model]
+struct ModelWithIndex {
+    #[key]
+    id: u32,
+    #[index]
+    name: felt252,
+    level: u8,
+}
+
 fn namespace_def() -> NamespaceDef {
     NamespaceDef {
         namespace: "dojo_cairo_test",
@@ -104,6 +128,9 @@ fn namespace_def() -> NamespaceDef {
             TestResource::Model(m_Foo3::TEST_CLASS_HASH.try_into().unwrap()),
             TestResource::Model(m_Foo4::TEST_CLASS_HASH.try_into().unwrap()),
             TestResource::Model(m_ModelWithUnitType::TEST_CLASS_HASH.try_into().unwrap()),
+            TestResource::Model(m_ModelWithPackedMembers::TEST_CLASS_HASH.try_into().unwrap()),
+            TestResource::Model(m_ModelWithIndex::TEST_CLASS_HASH.try_into().unwrap()),
+            TestResource::Model(m_ModelWithIndexNameIndex::TEST_CLASS_HASH.try_into().unwrap()),
         ]
             .span(),
     }
@@ -401,3 +428,143 @@ fn test_access_with_unit_type() {
         "Bad default model",
     );
 }
+
+#[test]
+fn test_packed_members_layout() {
+    let expected_layout = dojo::meta::Layout::Struct(
+        [
+            dojo::meta::FieldLayout {
+                selector: selector!("alive"), layout: dojo::meta::Layout::Fixed([1, 8, 32].span()),
+            },
+            dojo::meta::FieldLayout {
+                selector: selector!("name"), layout: dojo::meta::Layout::Fixed([251].span()),
+            },
+        ]
+            .span(),
+    );
+
+    assert_eq!(Model::<ModelWithPackedMembers>::layout(), expected_layout);
+    assert_eq!(
+        Model::<ModelWithPackedMembers>::packed_member(selector!("xp")),
+        Option::Some((selector!("alive"), 2)),
+    );
+    assert_eq!(Model::<ModelWithPackedMembers>::packed_member(selector!("name")), Option::None);
+}
+
+#[test]
+fn test_access_packed_members() {
+    let mut world = spawn_foo_world();
+
+    let m = ModelWithPackedMembers { k: 1, alive: true, level: 12, xp: 4200, name: 'bob' };
+    let m2 = ModelWithPackedMembers { k: 2, alive: false, level: 3, xp: 10, name: 'alice' };
+    world.write_models([@m, @m2].span());
+
+    let read_m: ModelWithPackedMembers = world.read_model(1);
+    assert!(m == read_m, "Bad read model");
+
+    let level: u8 = world.read_member(m.ptr(), selector!("level"));
+    assert!(level == 12, "Bad read member");
+
+    world.write_member(m.ptr(), selector!("level"), 13_u8);
+    let read_m: ModelWithPackedMembers = world.read_model(1);
+    assert!(read_m.level == 13 && read_m.xp == m.xp && read_m.alive, "Bad write member");
+
+    let ptrs = [m.ptr(), m2.ptr()].span();
+    world.write_member_of_models(ptrs, selector!("xp"), [1, 2].span());
+    let xps: Array<u32> = world.read_member_of_models(ptrs, selector!("xp"));
+    let alives: Array<bool> = world.read_member_of_models(ptrs, selector!("alive"));
+    assert!(xps == array![1, 2], "Bad write members");
+    assert!(alives == array![true, false], "Bad read members");
+}
+
+#[test]
+fn test_index_lookup() {
+    let mut world = spawn_foo_world();
+
+    let m = ModelWithIndex { id: 1, name: 'bob', level: 1 };
+    let m2 = ModelWithIndex { id: 2, name: 'alice', level: 2 };
+    world.write_models([@m, @m2].span());
+
+    assert!(world.read_model_with_index_by_name('bob') == Option::Some(m), "Bad lookup");
+    assert!(world.read_model_with_index_by_name('alice') == Option::Some(m2), "Bad lookup");
+    assert!(world.read_model_with_index_by_name('carol').is_none(), "Bad missing lookup");
+}
+
+#[test]
+fn test_index_update() {
+    let mut world = spawn_foo_world();
+
+    let mut m = ModelWithIndex { id: 1, name: 'bob', level: 1 };
+    world.write_model(@m);
+
+    m.name = 'robert';
+    world.write_model(@m);
+    assert!(world.read_model_with_index_by_name('bob').is_none(), "Stale index after write");
+    assert!(world.read_model_with_index_by_name('robert') == Option::Some(m), "Bad lookup");
+
+    // The values written from the keys update the index as well.
+    let value = ModelWithIndexValue { name: 'rob', level: 2 };
+    world.write_value(1_u32, @value);
+    assert!(world.read_model_with_index_by_name('robert').is_none(), "Stale index after value");
+
+    let expected = ModelWithIndex { id: 1, name: 'rob', level: 2 };
+    assert!(world.read_model_with_index_by_name('rob') == Option::Some(expected), "Bad lookup");
+
+    // The members which are not indexed can still be written alone.
+    world.write_member(m.ptr(), selector!("level"), 3_u8);
+    let expected = ModelWithIndex { id: 1, name: 'rob', level: 3 };
+    assert!(world.read_model_with_index_by_name('rob') == Option::Some(expected), "Bad lookup");
+}
+
+#[test]
+fn test_index_overwritten_by_another_entity() {
+    let mut world = spawn_foo_world();
+
+    let m = ModelWithIndex { id: 1, name: 'bob', level: 1 };
+    let m2 = ModelWithIndex { id: 2, name: 'bob', level: 2 };
+    world.write_models([@m, @m2].span());
+    assert!(world.read_model_with_index_by_name('bob') == Option::Some(m2), "Bad lookup");
+
+    // Erasing the first entity keeps the entry of the last written one.
+    world.erase_model(@m);
+    assert!(world.read_model_with_index_by_name('bob') == Option::Some(m2), "Bad lookup");
+}
+
+#[test]
+fn test_index_delete() {
+    let mut world = spawn_foo_world();
+
+    let m = ModelWithIndex { id: 1, name: 'bob', level: 1 };
+    let m2 = ModelWithIndex { id: 2, name: 'alice', level: 2 };
+    world.write_models([@m, @m2].span());
+
+    world.erase_model(@m);
+    assert!(world.read_model_with_index_by_name('bob').is_none(), "Stale index after erase");
+
+    world.erase_model_ptr(m2.ptr());
+    assert!(world.read_model_with_index_by_name('alice').is_none(), "Stale index after erase");
+}
+
+#[test]
+#[should_panic(
+    expected: ("Model: an indexed member can't be written alone, write the model to update its index.",),
+)]
+fn test_write_indexed_member() {
+    let mut world = spawn_foo_world();
+
+    let m = ModelWithIndex { id: 1, name: 'bob', level: 1 };
+    world.write_model(@m);
+    world.write_member(m.ptr(), selector!("name"), 'alice');
+}
+
+#[test]
+#[should_panic(
+    expected: ("Value: the model has indexes, write the value from its keys to update them.",),
+)]
+fn test_write_indexed_value_from_id() {
+    let mut world = spawn_foo_world();
+
+    let m = ModelWithIndex { id: 1, name: 'bob', level: 1 };
+    let value = ModelWithIndexValue { name: 'alice', level: 1 };
+    world.write_value_from_id(m.ptr().id, @value);
+}
@@ -48,6 +48,15 @@ pub mod model {
     pub mod model;
     pub use model::{Model, KeyParser, ModelPtr, ModelPtrsTrait};
 
+    pub mod index;
+    pub use index::ModelIndexes;
+
+    pub mod migration;
+    pub use migration::{
+        ModelMigration, IMigratableModel, IMigratableModelDispatcher,
+        IMigratableModelDispatcherTrait,
+    };
+
     pub mod model_value;
     pub use model_value::{ModelValue, ModelValueKey};
 
@@ -182,6 +182,10 @@ pub enum Ty {
     // one element, does the trick.
     Array: Span<Ty>,
     ByteArray,
+    // A fixed-size array `[T; N]`, as its item type and its size. For the same reason
+    // as `Array`, the item type is wrapped in a Span with one element.
+    // Its layout is a tuple of `N` items, so fixed-size arrays and tuples share the same storage.
+    FixedArray: Span<(Ty, u32)>,
 }
 
 #[derive(Copy, Drop, Serde, Debug, PartialEq)]
@@ -253,6 +257,13 @@ impl TyCompareImpl of TyCompareTrait<Ty> {
             },
             (Ty::ByteArray, Ty::ByteArray) => true,
             (Ty::Enum(n), Ty::Enum(o)) => n.is_an_upgrade_of(o),
+            (
+                Ty::FixedArray(n), Ty::FixedArray(o),
+            ) => {
+                let (n_ty, n_size) = (*n).at(0);
+                let (o_ty, o_size) = (*o).at(0);
+                *n_size == *o_size && n_ty.is_an_upgrade_of(o_ty)
+            },
             _ => false,
         }
     }
@@ -374,6 +385,7 @@ impl MemberCompareImpl of TyCompareTrait<Member> {
                 (Ty::Struct(n), Ty::Struct(o)) => n == o,
                 (Ty::Array(n), Ty::Array(o)) => n == o,
                 (Ty::Tuple(n), Ty::Tuple(o)) => n == o,
+                (Ty::FixedArray(n), Ty::FixedArray(o)) => n == o,
                 (Ty::ByteArray, Ty::ByteArray) => true,
                 _ => false,
             }
@@ -22,6 +22,11 @@ pub trait ModelDefinition<T> {
     fn layout() -> Layout;
     fn schema() -> Struct;
     fn size() -> Option<usize>;
+    /// The version of the model, set with `#[dojo::model(version: N)]` and 1 by default.
+    fn version() -> u32;
+    /// The packed group of a member declared with `#[packed]`, as the selector of the group field
+    /// and the index of the member in the group.
+    fn packed_member(field_selector: felt252) -> Option<(felt252, u32)>;
 }
 
 /// A plain struct with all the fields of a model definition.
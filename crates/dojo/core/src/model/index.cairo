@@ -0,0 +1,23 @@
+use dojo::world::WorldStorage;
+
+/// The `ModelIndexes` trait maintains the indexes of a model, declared with the `#[index]`
+/// attribute on its members.
+///
+/// Each index is stored in a dedicated model, keyed by the indexed member and storing the keys of
+/// the indexed entity. The implementation is generated by the `dojo::model` attribute for the
+/// model and its value type, and is empty for models without index.
+///
+/// The index entries require the keys of the entity, so the indexes are maintained by the writes
+/// knowing them (`write_model(s)`, `write_value(s)`) and by the erasures. The writes addressing an
+/// entity by its id panic instead of leaving a stale index.
+pub trait ModelIndexes<M> {
+    /// Updates the indexes of the entity `entity_id`, before it's written with the serialized
+    /// keys and the values of `entry`, or erased if `entry` is `None`.
+    fn update_indexes(
+        ref world: WorldStorage, entity_id: felt252, entry: Option<(Span<felt252>, @M)>,
+    );
+    /// Returns true if the model has at least one index.
+    fn has_indexes() -> bool;
+    /// Returns true if the member of the given selector is indexed.
+    fn is_indexed(field_selector: felt252) -> bool;
+}
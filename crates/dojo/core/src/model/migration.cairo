@@ -0,0 +1,55 @@
+use dojo::model::ModelDefinition;
+use dojo::model::model_value::ModelValueParser;
+
+/// The `ModelMigration` trait transforms the values of a record written by a previous version of
+/// a model, declared with `#[dojo::model(version: N)]`, into the values of the current version.
+///
+/// It must be implemented by the model author on the model value type (`<Model>Value`) for every
+/// model with a version greater than 1. The implementation is generated by the `dojo::model`
+/// attribute for models without a version, and panics as there is nothing to migrate.
+pub trait ModelMigration<V> {
+    /// Returns the values of the record migrated from the version `from_version`.
+    ///
+    /// `keys` and `values` are the serialized keys and values of the record, as written by the
+    /// version `from_version` of the model.
+    fn migrate(from_version: u32, keys: Span<felt252>, values: Span<felt252>) -> V;
+}
+
+/// The `IMigratableModel` starknet interface.
+///
+/// This is the interface used by offchain components to migrate the records of a model
+/// after an upgrade.
+#[starknet::interface]
+pub trait IMigratableModel<T> {
+    fn version(self: @T) -> u32;
+    fn migrate_values(
+        self: @T, from_version: u32, keys: Span<felt252>, values: Span<felt252>,
+    ) -> Span<felt252>;
+}
+
+#[starknet::embeddable]
+pub impl IMigratableModelImpl<
+    TContractState, V, +ModelDefinition<V>, +ModelValueParser<V>, +ModelMigration<V>, +Drop<V>,
+> of IMigratableModel<TContractState> {
+    fn version(self: @TContractState) -> u32 {
+        ModelDefinition::<V>::version()
+    }
+
+    fn migrate_values(
+        self: @TContractState, from_version: u32, keys: Span<felt252>, values: Span<felt252>,
+    ) -> Span<felt252> {
+        let version = ModelDefinition::<V>::version();
+
+        if from_version == 0 || from_version >= version {
+            panic!(
+                "Model `{}`: cannot migrate from version {} to version {}.",
+                ModelDefinition::<V>::name(),
+                from_version,
+                version,
+            );
+        }
+
+        let value = ModelMigration::<V>::migrate(from_version, keys, values);
+        ModelValueParser::<V>::serialize_values(@value)
+    }
+}
@@ -79,6 +79,9 @@ pub trait Model<M> {
     fn layout() -> Layout;
     /// Returns the layout of a field in the model.
     fn field_layout(field_selector: felt252) -> Option<Layout>;
+    /// Returns the selector of the group field storing a `#[packed]` member, and the index of the
+    /// member in this group.
+    fn packed_member(field_selector: felt252) -> Option<(felt252, u32)>;
     /// Returns the unpacked size of the model. Only applicable for fixed size models.
     fn unpacked_size() -> Option<usize>;
     /// Returns the packed size of the model. Only applicable for fixed size models.
@@ -146,6 +149,10 @@ pub impl ModelImpl<M, +ModelParser<M>, +ModelDefinition<M>, +Serde<M>, +Drop<M>>
         find_model_field_layout(Self::layout(), field_selector)
     }
 
+    fn packed_member(field_selector: felt252) -> Option<(felt252, u32)> {
+        ModelDefinition::<M>::packed_member(field_selector)
+    }
+
     fn schema() -> Struct {
         ModelDefinition::<M>::schema()
     }
@@ -3,7 +3,8 @@
 use core::panic_with_felt252;
 use dojo::world::{IWorldDispatcher, IWorldDispatcherTrait, Resource};
 use dojo::model::{
-    Model, ModelIndex, ModelValueKey, ModelValue, ModelStorage, ModelPtr, ModelPtrsTrait,
+    Model, ModelIndex, ModelIndexes, ModelValueKey, ModelValue, ModelStorage, ModelPtr,
+    ModelPtrsTrait,
 };
 use dojo::event::{Event, EventStorage};
 use dojo::meta::{Layout, FieldLayout, Introspect};
@@ -37,6 +38,114 @@ fn make_partial_struct_layout<M, +Model<M>>(field_selectors: Span<felt252>) -> L
     Layout::Struct(layouts.span())
 }
 
+/// Reads the serialized value of a model member.
+///
+/// A member declared with `#[packed]` is read from the field of its packed group.
+fn read_serialized_member<M, +Model<M>>(
+    world: IWorldDispatcher, model_selector: felt252, entity_id: felt252, field_selector: felt252,
+) -> Span<felt252> {
+    match Model::<M>::packed_member(field_selector) {
+        Option::Some((group_selector, index)) => {
+            let values = IWorldDispatcherTrait::entity(
+                world,
+                model_selector,
+                ModelIndex::MemberId((entity_id, group_selector)),
+                field_layout_unwrap::<M>(group_selector),
+            );
+            [*values[index]].span()
+        },
+        Option::None => IWorldDispatcherTrait::entity(
+            world,
+            model_selector,
+            ModelIndex::MemberId((entity_id, field_selector)),
+            field_layout_unwrap::<M>(field_selector),
+        ),
+    }
+}
+
+/// Writes the serialized value of a model member.
+///
+/// A member declared with `#[packed]` shares the felts of its packed group, so the whole group is
+/// read and written back with the new member value.
+fn write_serialized_member<M, +Model<M>>(
+    world: IWorldDispatcher,
+    model_selector: felt252,
+    entity_id: felt252,
+    field_selector: felt252,
+    value: Span<felt252>,
+) {
+    match Model::<M>::packed_member(field_selector) {
+        Option::Some((group_selector, index)) => {
+            let layout = field_layout_unwrap::<M>(group_selector);
+            let group_index = ModelIndex::MemberId((entity_id, group_selector));
+
+            let mut group_values = array![];
+            let mut i = 0;
+            for v in IWorldDispatcherTrait::entity(world, model_selector, group_index, layout) {
+                if i == index {
+                    group_values.append_span(value);
+                } else {
+                    group_values.append(*v);
+                }
+                i += 1;
+            };
+
+            IWorldDispatcherTrait::set_entity(
+                world, model_selector, group_index, group_values.span(), layout,
+            );
+        },
+        Option::None => IWorldDispatcherTrait::set_entity(
+            world,
+            model_selector,
+            ModelIndex::MemberId((entity_id, field_selector)),
+            value,
+            field_layout_unwrap::<M>(field_selector),
+        ),
+    }
+}
+
+/// Writes the values of the entities `entity_ids`, without updating their indexes.
+fn set_values<V, +ModelValue<V>>(world: WorldStorage, entity_ids: Span<felt252>, values: Span<@V>) {
+    let mut indexes: Array<ModelIndex> = array![];
+    let mut all_values: Array<Span<felt252>> = array![];
+    let mut i = 0;
+
+    loop {
+        if i >= entity_ids.len() {
+            break;
+        }
+
+        indexes.append(ModelIndex::Id(*entity_ids[i]));
+        all_values.append(ModelValue::<V>::serialized_values(*values[i]));
+
+        i += 1;
+    };
+
+    IWorldDispatcherTrait::set_entities(
+        world.dispatcher,
+        ModelValue::<V>::selector(world.namespace_hash),
+        indexes.span(),
+        all_values.span(),
+        ModelValue::<V>::layout(),
+    );
+}
+
+/// Panics if the member is indexed, since its index entry requires the keys of the entity.
+fn assert_not_indexed<M, +ModelIndexes<M>>(field_selector: felt252) {
+    if ModelIndexes::<M>::is_indexed(field_selector) {
+        panic!(
+            "Model: an indexed member can't be written alone, write the model to update its index.",
+        );
+    }
+}
+
+/// Panics if the model has indexes, since their entries require the keys of the entity.
+fn assert_no_indexes<V, +ModelIndexes<V>>() {
+    if ModelIndexes::<V>::has_indexes() {
+        panic!("Value: the model has indexes, write the value from its keys to update them.");
+    }
+}
+
 #[generate_trait]
 pub impl WorldStorageInternalImpl of WorldStorageTrait {
     fn new(world: IWorldDispatcher, namespace: @ByteArray) -> WorldStorage {
@@ -113,7 +222,9 @@ pub impl EventStorageWorldStorageImpl<E, +Event<E>> of EventStorage<WorldStorage
     }
 }
 
-pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<WorldStorage, M> {
+pub impl ModelStorageWorldStorageImpl<
+    M, +Model<M>, +ModelIndexes<M>, +Drop<M>,
+> of ModelStorage<WorldStorage, M> {
     fn read_model<K, +Drop<K>, +Serde<K>>(self: @WorldStorage, keys: K) -> M {
         let mut keys = serialize_inline::<K>(@keys);
         let mut values = IWorldDispatcherTrait::entity(
@@ -167,10 +278,15 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     }
 
     fn write_model(ref self: WorldStorage, model: @M) {
+        let keys = Model::<M>::serialized_keys(model);
+        ModelIndexes::<
+            M,
+        >::update_indexes(ref self, Model::<M>::entity_id(model), Option::Some((keys, model)));
+
         IWorldDispatcherTrait::set_entity(
             self.dispatcher,
             Model::<M>::selector(self.namespace_hash),
-            ModelIndex::Keys(Model::<M>::serialized_keys(model)),
+            ModelIndex::Keys(keys),
             Model::<M>::serialized_values(model),
             Model::<M>::layout(),
         );
@@ -180,7 +296,10 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
         let mut keys: Array<ModelIndex> = array![];
         let mut values: Array<Span<felt252>> = array![];
         for m in models {
-            keys.append(ModelIndex::Keys(Model::<M>::serialized_keys(*m)));
+            let entity_keys = Model::<M>::serialized_keys(*m);
+            let entity_id = entity_id_from_serialized_keys(entity_keys);
+            ModelIndexes::<M>::update_indexes(ref self, entity_id, Option::Some((entity_keys, *m)));
+            keys.append(ModelIndex::Keys(entity_keys));
             values.append(Model::<M>::serialized_values(*m));
         };
 
@@ -194,6 +313,8 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     }
 
     fn erase_model(ref self: WorldStorage, model: @M) {
+        ModelIndexes::<M>::update_indexes(ref self, Model::<M>::entity_id(model), Option::None);
+
         IWorldDispatcherTrait::delete_entity(
             self.dispatcher,
             Model::<M>::selector(self.namespace_hash),
@@ -205,7 +326,9 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     fn erase_models(ref self: WorldStorage, models: Span<@M>) {
         let mut ids: Array<ModelIndex> = array![];
         for m in models {
-            ids.append(ModelIndex::Id(Model::<M>::entity_id(*m)));
+            let entity_id = Model::<M>::entity_id(*m);
+            ModelIndexes::<M>::update_indexes(ref self, entity_id, Option::None);
+            ids.append(ModelIndex::Id(entity_id));
         };
 
         IWorldDispatcherTrait::delete_entities(
@@ -217,6 +340,8 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     }
 
     fn erase_model_ptr(ref self: WorldStorage, ptr: ModelPtr<M>) {
+        ModelIndexes::<M>::update_indexes(ref self, ptr.id, Option::None);
+
         IWorldDispatcherTrait::delete_entity(
             self.dispatcher,
             Model::<M>::selector(self.namespace_hash),
@@ -228,13 +353,9 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     fn read_member<T, +Serde<T>>(
         self: @WorldStorage, ptr: ModelPtr<M>, field_selector: felt252,
     ) -> T {
+        let model_selector = Model::<M>::selector(*self.namespace_hash);
         deserialize_unwrap(
-            IWorldDispatcherTrait::entity(
-                *self.dispatcher,
-                Model::<M>::selector(*self.namespace_hash),
-                ModelIndex::MemberId((ptr.id, field_selector)),
-                field_layout_unwrap::<M>(field_selector),
-            ),
+            read_serialized_member::<M>(*self.dispatcher, model_selector, ptr.id, field_selector),
         )
     }
 
@@ -242,6 +363,15 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
         self: @WorldStorage, ptrs: Span<ModelPtr<M>>, field_selector: felt252,
     ) -> Array<T> {
         let mut values: Array<T> = array![];
+
+        // packed members are read one by one from the field of their group.
+        if Model::<M>::packed_member(field_selector).is_some() {
+            for ptr in ptrs {
+                values.append(Self::read_member(self, *ptr, field_selector));
+            };
+            return values;
+        }
+
         for entity in IWorldDispatcherTrait::entities(
             *self.dispatcher,
             Model::<M>::selector(*self.namespace_hash),
@@ -256,22 +386,38 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     fn write_member<T, +Serde<T>, +Drop<T>>(
         ref self: WorldStorage, ptr: ModelPtr<M>, field_selector: felt252, value: T,
     ) {
-        IWorldDispatcherTrait::set_entity(
-            self.dispatcher,
-            Model::<M>::selector(self.namespace_hash),
-            ModelIndex::MemberId((ptr.id, field_selector)),
-            serialize_inline(@value),
-            field_layout_unwrap::<M>(field_selector),
-        );
+        assert_not_indexed::<M>(field_selector);
+
+        let model_selector = Model::<M>::selector(self.namespace_hash);
+        write_serialized_member::<
+            M,
+        >(self.dispatcher, model_selector, ptr.id, field_selector, serialize_inline(@value));
     }
 
     fn write_member_of_models<T, +Serde<T>, +Drop<T>>(
         ref self: WorldStorage, ptrs: Span<ModelPtr<M>>, field_selector: felt252, values: Span<T>,
     ) {
+        assert_not_indexed::<M>(field_selector);
+
         let mut serialized_values = ArrayTrait::<Span<felt252>>::new();
         for value in values {
             serialized_values.append(serialize_inline(value));
         };
+
+        // packed members are written one by one into the field of their group.
+        if Model::<M>::packed_member(field_selector).is_some() {
+            let model_selector = Model::<M>::selector(self.namespace_hash);
+            let mut i = 0;
+            for ptr in ptrs {
+                let value = *serialized_values[i];
+                write_serialized_member::<
+                    M,
+                >(self.dispatcher, model_selector, *ptr.id, field_selector, value);
+                i += 1;
+            };
+            return;
+        }
+
         IWorldDispatcherTrait::set_entities(
             self.dispatcher,
             Model::<M>::selector(self.namespace_hash),
@@ -284,6 +430,7 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     fn erase_models_ptrs(ref self: WorldStorage, ptrs: Span<ModelPtr<M>>) {
         let mut indexes: Array<ModelIndex> = array![];
         for ptr in ptrs {
+            ModelIndexes::<M>::update_indexes(ref self, *ptr.id, Option::None);
             indexes.append(ModelIndex::Id(*ptr.id));
         };
 
@@ -328,7 +475,7 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
 }
 
 impl ModelValueStorageWorldStorageImpl<
-    V, +ModelValue<V>, +Drop<V>,
+    V, +ModelValue<V>, +ModelIndexes<V>, +Drop<V>,
 > of dojo::model::ModelValueStorage<WorldStorage, V> {
     fn read_value<K, +Drop<K>, +Serde<K>, +ModelValueKey<V, K>>(self: @WorldStorage, keys: K) -> V {
         Self::read_value_from_id(self, entity_id_from_keys(@keys))
@@ -390,11 +537,15 @@ impl ModelValueStorageWorldStorageImpl<
     fn write_value<K, +Drop<K>, +Serde<K>, +ModelValueKey<V, K>>(
         ref self: WorldStorage, keys: K, value: @V,
     ) {
+        let entity_keys = serialize_inline::<K>(@keys);
+        let entity_id = entity_id_from_serialized_keys(entity_keys);
+        ModelIndexes::<V>::update_indexes(ref self, entity_id, Option::Some((entity_keys, value)));
+
         IWorldDispatcherTrait::set_entity(
             self.dispatcher,
             ModelValue::<V>::selector(self.namespace_hash),
             // We need Id here to trigger the store update event.
-            ModelIndex::Id(entity_id_from_serialized_keys(serialize_inline::<K>(@keys))),
+            ModelIndex::Id(entity_id),
             ModelValue::<V>::serialized_values(value),
             ModelValue::<V>::layout(),
         );
@@ -404,14 +555,23 @@ impl ModelValueStorageWorldStorageImpl<
         ref self: WorldStorage, keys: Span<K>, values: Span<@V>,
     ) {
         let mut ids: Array<felt252> = array![];
+        let mut i = 0;
         for k in keys {
-            ids.append(entity_id_from_keys(k));
+            let entity_keys = serialize_inline::<K>(k);
+            let entity_id = entity_id_from_serialized_keys(entity_keys);
+            ModelIndexes::<
+                V,
+            >::update_indexes(ref self, entity_id, Option::Some((entity_keys, *values[i])));
+            ids.append(entity_id);
+            i += 1;
         };
 
-        Self::write_values_from_ids(ref self, ids.span(), values);
+        set_values::<V>(self, ids.span(), values);
     }
 
     fn write_value_from_id(ref self: WorldStorage, entity_id: felt252, value: @V) {
+        assert_no_indexes::<V>();
+
         IWorldDispatcherTrait::set_entity(
             self.dispatcher,
             ModelValue::<V>::selector(self.namespace_hash),
@@ -422,28 +582,8 @@ impl ModelValueStorageWorldStorageImpl<
     }
 
     fn write_values_from_ids(ref self: WorldStorage, entity_ids: Span<felt252>, values: Span<@V>) {
-        let mut indexes: Array<ModelIndex> = array![];
-        let mut all_values: Array<Span<felt252>> = array![];
-        let mut i = 0;
-
-        loop {
-            if i >= entity_ids.len() {
-                break;
-            }
-
-            indexes.append(ModelIndex::Id(*entity_ids[i]));
-            all_values.append(ModelValue::<V>::serialized_values(*values[i]));
-
-            i += 1;
-        };
-
-        IWorldDispatcherTrait::set_entities(
-            self.dispatcher,
-            ModelValue::<V>::selector(self.namespace_hash),
-            indexes.span(),
-            all_values.span(),
-            ModelValue::<V>::layout(),
-        );
+        assert_no_indexes::<V>();
+        set_values::<V>(self, entity_ids, values);
     }
 }
 
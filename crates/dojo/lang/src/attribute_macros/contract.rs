@@ -12,6 +12,8 @@ use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 use dojo_types::naming;
 
 use crate::aux_data::ContractAuxData;
+use crate::lints::{self, LintConfig};
+use crate::syntax::dependencies::contract_dependencies;
 
 const CONTRACT_PATCH: &str = include_str!("./patches/contract.patch.cairo");
 const DEFAULT_INIT_PATCH: &str = include_str!("./patches/default_init.patch.cairo");
@@ -34,6 +36,7 @@ impl DojoContract {
         db: &dyn SyntaxGroup,
         module_ast: &ast::ItemModule,
         metadata: &MacroPluginMetadata<'_>,
+        lint_config: &LintConfig,
     ) -> PluginResult {
         let name = module_ast.name(db).text(db);
 
@@ -62,6 +65,11 @@ impl DojoContract {
         let mut has_constructor = false;
 
         if let MaybeModuleBody::Some(body) = module_ast.body(db) {
+            let dependencies = contract_dependencies(
+                db,
+                body.iter_items_in_cfg(db, metadata.cfg_set).map(|el| el.as_syntax_node()),
+            );
+
             let mut body_nodes: Vec<_> = body
                 .iter_items_in_cfg(db, metadata.cfg_set)
                 .flat_map(|el| {
@@ -86,7 +94,7 @@ impl DojoContract {
 
                         if fn_name == DOJO_INIT_FN {
                             has_init = true;
-                            return contract.handle_init_fn(db, fn_ast);
+                            return contract.handle_init_fn(db, fn_ast, lint_config);
                         }
                     }
 
@@ -150,6 +158,7 @@ impl DojoContract {
                     aux_data: Some(DynGeneratedFileAuxData::new(ContractAuxData {
                         name: name.to_string(),
                         systems: contract.systems.clone(),
+                        dependencies,
                     })),
                     code_mappings,
                     diagnostics_note: None,
@@ -220,6 +229,7 @@ impl DojoContract {
         &mut self,
         db: &dyn SyntaxGroup,
         fn_ast: &ast::FunctionWithBody,
+        lint_config: &LintConfig,
     ) -> Vec<RewriteNode> {
         let fn_decl = fn_ast.declaration(db);
 
@@ -232,6 +242,8 @@ impl DojoContract {
             });
         }
 
+        self.diagnostics.extend(lints::check_init_params(db, lint_config, fn_ast));
+
         let params: Vec<String> = fn_decl
             .signature(db)
             .parameters(db)
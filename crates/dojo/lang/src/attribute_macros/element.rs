@@ -1,7 +1,8 @@
 use cairo_lang_defs::patcher::RewriteNode;
 use cairo_lang_defs::plugin::PluginDiagnostic;
 use cairo_lang_diagnostics::Severity;
-use cairo_lang_syntax::node::ast::Member as MemberAst;
+use cairo_lang_syntax::attribute::structured::{AttributeArgVariant, AttributeStructurize};
+use cairo_lang_syntax::node::ast::{self, ItemStruct, Member as MemberAst};
 use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::helpers::QueryAttrs;
 use cairo_lang_syntax::node::{Terminal, TypedStablePtr, TypedSyntaxNode};
@@ -36,6 +37,117 @@ pub fn compute_unique_hash(
     poseidon_hash_many(&hashes)
 }
 
+/// The arguments of the `dojo::model` or `dojo::event` attribute of a struct.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResourceArgs {
+    /// The resource name given by the `name` argument, if any.
+    pub name: Option<String>,
+    /// The resource version given by the `version` argument, if any.
+    pub version: Option<u32>,
+}
+
+/// Parses the arguments of the `dojo::model` or `dojo::event` attribute of a struct, and returns
+/// the resource name given by the `name` argument, if any.
+///
+/// The resource name defaults to the struct name, overriding it allows to rename the struct
+/// without changing the selector of the resource.
+pub fn parse_resource_name(
+    db: &dyn SyntaxGroup,
+    struct_ast: &ItemStruct,
+    attr_name: &str,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> Option<String> {
+    parse_resource_args(db, struct_ast, attr_name, false, diagnostics).name
+}
+
+/// Parses the arguments of the `dojo::model` or `dojo::event` attribute of a struct.
+///
+/// The `version` argument is only accepted if `with_version` is true.
+///
+/// An explicit `selector` argument is rejected: the world computes the selector of a resource from
+/// its namespace and name when it's registered, hence a selector chosen in the attribute could not
+/// be honored. Keeping the selector of a renamed struct is done with the `name` argument.
+pub fn parse_resource_args(
+    db: &dyn SyntaxGroup,
+    struct_ast: &ItemStruct,
+    attr_name: &str,
+    with_version: bool,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> ResourceArgs {
+    let mut args = ResourceArgs::default();
+
+    let Some(attr) = struct_ast.attributes(db).query_attr(db, attr_name).into_iter().next() else {
+        return args;
+    };
+
+    for arg in attr.structurize(db).args {
+        let stable_ptr = arg.arg.stable_ptr().untyped();
+
+        match arg.variant {
+            AttributeArgVariant::Named { name, value } if name.name == "name" => {
+                match value {
+                    ast::Expr::String(s) => args.name = s.string_value(db),
+                    _ => diagnostics.push(PluginDiagnostic {
+                        stable_ptr,
+                        message: format!(
+                            "The `name` argument of `{attr_name}` must be a string literal."
+                        ),
+                        severity: Severity::Error,
+                    }),
+                }
+            }
+            AttributeArgVariant::Named { name, value }
+                if with_version && name.name == "version" =>
+            {
+                let version = match &value {
+                    ast::Expr::Literal(l) => l.text(db).parse::<u32>().ok().filter(|v| *v > 0),
+                    _ => None,
+                };
+
+                match version {
+                    Some(version) => args.version = Some(version),
+                    None => diagnostics.push(PluginDiagnostic {
+                        stable_ptr,
+                        message: format!(
+                            "The `version` argument of `{attr_name}` must be a positive integer \
+                             literal."
+                        ),
+                        severity: Severity::Error,
+                    }),
+                }
+            }
+            AttributeArgVariant::Named { name, .. } if name.name == "selector" => {
+                diagnostics.push(PluginDiagnostic {
+                    stable_ptr,
+                    message: format!(
+                        "The selector of `{attr_name}` can't be set explicitly, since it's \
+                         computed by the world from the namespace and the name. Use `name: \
+                         \"<name>\"` to keep the selector when renaming the struct."
+                    ),
+                    severity: Severity::Error,
+                })
+            }
+            _ => diagnostics.push(PluginDiagnostic {
+                stable_ptr,
+                message: if with_version {
+                    format!(
+                        "Unsupported argument for `{attr_name}`, only `name: \"<name>\"` and \
+                         `version: <version>` are supported."
+                    )
+                } else {
+                    format!(
+                        "Unsupported argument for `{attr_name}`, only `name: \"<name>\"` is \
+                         supported."
+                    )
+                },
+                severity: Severity::Error,
+            }),
+        }
+    }
+
+    args
+}
+
 pub fn parse_members(
     db: &dyn SyntaxGroup,
     members: &[MemberAst],
@@ -17,11 +17,15 @@ use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 use dojo_types::naming;
 
-use super::element::{compute_unique_hash, parse_members, serialize_keys_and_values};
+use super::element::{
+    compute_unique_hash, parse_members, parse_resource_name, serialize_keys_and_values,
+};
+use super::DOJO_EVENT_ATTR;
 use crate::aux_data::EventAuxData;
 use crate::derive_macros::{
     extract_derive_attr_names, handle_derive_attrs, DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE,
 };
+use crate::lints::LintConfig;
 
 const EVENT_PATCH: &str = include_str!("./patches/event.patch.cairo");
 
@@ -36,10 +40,16 @@ impl DojoEvent {
     ///
     /// Returns:
     /// * A RewriteNode containing the generated code.
-    pub fn from_struct(db: &dyn SyntaxGroup, struct_ast: ast::ItemStruct) -> PluginResult {
+    pub fn from_struct(
+        db: &dyn SyntaxGroup,
+        struct_ast: ast::ItemStruct,
+        lint_config: &LintConfig,
+    ) -> PluginResult {
         let mut diagnostics = vec![];
 
-        let event_name = struct_ast.name(db).as_syntax_node().get_text(db).trim().to_string();
+        let event_type = struct_ast.name(db).as_syntax_node().get_text(db).trim().to_string();
+        let event_name = parse_resource_name(db, &struct_ast, DOJO_EVENT_ATTR, &mut diagnostics)
+            .unwrap_or(event_type.clone());
 
         for (id, value) in [("name", &event_name)] {
             if !naming::is_name_valid(value) {
@@ -117,8 +127,12 @@ impl DojoEvent {
             derive_attr_names.push(DOJO_INTROSPECT_DERIVE.to_string());
         }
 
-        let (derive_nodes, derive_diagnostics) =
-            handle_derive_attrs(db, &derive_attr_names, &ModuleItem::Struct(struct_ast.clone()));
+        let (derive_nodes, derive_diagnostics) = handle_derive_attrs(
+            db,
+            &derive_attr_names,
+            &ModuleItem::Struct(struct_ast.clone()),
+            lint_config,
+        );
 
         let unique_hash =
             compute_unique_hash(db, &event_name, false, &struct_ast.members(db).elements(db))
@@ -129,7 +143,8 @@ impl DojoEvent {
         let node = RewriteNode::interpolate_patched(
             EVENT_PATCH,
             &UnorderedHashMap::from([
-                ("type_name".to_string(), RewriteNode::Text(event_name.clone())),
+                ("type_name".to_string(), RewriteNode::Text(event_type.clone())),
+                ("event_name".to_string(), RewriteNode::Text(event_name.clone())),
                 ("member_names".to_string(), RewriteNode::new_modified(member_names)),
                 ("serialized_keys".to_string(), RewriteNode::new_modified(serialized_keys)),
                 ("serialized_values".to_string(), RewriteNode::new_modified(serialized_values)),
@@ -148,13 +163,14 @@ impl DojoEvent {
 
         let (code, code_mappings) = builder.build();
 
-        crate::debug_expand(&format!("EVENT PATCH: {event_name}"), &code);
+        crate::debug_expand(&format!("EVENT PATCH: {event_type}"), &code);
 
-        let aux_data = EventAuxData { name: event_name.clone(), members };
+        let aux_data =
+            EventAuxData { name: event_name, struct_name: event_type.clone(), members };
 
         PluginResult {
             code: Some(PluginGeneratedFile {
-                name: event_name.into(),
+                name: event_type.into(),
                 content: code,
                 aux_data: Some(DynGeneratedFileAuxData::new(aux_data)),
                 code_mappings,
@@ -165,3 +181,43 @@ impl DojoEvent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_parser::utils::SimpleParserDatabase;
+
+    use super::*;
+
+    #[test]
+    fn test_event_name_argument() {
+        let db = SimpleParserDatabase::default();
+        let node = db
+            .parse_virtual(
+                r#"
+#[dojo::event(name: "Scored")]
+struct PlayerScored {
+    #[key]
+    id: u32,
+    score: u32,
+}
+"#,
+            )
+            .unwrap();
+
+        let items = ast::SyntaxFile::from_syntax_node(&db, node).items(&db).elements(&db);
+        let Some(ModuleItem::Struct(struct_ast)) = items.into_iter().next() else {
+            panic!("expected a struct");
+        };
+
+        let result = DojoEvent::from_struct(&db, struct_ast, &LintConfig::default());
+        assert!(result.diagnostics.is_empty());
+
+        let file = result.code.unwrap();
+        assert!(file.content.contains("pub mod e_Scored"));
+
+        let aux_data = file.aux_data.unwrap();
+        let aux_data = aux_data.as_any().downcast_ref::<EventAuxData>().unwrap();
+        assert_eq!(aux_data.name, "Scored");
+        assert_eq!(aux_data.struct_name, "PlayerScored");
+    }
+}
@@ -12,6 +12,7 @@ use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 use dojo_types::naming;
 
 use crate::aux_data::ContractAuxData;
+use crate::syntax::dependencies::contract_dependencies;
 
 const LIBRARY_PATCH: &str = include_str!("./patches/library.patch.cairo");
 const CONSTRUCTOR_FN: &str = "constructor";
@@ -61,6 +62,11 @@ impl DojoLibrary {
         let mut has_constructor = false;
 
         if let MaybeModuleBody::Some(body) = module_ast.body(db) {
+            let dependencies = contract_dependencies(
+                db,
+                body.iter_items_in_cfg(db, metadata.cfg_set).map(|el| el.as_syntax_node()),
+            );
+
             let mut body_nodes: Vec<_> = body
                 .iter_items_in_cfg(db, metadata.cfg_set)
                 .flat_map(|el| {
@@ -146,6 +152,7 @@ impl DojoLibrary {
                     aux_data: Some(DynGeneratedFileAuxData::new(ContractAuxData {
                         name: name.to_string(),
                         systems: library.systems.clone(),
+                        dependencies,
                     })),
                     code_mappings,
                     diagnostics_note: None,
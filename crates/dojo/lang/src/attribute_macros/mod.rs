@@ -21,3 +21,5 @@ pub const DOJO_LIBRARY_ATTR: &str = "dojo::library";
 pub const DOJO_INTERFACE_ATTR: &str = "dojo::interface";
 pub const DOJO_MODEL_ATTR: &str = "dojo::model";
 pub const DOJO_EVENT_ATTR: &str = "dojo::event";
+pub const DOJO_INDEX_ATTR: &str = "index";
+pub const DOJO_PACKED_ATTR: &str = "packed";
@@ -7,22 +7,50 @@ use cairo_lang_defs::plugin::{
     DynGeneratedFileAuxData, PluginDiagnostic, PluginGeneratedFile, PluginResult,
 };
 use cairo_lang_diagnostics::Severity;
-use cairo_lang_syntax::node::ast::{ItemStruct, ModuleItem};
+use cairo_lang_syntax::attribute::structured::{AttributeArgVariant, AttributeStructurize};
+use cairo_lang_syntax::node::ast::{self, ItemStruct, ModuleItem};
 use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::helpers::QueryAttrs;
-use cairo_lang_syntax::node::{TypedStablePtr, TypedSyntaxNode};
+use cairo_lang_syntax::node::{Terminal, TypedStablePtr, TypedSyntaxNode};
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 use dojo_types::naming;
 use starknet::core::utils::get_selector_from_name;
 
-use super::element::{compute_unique_hash, parse_members, serialize_member_ty};
+use super::element::{
+    compute_unique_hash, parse_members, parse_resource_args, serialize_member_ty,
+};
+use super::{DOJO_INDEX_ATTR, DOJO_MODEL_ATTR};
 use crate::aux_data::{Member, ModelAuxData};
+use crate::derive_macros::introspect::layout::{parse_packed_member_groups, PackedMemberGroup};
 use crate::derive_macros::{
     extract_derive_attr_names, handle_derive_attrs, DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE,
 };
+use crate::lints::{self, LintConfig};
 
 const MODEL_CODE_PATCH: &str = include_str!("./patches/model.patch.cairo");
 const MODEL_FIELD_CODE_PATCH: &str = include_str!("./patches/model_field_store.patch.cairo");
+const MODEL_INDEX_CODE_PATCH: &str = include_str!("./patches/model_index.patch.cairo");
+const MODEL_INDEX_UPDATE_CODE_PATCH: &str =
+    include_str!("./patches/model_index_update.patch.cairo");
+const MODEL_MIGRATION_CODE_PATCH: &str = include_str!("./patches/model_migration.patch.cairo");
+
+/// The version of a model declared without the `version` argument.
+const DEFAULT_MODEL_VERSION: u32 = 1;
+
+/// The member of an index model storing the keys of the indexed entity.
+const INDEX_ENTITY_KEYS: &str = "entity_keys";
+
+/// An index declared with the `#[index]` attribute on model members.
+///
+/// An index is unique: it maps the values of its members to the keys of the last written entity
+/// with these values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelIndex {
+    /// The index name, which defaults to the member name.
+    pub name: String,
+    /// The indexed members, in declaration order.
+    pub members: Vec<Member>,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct DojoModel {}
@@ -35,12 +63,20 @@ impl DojoModel {
     ///
     /// Returns:
     /// * A RewriteNode containing the generated code.
-    pub fn from_struct(db: &dyn SyntaxGroup, struct_ast: ItemStruct) -> PluginResult {
+    pub fn from_struct(
+        db: &dyn SyntaxGroup,
+        struct_ast: ItemStruct,
+        lint_config: &LintConfig,
+    ) -> PluginResult {
         let mut diagnostics = vec![];
 
         let model_type = struct_ast.name(db).as_syntax_node().get_text(db).trim().to_string();
+        let model_args =
+            parse_resource_args(db, &struct_ast, DOJO_MODEL_ATTR, true, &mut diagnostics);
+        let model_name = model_args.name.unwrap_or(model_type.clone());
+        let model_version = model_args.version.unwrap_or(DEFAULT_MODEL_VERSION);
 
-        for (id, value) in [("name", &model_type)] {
+        for (id, value) in [("name", &model_name)] {
             if !naming::is_name_valid(value) {
                 return PluginResult {
                     code: None,
@@ -122,6 +158,9 @@ impl DojoModel {
                 severity: Severity::Error,
             });
         }
+
+        let indexes = parse_model_indexes(db, &struct_ast, &mut diagnostics);
+
         if !diagnostics.is_empty() {
             return PluginResult { code: None, diagnostics, remove_original_item: false };
         }
@@ -154,19 +193,39 @@ impl DojoModel {
 
         let is_packed = derive_attr_names.contains(&DOJO_PACKED_DERIVE.to_string());
 
-        let (derive_nodes, derive_diagnostics) =
-            handle_derive_attrs(db, &derive_attr_names, &ModuleItem::Struct(struct_ast.clone()));
+        // The invalid `#[packed]` members are reported by the derive of the model layout, handled
+        // below: `Introspect` checks the groups, and `IntrospectPacked` rejects the attribute
+        // since the members of a packed model have no field to share.
+        let packed_groups = if is_packed {
+            vec![]
+        } else {
+            parse_packed_member_groups(db, &mut vec![], &struct_ast)
+        };
+
+        let (derive_nodes, derive_diagnostics) = handle_derive_attrs(
+            db,
+            &derive_attr_names,
+            &ModuleItem::Struct(struct_ast.clone()),
+            lint_config,
+        );
 
         let unique_hash =
-            compute_unique_hash(db, &model_type, is_packed, &struct_ast.members(db).elements(db))
+            compute_unique_hash(db, &model_name, is_packed, &struct_ast.members(db).elements(db))
                 .to_string();
 
         diagnostics.extend(derive_diagnostics);
+        diagnostics.extend(lints::check_model_members(db, lint_config, &struct_ast, &members));
+
+        let index_models = indexes
+            .iter()
+            .map(|index| naming::get_index_model_name(&model_name, &index.name))
+            .collect::<Vec<_>>();
 
         let node = RewriteNode::interpolate_patched(
             MODEL_CODE_PATCH,
             &UnorderedHashMap::from([
                 ("model_type".to_string(), RewriteNode::Text(model_type.clone())),
+                ("model_name".to_string(), RewriteNode::Text(model_name.clone())),
                 ("serialized_keys".to_string(), RewriteNode::new_modified(serialized_keys)),
                 ("serialized_values".to_string(), RewriteNode::new_modified(serialized_values)),
                 ("keys_to_tuple".to_string(), RewriteNode::Text(keys_to_tuple)),
@@ -178,6 +237,61 @@ impl DojoModel {
                     RewriteNode::Text(model_value_derive_attr_names),
                 ),
                 ("unique_hash".to_string(), RewriteNode::Text(unique_hash)),
+                ("model_version".to_string(), RewriteNode::Text(model_version.to_string())),
+                (
+                    "packed_member_param".to_string(),
+                    RewriteNode::Text(
+                        if packed_groups.is_empty() { "_field_selector" } else { "field_selector" }
+                            .to_string(),
+                    ),
+                ),
+                (
+                    "packed_members".to_string(),
+                    RewriteNode::Text(generate_packed_members(&packed_groups)),
+                ),
+                (
+                    "model_migration".to_string(),
+                    generate_model_migration(&model_type, &model_name, model_version),
+                ),
+                (
+                    "update_indexes_params".to_string(),
+                    RewriteNode::Text(generate_update_indexes_params(&indexes)),
+                ),
+                (
+                    "update_indexes".to_string(),
+                    RewriteNode::new_modified(generate_update_indexes(&model_type, &indexes)),
+                ),
+                ("has_indexes".to_string(), RewriteNode::Text((!indexes.is_empty()).to_string())),
+                (
+                    "is_indexed_param".to_string(),
+                    RewriteNode::Text(
+                        if indexes.is_empty() { "_field_selector" } else { "field_selector" }
+                            .to_string(),
+                    ),
+                ),
+                (
+                    "indexed_members".to_string(),
+                    RewriteNode::Text(generate_indexed_members(&indexes)),
+                ),
+                (
+                    "index_models".to_string(),
+                    RewriteNode::Text(
+                        index_models
+                            .iter()
+                            .map(|m| format!("\"{m}\""))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                ),
+                (
+                    "model_indexes".to_string(),
+                    RewriteNode::new_modified(
+                        indexes
+                            .iter()
+                            .map(|index| generate_index_model(&model_type, &model_name, index))
+                            .collect(),
+                    ),
+                ),
             ]),
         );
 
@@ -193,7 +307,12 @@ impl DojoModel {
 
         crate::debug_expand(&format!("MODEL PATCH: {model_type}"), &code);
 
-        let aux_data = ModelAuxData { name: model_type.clone(), members };
+        let aux_data = ModelAuxData {
+            name: model_name,
+            struct_name: model_type.clone(),
+            members,
+            indexes: index_models,
+        };
 
         PluginResult {
             code: Some(PluginGeneratedFile {
@@ -237,3 +356,537 @@ fn generate_field_accessors(model_type: String, member: &Member) -> RewriteNode
         ]),
     )
 }
+
+/// Generates the default `ModelMigration` implementation of a model without previous version.
+///
+/// Versioned models must implement `ModelMigration` on their model value type, to transform the
+/// records written by the previous versions.
+fn generate_model_migration(model_type: &str, model_name: &str, version: u32) -> RewriteNode {
+    if version > DEFAULT_MODEL_VERSION {
+        return RewriteNode::Text("".to_string());
+    }
+
+    RewriteNode::interpolate_patched(
+        MODEL_MIGRATION_CODE_PATCH,
+        &UnorderedHashMap::from([
+            ("model_type".to_string(), RewriteNode::Text(model_type.to_string())),
+            ("model_name".to_string(), RewriteNode::Text(model_name.to_string())),
+        ]),
+    )
+}
+
+/// Generates the body of `ModelDefinition::packed_member`, mapping each `#[packed]` member to
+/// its packed group field and its index in the group.
+fn generate_packed_members(groups: &[PackedMemberGroup]) -> String {
+    let mut body = String::new();
+
+    for group in groups {
+        let group_selector =
+            get_selector_from_name(&group.members[0]).expect("invalid member name").to_string();
+
+        for (index, member) in group.members.iter().enumerate() {
+            let selector = get_selector_from_name(member).expect("invalid member name");
+            body.push_str(&format!(
+                "if field_selector == {selector} {{
+                return Option::Some(({group_selector}, {index}));
+            }}\n"
+            ));
+        }
+    }
+
+    body.push_str("Option::None");
+    body
+}
+
+/// Parses the `#[index]` attributes of the model members.
+///
+/// A member is indexed on its own with `#[index]`, and members sharing the same
+/// `#[index(name: "<name>")]` are indexed together.
+pub fn parse_model_indexes(
+    db: &dyn SyntaxGroup,
+    struct_ast: &ItemStruct,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> Vec<ModelIndex> {
+    let mut indexes: Vec<ModelIndex> = vec![];
+
+    for member_ast in struct_ast.members(db).elements(db) {
+        let member = Member {
+            name: member_ast.name(db).text(db).to_string(),
+            ty: member_ast.type_clause(db).ty(db).as_syntax_node().get_text(db).trim().to_string(),
+            key: member_ast.has_attr(db, "key"),
+        };
+
+        for attr in member_ast.query_attr(db, DOJO_INDEX_ATTR) {
+            let stable_ptr = attr.stable_ptr().untyped();
+
+            if member.key {
+                diagnostics.push(PluginDiagnostic {
+                    stable_ptr,
+                    message: "Key members can't be indexed, they already address the model."
+                        .into(),
+                    severity: Severity::Error,
+                });
+                continue;
+            }
+
+            if member.name == INDEX_ENTITY_KEYS {
+                diagnostics.push(PluginDiagnostic {
+                    stable_ptr,
+                    message: format!("The member name `{INDEX_ENTITY_KEYS}` can't be indexed."),
+                    severity: Severity::Error,
+                });
+                continue;
+            }
+
+            let Some(name) = parse_index_name(db, &attr, diagnostics) else {
+                continue;
+            };
+            let name = name.unwrap_or(member.name.clone());
+
+            if !naming::is_name_valid(&name) {
+                diagnostics.push(PluginDiagnostic {
+                    stable_ptr,
+                    message: format!(
+                        "The index name '{name}' can only contain characters (a-z/A-Z), digits \
+                         (0-9) and underscore (_)."
+                    ),
+                    severity: Severity::Error,
+                });
+                continue;
+            }
+
+            match indexes.iter_mut().find(|index| index.name == name) {
+                Some(index) if index.members.contains(&member) => {}
+                Some(index) => index.members.push(member.clone()),
+                None => indexes.push(ModelIndex { name, members: vec![member.clone()] }),
+            }
+        }
+    }
+
+    indexes
+}
+
+/// Parses the arguments of an `#[index]` attribute, returning the index name if any, or `None`
+/// if the arguments are invalid.
+fn parse_index_name(
+    db: &dyn SyntaxGroup,
+    attr: &ast::Attribute,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> Option<Option<String>> {
+    let mut name = None;
+
+    for arg in attr.structurize(db).args {
+        match arg.variant {
+            AttributeArgVariant::Named { name: arg_name, value: ast::Expr::String(s) }
+                if arg_name.name == "name" =>
+            {
+                name = s.string_value(db);
+            }
+            _ => {
+                diagnostics.push(PluginDiagnostic {
+                    stable_ptr: arg.arg.stable_ptr().untyped(),
+                    message: format!(
+                        "Unsupported argument for `{DOJO_INDEX_ATTR}`, only `name: \"<name>\"` is \
+                         supported."
+                    ),
+                    severity: Severity::Error,
+                });
+                return None;
+            }
+        }
+    }
+
+    Some(name)
+}
+
+/// Generates the parameters of `ModelIndexes::update_indexes`, unused without index. The type of
+/// the `entry` parameter is set by the patch, for the model and its value type.
+fn generate_update_indexes_params(indexes: &[ModelIndex]) -> String {
+    let prefix = if indexes.is_empty() { "_" } else { "" };
+
+    format!(
+        "ref {prefix}world: dojo::world::WorldStorage, {prefix}entity_id: felt252, {prefix}entry"
+    )
+}
+
+/// Generates the body of `ModelIndexes::is_indexed`, true for the members of any index.
+fn generate_indexed_members(indexes: &[ModelIndex]) -> String {
+    let mut selectors: Vec<String> = vec![];
+
+    for member in indexes.iter().flat_map(|index| index.members.iter()) {
+        let selector = get_selector_from_name(&member.name).expect("invalid member name");
+
+        if !selectors.contains(&selector.to_string()) {
+            selectors.push(selector.to_string());
+        }
+    }
+
+    if selectors.is_empty() {
+        return "false".to_string();
+    }
+
+    selectors.iter().map(|s| format!("field_selector == {s}")).collect::<Vec<_>>().join(" || ")
+}
+
+/// Generates the body of `ModelIndexes::update_indexes`, which removes the index entries of the
+/// previous values of the entity, and adds the entries of the new values.
+fn generate_update_indexes(model_type: &str, indexes: &[ModelIndex]) -> Vec<RewriteNode> {
+    if indexes.is_empty() {
+        return vec![];
+    }
+
+    let mut nodes = vec![RewriteNode::Text(format!(
+        "let previous = @dojo::model::ModelValueStorage::<dojo::world::WorldStorage, \
+         {model_type}Value>::read_value_from_id(@world, entity_id);\n"
+    ))];
+
+    for index in indexes {
+        let previous_args = index
+            .members
+            .iter()
+            .map(|m| format!("core::clone::Clone::clone(previous.{})", m.name))
+            .collect::<Vec<_>>();
+
+        let model_args = index
+            .members
+            .iter()
+            .map(|m| format!("{}: core::clone::Clone::clone(model.{}),\n", m.name, m.name))
+            .collect::<String>();
+
+        nodes.push(RewriteNode::interpolate_patched(
+            MODEL_INDEX_UPDATE_CODE_PATCH,
+            &UnorderedHashMap::from([
+                (
+                    "index_model_type".to_string(),
+                    RewriteNode::Text(naming::get_index_model_name(model_type, &index.name)),
+                ),
+                ("previous_args".to_string(), RewriteNode::Text(args_to_tuple(&previous_args))),
+                ("model_args".to_string(), RewriteNode::Text(model_args)),
+            ]),
+        ));
+    }
+
+    nodes
+}
+
+/// Generates the model storing an index, and its lookup function on `WorldStorage`.
+fn generate_index_model(model_type: &str, model_name: &str, index: &ModelIndex) -> RewriteNode {
+    let index_members = index
+        .members
+        .iter()
+        .map(|m| format!("#[key]\npub {}: {},\n", m.name, m.ty))
+        .collect::<String>();
+
+    let index_params =
+        index.members.iter().map(|m| format!("{}: {}", m.name, m.ty)).collect::<Vec<_>>();
+
+    let index_args = index.members.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+
+    RewriteNode::interpolate_patched(
+        MODEL_INDEX_CODE_PATCH,
+        &UnorderedHashMap::from([
+            ("model_type".to_string(), RewriteNode::Text(model_type.to_string())),
+            ("model_snake_name".to_string(), RewriteNode::Text(to_snake_case(model_type))),
+            ("index_name".to_string(), RewriteNode::Text(index.name.clone())),
+            (
+                "index_model_type".to_string(),
+                RewriteNode::Text(naming::get_index_model_name(model_type, &index.name)),
+            ),
+            (
+                "index_model_name".to_string(),
+                RewriteNode::Text(naming::get_index_model_name(model_name, &index.name)),
+            ),
+            ("index_members".to_string(), RewriteNode::Text(index_members)),
+            ("index_params".to_string(), RewriteNode::Text(index_params.join(", "))),
+            ("index_args".to_string(), RewriteNode::Text(args_to_tuple(&index_args))),
+        ]),
+    )
+}
+
+/// Returns the single argument as is, or a tuple of the arguments.
+fn args_to_tuple(args: &[String]) -> String {
+    if args.len() == 1 { args[0].clone() } else { format!("({})", args.join(", ")) }
+}
+
+/// Converts a type name to snake case, `PlayerConfig` becoming `player_config`.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lowercase = false;
+
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lowercase {
+            snake.push('_');
+        }
+
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_parser::utils::SimpleParserDatabase;
+
+    use super::*;
+
+    fn parse_struct(db: &SimpleParserDatabase, content: &str) -> ItemStruct {
+        let node = db.parse_virtual(content).unwrap();
+        let items = ast::SyntaxFile::from_syntax_node(db, node).items(db).elements(db);
+
+        match items.into_iter().next() {
+            Some(ModuleItem::Struct(struct_ast)) => struct_ast,
+            _ => panic!("expected a struct"),
+        }
+    }
+
+    #[test]
+    fn test_parse_model_indexes() {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+struct Position {
+    #[key]
+    player: ContractAddress,
+    #[index(name: "tile")]
+    x: u32,
+    #[index(name: "tile")]
+    y: u32,
+    #[index]
+    name: ByteArray,
+}
+"#,
+        );
+
+        let mut diagnostics = vec![];
+        let indexes = parse_model_indexes(&db, &struct_ast, &mut diagnostics);
+        assert!(diagnostics.is_empty());
+
+        let member = |name: &str, ty: &str| Member { name: name.into(), ty: ty.into(), key: false };
+        assert_eq!(
+            indexes,
+            vec![
+                ModelIndex {
+                    name: "tile".into(),
+                    members: vec![member("x", "u32"), member("y", "u32")]
+                },
+                ModelIndex { name: "name".into(), members: vec![member("name", "ByteArray")] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_model_indexes_on_key() {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+struct Position {
+    #[key]
+    #[index]
+    player: ContractAddress,
+    x: u32,
+}
+"#,
+        );
+
+        let mut diagnostics = vec![];
+        assert!(parse_model_indexes(&db, &struct_ast, &mut diagnostics).is_empty());
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_generate_indexed_members() {
+        assert_eq!(generate_indexed_members(&[]), "false");
+
+        let member = |name: &str| Member { name: name.into(), ty: "u32".into(), key: false };
+        let indexes = vec![
+            ModelIndex { name: "tile".into(), members: vec![member("x"), member("y")] },
+            ModelIndex { name: "x".into(), members: vec![member("x")] },
+        ];
+
+        let x = get_selector_from_name("x").unwrap();
+        let y = get_selector_from_name("y").unwrap();
+        assert_eq!(
+            generate_indexed_members(&indexes),
+            format!("field_selector == {x} || field_selector == {y}")
+        );
+    }
+
+    #[test]
+    fn test_parse_model_version() {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+#[dojo::model(name: "Pos", version: 2)]
+struct Position {
+    #[key]
+    player: ContractAddress,
+    x: u32,
+}
+"#,
+        );
+
+        let mut diagnostics = vec![];
+        let args = parse_resource_args(&db, &struct_ast, DOJO_MODEL_ATTR, true, &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(args.name, Some("Pos".to_string()));
+        assert_eq!(args.version, Some(2));
+
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+#[dojo::model(version: 0)]
+struct Position {
+    #[key]
+    player: ContractAddress,
+    x: u32,
+}
+"#,
+        );
+
+        let args = parse_resource_args(&db, &struct_ast, DOJO_MODEL_ATTR, true, &mut diagnostics);
+
+        assert_eq!(args.version, None);
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_model_name_argument() {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+#[dojo::model(name: "PlayerV2")]
+struct Player {
+    #[key]
+    id: u32,
+    score: u32,
+}
+"#,
+        );
+
+        let result = DojoModel::from_struct(&db, struct_ast, &LintConfig::default());
+        assert!(result.diagnostics.is_empty());
+
+        let file = result.code.unwrap();
+        assert!(file.content.contains("\"PlayerV2\""));
+        assert!(file.content.contains("pub struct PlayerValue"));
+
+        let aux_data = file.aux_data.unwrap();
+        let aux_data = aux_data.as_any().downcast_ref::<ModelAuxData>().unwrap();
+        assert_eq!(aux_data.name, "PlayerV2");
+        assert_eq!(aux_data.struct_name, "Player");
+    }
+
+    #[test]
+    fn test_model_selector_argument() {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+#[dojo::model(selector: 0x1234)]
+struct Player {
+    #[key]
+    id: u32,
+    score: u32,
+}
+"#,
+        );
+
+        let result = DojoModel::from_struct(&db, struct_ast, &LintConfig::default());
+
+        assert!(result.code.is_none());
+        assert_eq!(result.diagnostics.len(), 1);
+        assert!(result.diagnostics[0].message.contains("can't be set explicitly"));
+    }
+
+    #[test]
+    fn test_packed_member_of_packed_model() {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+#[derive(IntrospectPacked)]
+#[dojo::model]
+struct Player {
+    #[key]
+    id: u32,
+    #[packed]
+    alive: bool,
+    level: u8,
+}
+"#,
+        );
+
+        let result = DojoModel::from_struct(&db, struct_ast, &LintConfig::default());
+
+        assert_eq!(result.diagnostics.len(), 1);
+        assert!(result.diagnostics[0].message.contains("can't be used with `IntrospectPacked`"));
+    }
+
+    #[test]
+    fn test_parse_packed_member_groups() {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = parse_struct(
+            &db,
+            r#"
+struct Flags {
+    #[key]
+    player: ContractAddress,
+    #[packed]
+    alive: bool,
+    #[packed]
+    level: u8,
+    name: ByteArray,
+    #[packed]
+    x: u16,
+    #[packed]
+    y: u16,
+    #[packed]
+    position: u256,
+    #[packed]
+    z: u32,
+}
+"#,
+        );
+
+        let mut diagnostics = vec![];
+        let groups = parse_packed_member_groups(&db, &mut diagnostics, &struct_ast);
+
+        // u256 can't be packed, and splits the group.
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            groups,
+            vec![
+                PackedMemberGroup {
+                    members: vec!["alive".into(), "level".into()],
+                    bits: vec![1, 8],
+                },
+                PackedMemberGroup { members: vec!["x".into(), "y".into()], bits: vec![16, 16] },
+                PackedMemberGroup { members: vec!["z".into()], bits: vec![32] },
+            ]
+        );
+
+        let members = generate_packed_members(&groups[..1]);
+        let alive = get_selector_from_name("alive").unwrap();
+        let level = get_selector_from_name("level").unwrap();
+
+        assert!(members.contains(&format!(
+            "if field_selector == {level} {{
+                return Option::Some(({alive}, 1));"
+        )));
+        assert!(members.ends_with("Option::None"));
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Position"), "position");
+        assert_eq!(to_snake_case("PlayerConfig"), "player_config");
+        assert_eq!(to_snake_case("ERC20Balance"), "erc20_balance");
+    }
+}
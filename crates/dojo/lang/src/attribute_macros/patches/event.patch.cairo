@@ -10,7 +10,7 @@ pub struct $type_name$Value {
 pub impl $type_name$Definition of dojo::event::EventDefinition<$type_name$>{
     #[inline(always)]
     fn name() -> ByteArray {
-        "$type_name$"
+        "$event_name$"
     }
 }
 
@@ -30,7 +30,7 @@ pub impl $type_name$ModelParser of dojo::model::model::ModelParser<$type_name$>{
 pub impl $type_name$EventImpl = dojo::event::event::EventImpl<$type_name$>;
 
 #[starknet::contract]
-pub mod e_$type_name$ {
+pub mod e_$event_name$ {
     use super::$type_name$;
     use super::$type_name$Value;
 
@@ -38,13 +38,13 @@ pub mod e_$type_name$ {
     struct Storage {}
 
     #[abi(embed_v0)]
-    impl $type_name$__DeployedEventImpl = dojo::event::component::IDeployedEventImpl<ContractState, $type_name$>;
+    impl $event_name$__DeployedEventImpl = dojo::event::component::IDeployedEventImpl<ContractState, $type_name$>;
 
     #[abi(embed_v0)]
-    impl $type_name$__StoredEventImpl = dojo::event::component::IStoredEventImpl<ContractState, $type_name$>;
+    impl $event_name$__StoredEventImpl = dojo::event::component::IStoredEventImpl<ContractState, $type_name$>;
 
      #[abi(embed_v0)]
-    impl $type_name$__EventImpl = dojo::event::component::IEventImpl<ContractState, $type_name$>;
+    impl $event_name$__EventImpl = dojo::event::component::IEventImpl<ContractState, $type_name$>;
 
     #[abi(per_item)]
     #[generate_trait]
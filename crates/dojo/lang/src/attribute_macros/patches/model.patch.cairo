@@ -21,7 +21,7 @@ pub mod m_$model_type$_definition {
     pub impl $model_type$DefinitionImpl<T> of dojo::model::ModelDefinition<T>{
         #[inline(always)]
         fn name() -> ByteArray {
-            "$model_type$"
+            "$model_name$"
         }
 
         #[inline(always)]
@@ -43,6 +43,16 @@ pub mod m_$model_type$_definition {
         fn size() -> Option<usize> {
             dojo::meta::Introspect::<$model_type$>::size()
         }
+
+        #[inline(always)]
+        fn version() -> u32 {
+            $model_version$
+        }
+
+        #[inline(always)]
+        fn packed_member($packed_member_param$: felt252) -> Option<(felt252, u32)> {
+            $packed_members$
+        }
     }
 }
 
@@ -70,11 +80,43 @@ pub impl $model_type$ModelValueParser of dojo::model::model_value::ModelValuePar
     }
 }
 
+pub impl $model_type$ModelIndexesImpl of dojo::model::ModelIndexes<$model_type$> {
+    fn update_indexes($update_indexes_params$: Option<(Span<felt252>, @$model_type$)>) {
+        $update_indexes$
+    }
+
+    fn has_indexes() -> bool {
+        $has_indexes$
+    }
+
+    fn is_indexed($is_indexed_param$: felt252) -> bool {
+        $indexed_members$
+    }
+}
+
+pub impl $model_type$ModelValueIndexesImpl of dojo::model::ModelIndexes<$model_type$Value> {
+    fn update_indexes($update_indexes_params$: Option<(Span<felt252>, @$model_type$Value)>) {
+        $update_indexes$
+    }
+
+    fn has_indexes() -> bool {
+        $has_indexes$
+    }
+
+    fn is_indexed($is_indexed_param$: felt252) -> bool {
+        $indexed_members$
+    }
+}
+
+$model_indexes$
+
+$model_migration$
+
 pub impl $model_type$ModelImpl = dojo::model::model::ModelImpl<$model_type$>;
 pub impl $model_type$ModelValueImpl = dojo::model::model_value::ModelValueImpl<$model_type$Value>;
 
 #[starknet::contract]
-pub mod m_$model_type$ {
+pub mod m_$model_name$ {
     use super::$model_type$;
     use super::$model_type$Value;
 
@@ -82,13 +124,16 @@ pub mod m_$model_type$ {
     struct Storage {}
 
     #[abi(embed_v0)]
-    impl $model_type$__DojoDeployedModelImpl = dojo::model::component::IDeployedModelImpl<ContractState, $model_type$>;
+    impl $model_name$__DojoDeployedModelImpl = dojo::model::component::IDeployedModelImpl<ContractState, $model_type$>;
+
+    #[abi(embed_v0)]
+    impl $model_name$__DojoStoredModelImpl = dojo::model::component::IStoredModelImpl<ContractState, $model_type$>;
 
     #[abi(embed_v0)]
-    impl $model_type$__DojoStoredModelImpl = dojo::model::component::IStoredModelImpl<ContractState, $model_type$>;
+    impl $model_name$__DojoModelImpl = dojo::model::component::IModelImpl<ContractState, $model_type$>;
 
     #[abi(embed_v0)]
-    impl $model_type$__DojoModelImpl = dojo::model::component::IModelImpl<ContractState, $model_type$>;
+    impl $model_name$__DojoMigratableModelImpl = dojo::model::migration::IMigratableModelImpl<ContractState, $model_type$Value>;
 
     #[abi(per_item)]
     #[generate_trait]
@@ -113,5 +158,12 @@ pub mod m_$model_type$ {
         fn ensure_unique(self: @ContractState) {
             let _hash = $unique_hash$;
         }
+
+        // Returns the names of the models storing the indexes of the model, declared with the
+        // `#[index]` attribute on its members.
+        #[external(v0)]
+        fn indexes(self: @ContractState) -> Span<ByteArray> {
+            array![$index_models$].span()
+        }
     }
 }
@@ -0,0 +1,22 @@
+#[derive(Drop, Serde)]
+#[dojo::model(name: "$index_model_name$")]
+pub struct $index_model_type$ {
+    $index_members$
+    pub entity_keys: Span<felt252>,
+}
+
+#[generate_trait]
+pub impl $index_model_type$LookupImpl of $index_model_type$LookupTrait {
+    // Returns the `$model_type$` indexed by the given values, if any.
+    fn read_$model_snake_name$_by_$index_name$(self: @dojo::world::WorldStorage, $index_params$) -> Option<$model_type$> {
+        let index: $index_model_type$ = dojo::model::ModelStorage::<dojo::world::WorldStorage, $index_model_type$>::read_model(self, $index_args$);
+        let mut entity_keys = index.entity_keys;
+
+        if core::array::SpanTrait::len(entity_keys) == 0 {
+            return Option::None;
+        }
+
+        let keys = core::serde::Serde::<$model_type$KeyType>::deserialize(ref entity_keys)?;
+        Option::Some(dojo::model::ModelStorage::<dojo::world::WorldStorage, $model_type$>::read_model(self, keys))
+    }
+}
@@ -0,0 +1,16 @@
+{
+    let previous_index: $index_model_type$ = dojo::model::ModelStorage::<dojo::world::WorldStorage, $index_model_type$>::read_model(@world, $previous_args$);
+
+    // The entry of the previous value may have been overwritten by another entity.
+    if dojo::utils::entity_id_from_serialized_keys(previous_index.entity_keys) == entity_id {
+        dojo::model::ModelStorage::<dojo::world::WorldStorage, $index_model_type$>::erase_model(ref world, @previous_index);
+    }
+
+    if let Option::Some((entity_keys, model)) = entry {
+        let index = $index_model_type$ {
+            $model_args$
+            entity_keys,
+        };
+        dojo::model::ModelStorage::<dojo::world::WorldStorage, $index_model_type$>::write_model(ref world, @index);
+    }
+}
@@ -0,0 +1,7 @@
+pub impl $model_type$ModelMigrationImpl of dojo::model::ModelMigration<$model_type$Value> {
+    fn migrate(
+        _from_version: u32, _keys: Span<felt252>, _values: Span<felt252>,
+    ) -> $model_type$Value {
+        panic!("Model `$model_name$`: no previous version to migrate from.")
+    }
+}
@@ -1,9 +1,13 @@
 //! Auxiliary data for Dojo generated files.
 //!
 //! The plugin generates aux data for models, contracts and events.
-//! Then the compiler uses this aux data to generate the manifests and organize the artifacts.
+//! Then the compiler uses this aux data to generate the manifests and organize the artifacts,
+//! and sozo uses the contracts dependencies to check their writer permissions.
 
+use cairo_lang_defs::db::DefsGroup;
 use cairo_lang_defs::plugin::GeneratedFileAuxData;
+use cairo_lang_filesystem::ids::CrateId;
+use dojo_types::system::Dependency;
 use serde::{Deserialize, Serialize};
 
 /// Represents a member of a struct.
@@ -21,21 +25,65 @@ pub struct Member {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ModelAuxData {
     pub name: String,
+    /// Name of the model struct, which the contracts refer to.
+    pub struct_name: String,
     pub members: Vec<Member>,
+    /// Names of the models storing the indexes of the model.
+    pub indexes: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContractAuxData {
     pub name: String,
     pub systems: Vec<String>,
+    /// Models and events read and written by the contract, by type path as written in the
+    /// contract.
+    pub dependencies: Vec<Dependency>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct EventAuxData {
     pub name: String,
+    /// Name of the event struct, which the contracts refer to.
+    pub struct_name: String,
     pub members: Vec<Member>,
 }
 
+/// The aux data generated by the plugin for the models, events and contracts of crates. The models
+/// and events come with the full path of the module defining them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DojoAuxData {
+    pub models: Vec<(String, ModelAuxData)>,
+    pub events: Vec<(String, EventAuxData)>,
+    pub contracts: Vec<ContractAuxData>,
+}
+
+impl DojoAuxData {
+    /// Collects the aux data of the files generated for all the modules of the given crate.
+    pub fn extend_from_crate(&mut self, db: &dyn DefsGroup, crate_id: CrateId) {
+        for module_id in db.crate_modules(crate_id).iter() {
+            // Modules that fail to load are reported by the compilation.
+            let Ok(aux_data) = db.module_generated_file_aux_data(*module_id) else {
+                continue;
+            };
+
+            let module_path = module_id.full_path(db);
+
+            for aux_data in aux_data.iter().flatten() {
+                let aux_data = aux_data.as_any();
+
+                if let Some(model) = aux_data.downcast_ref::<ModelAuxData>() {
+                    self.models.push((module_path.clone(), model.clone()));
+                } else if let Some(event) = aux_data.downcast_ref::<EventAuxData>() {
+                    self.events.push((module_path.clone(), event.clone()));
+                } else if let Some(contract) = aux_data.downcast_ref::<ContractAuxData>() {
+                    self.contracts.push(contract.clone());
+                }
+            }
+        }
+    }
+}
+
 impl GeneratedFileAuxData for ModelAuxData {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -6,15 +6,19 @@ use cairo_lang_diagnostics::Severity;
 use cairo_lang_semantic::plugin::PluginSuite;
 use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::helpers::QueryAttrs;
-use cairo_lang_syntax::node::{ast, Terminal, TypedSyntaxNode};
+use cairo_lang_syntax::node::{ast, Terminal, TypedStablePtr, TypedSyntaxNode};
 
 use super::attribute_macros::{
-    DojoContract, DojoEvent, DojoModel, DOJO_CONTRACT_ATTR, DOJO_EVENT_ATTR, DOJO_LIBRARY_ATTR,
-    DOJO_MODEL_ATTR,
+    DojoContract, DojoEvent, DojoModel, DOJO_CONTRACT_ATTR, DOJO_EVENT_ATTR, DOJO_INDEX_ATTR,
+    DOJO_LIBRARY_ATTR, DOJO_MODEL_ATTR, DOJO_PACKED_ATTR,
 };
 use super::derive_macros::{dojo_derive_all, DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE};
-use super::inline_macros::{BytearrayHashMacro, SelectorFromTagMacro};
+use super::inline_macros::{
+    BytearrayHashMacro, DeleteMacro, EmitMacro, GetMacro, GetModelsTestClassHashes,
+    SelectorFromTagMacro, SetMacro, SpawnTestWorld,
+};
 use crate::attribute_macros::DojoLibrary;
+use crate::lints::LintConfigs;
 
 // #[cfg(test)]
 // #[path = "plugin_test.rs"]
@@ -22,7 +26,9 @@ use crate::attribute_macros::DojoLibrary;
 pub const DOJO_PLUGIN_PACKAGE_NAME: &str = "dojo_plugin";
 
 #[derive(Debug, Default)]
-pub struct BuiltinDojoPlugin;
+pub struct BuiltinDojoPlugin {
+    lint_configs: LintConfigs,
+}
 
 pub fn dojo_plugin_suite() -> PluginSuite {
     let mut suite = PluginSuite::default();
@@ -30,7 +36,13 @@ pub fn dojo_plugin_suite() -> PluginSuite {
     suite
         .add_plugin::<BuiltinDojoPlugin>()
         .add_inline_macro_plugin::<SelectorFromTagMacro>()
-        .add_inline_macro_plugin::<BytearrayHashMacro>();
+        .add_inline_macro_plugin::<BytearrayHashMacro>()
+        .add_inline_macro_plugin::<GetMacro>()
+        .add_inline_macro_plugin::<SetMacro>()
+        .add_inline_macro_plugin::<DeleteMacro>()
+        .add_inline_macro_plugin::<EmitMacro>()
+        .add_inline_macro_plugin::<SpawnTestWorld>()
+        .add_inline_macro_plugin::<GetModelsTestClassHashes>();
 
     suite
 }
@@ -58,26 +70,37 @@ impl MacroPlugin for BuiltinDojoPlugin {
         // so that it can be used here.
         // let namespace_config = metadata.cfg_set.into();
 
-        match &item_ast {
+        // The invalid lint levels are reported on the first item of the package.
+        let (lint_config, lint_diagnostics) = match &item_ast {
+            ast::ModuleItem::Module(_) | ast::ModuleItem::Enum(_) | ast::ModuleItem::Struct(_) => {
+                self.lint_configs.get(db, item_ast.stable_ptr().untyped())
+            }
+            _ => return PluginResult::default(),
+        };
+
+        let mut result = match &item_ast {
             ast::ModuleItem::Module(module_ast) => {
                 if module_ast.has_attr(db, DOJO_CONTRACT_ATTR) {
-                    DojoContract::from_module(db, module_ast, metadata)
+                    DojoContract::from_module(db, module_ast, metadata, &lint_config)
                 } else if module_ast.has_attr(db, DOJO_LIBRARY_ATTR) {
                     DojoLibrary::from_module(db, module_ast, metadata)
                 } else {
                     PluginResult::default()
                 }
             }
-            ast::ModuleItem::Enum(enum_ast) => {
-                dojo_derive_all(db, enum_ast.attributes(db).query_attr(db, "derive"), &item_ast)
-            }
+            ast::ModuleItem::Enum(enum_ast) => dojo_derive_all(
+                db,
+                enum_ast.attributes(db).query_attr(db, "derive"),
+                &item_ast,
+                &lint_config,
+            ),
             ast::ModuleItem::Struct(struct_ast) => {
                 let n_model_attrs = struct_ast.attributes(db).query_attr(db, DOJO_MODEL_ATTR).len();
 
                 let n_event_attrs = struct_ast.attributes(db).query_attr(db, DOJO_EVENT_ATTR).len();
 
                 if n_model_attrs > 0 && n_event_attrs > 0 {
-                    return PluginResult::diagnostic_only(PluginDiagnostic {
+                    PluginResult::diagnostic_only(PluginDiagnostic {
                         stable_ptr: struct_ast.stable_ptr().0,
                         message: format!(
                             "The struct {} can only have one of the dojo::model or one \
@@ -85,18 +108,26 @@ impl MacroPlugin for BuiltinDojoPlugin {
                             struct_ast.name(db).text(db)
                         ),
                         severity: Severity::Error,
-                    });
+                    })
                 } else if n_model_attrs == 1 {
-                    return DojoModel::from_struct(db, struct_ast.clone());
+                    DojoModel::from_struct(db, struct_ast.clone(), &lint_config)
                 } else if n_event_attrs == 1 {
-                    return DojoEvent::from_struct(db, struct_ast.clone());
+                    DojoEvent::from_struct(db, struct_ast.clone(), &lint_config)
+                } else {
+                    // Not a model or event, but has derives.
+                    dojo_derive_all(
+                        db,
+                        struct_ast.attributes(db).query_attr(db, "derive"),
+                        &item_ast,
+                        &lint_config,
+                    )
                 }
-
-                // Not a model or event, but has derives.
-                dojo_derive_all(db, struct_ast.attributes(db).query_attr(db, "derive"), &item_ast)
             }
             _ => PluginResult::default(),
-        }
+        };
+
+        result.diagnostics.extend(lint_diagnostics);
+        result
     }
 
     fn declared_attributes(&self) -> Vec<String> {
@@ -106,6 +137,8 @@ impl MacroPlugin for BuiltinDojoPlugin {
             DOJO_EVENT_ATTR.to_string(),
             DOJO_MODEL_ATTR.to_string(),
             "key".to_string(),
+            DOJO_INDEX_ATTR.to_string(),
+            DOJO_PACKED_ATTR.to_string(),
         ]
     }
 
@@ -7,18 +7,106 @@ use cairo_lang_syntax::node::{ids, Terminal, TypedSyntaxNode};
 use starknet::core::utils::get_selector_from_name;
 
 use super::utils::{
-    get_array_item_type, get_tuple_item_types, is_array, is_byte_array, is_tuple,
-    is_unsupported_option_type,
+    get_array_item_type, get_fixed_array_item_type_and_size, get_tuple_item_types, is_array,
+    is_byte_array, is_fixed_array, is_tuple, is_unsupported_option_type,
 };
 
+use crate::attribute_macros::DOJO_PACKED_ATTR;
+
 const CAIRO_DELIMITERS: [char; 7] = ['[', ']', '<', '>', '(', ')', ','];
 
+/// The primitive types which can be declared `#[packed]`, with their size in bits.
+const PACKABLE_MEMBER_TYPES: [(&str, u32); 6] =
+    [("bool", 1), ("u8", 8), ("u16", 16), ("u32", 32), ("u64", 64), ("u128", 128)];
+
+/// A group of consecutive `#[packed]` members of a struct.
+///
+/// The members of a group are bit-packed together into the field of the first member of the
+/// group, while the other members of the struct keep their own field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedMemberGroup {
+    /// The member names, in declaration order.
+    pub members: Vec<String>,
+    /// The size in bits of each member.
+    pub bits: Vec<u32>,
+}
+
+/// Returns the size in bits of a type which can be declared `#[packed]`.
+pub fn packable_member_bits(item_type: &str) -> Option<u32> {
+    PACKABLE_MEMBER_TYPES.iter().find(|(ty, _)| *ty == item_type).map(|(_, bits)| *bits)
+}
+
+/// Groups the consecutive `#[packed]` members of the Struct.
+///
+/// Keys are not stored in the struct fields, so they don't split a group.
+pub fn parse_packed_member_groups(
+    db: &dyn SyntaxGroup,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+    struct_ast: &ItemStruct,
+) -> Vec<PackedMemberGroup> {
+    let mut groups: Vec<PackedMemberGroup> = vec![];
+    let mut in_group = false;
+
+    for m in struct_ast.members(db).elements(db) {
+        let packed = m.has_attr(db, DOJO_PACKED_ATTR);
+
+        if m.has_attr(db, "key") {
+            if packed {
+                diagnostics.push(PluginDiagnostic {
+                    stable_ptr: m.name(db).stable_ptr().0,
+                    message: "Key members can't be packed.".to_string(),
+                    severity: Severity::Error,
+                });
+            }
+            continue;
+        }
+
+        if !packed {
+            in_group = false;
+            continue;
+        }
+
+        let member_name = m.name(db).text(db).to_string();
+        let member_type = m.type_clause(db).ty(db).as_syntax_node().get_text(db).trim().to_string();
+
+        let Some(bits) = packable_member_bits(&member_type) else {
+            diagnostics.push(PluginDiagnostic {
+                stable_ptr: m.type_clause(db).stable_ptr().0,
+                message: format!(
+                    "The type `{member_type}` can't be packed, only bool, u8, u16, u32, u64 and \
+                     u128 members can be packed."
+                ),
+                severity: Severity::Error,
+            });
+            in_group = false;
+            continue;
+        };
+
+        match groups.last_mut() {
+            Some(group) if in_group => {
+                group.members.push(member_name);
+                group.bits.push(bits);
+            }
+            _ => groups.push(PackedMemberGroup { members: vec![member_name], bits: vec![bits] }),
+        }
+
+        in_group = true;
+    }
+
+    groups
+}
+
 /// build the full layout for every field in the Struct.
+///
+/// A group of `#[packed]` members is stored in a single field, named after the first member of
+/// the group, with a fixed layout packing all the members of the group.
 pub fn build_field_layouts(
     db: &dyn SyntaxGroup,
     diagnostics: &mut Vec<PluginDiagnostic>,
     struct_ast: &ItemStruct,
 ) -> String {
+    let packed_groups = parse_packed_member_groups(db, diagnostics, struct_ast);
+
     struct_ast
         .members(db)
         .elements(db)
@@ -48,8 +136,22 @@ pub fn build_field_layouts(
                 return None;
             }
 
-            let field_name = m.name(db).text(db);
-            let field_selector = get_selector_from_name(&field_name.to_string()).unwrap();
+            let field_name = m.name(db).text(db).to_string();
+            let field_selector = get_selector_from_name(&field_name).unwrap();
+
+            if m.has_attr(db, DOJO_PACKED_ATTR) {
+                // only the first member of a packed group has a field.
+                let group = packed_groups.iter().find(|g| g.members.first() == Some(&field_name))?;
+                let bits = group.bits.iter().map(|b| b.to_string()).collect::<Vec<_>>().join(",");
+
+                return Some(format!(
+                    "dojo::meta::FieldLayout {{
+                    selector: {field_selector},
+                    layout: dojo::meta::Layout::Fixed(array![{bits}].span())
+                }}"
+                ));
+            }
+
             let field_layout = get_layout_from_type_clause(db, diagnostics, &m.type_clause(db));
             Some(format!(
                 "dojo::meta::FieldLayout {{
@@ -112,6 +214,10 @@ pub fn get_layout_from_type_clause(
             let tuple_type = expr.as_syntax_node().get_text(db);
             build_tuple_layout_from_type(diagnostics, type_clause.stable_ptr().0, &tuple_type)
         }
+        Expr::FixedSizeArray(expr) => {
+            let array_type = expr.as_syntax_node().get_text(db);
+            build_fixed_array_layout_from_type(diagnostics, type_clause.stable_ptr().0, &array_type)
+        }
         _ => {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: type_clause.stable_ptr().0,
@@ -132,7 +238,7 @@ pub fn build_array_layout_from_type(
 ) -> String {
     let array_item_type = get_array_item_type(item_type);
 
-    if is_tuple(&array_item_type) {
+    if is_tuple(&array_item_type) || is_fixed_array(&array_item_type) {
         format!(
             "dojo::meta::Layout::Array(
                 array![
@@ -177,6 +283,56 @@ pub fn build_tuple_layout_from_type(
     )
 }
 
+/// Build the fixed-size array layout describing the provided fixed-size array type.
+/// A fixed-size array is stored as a tuple of `N` items, so its layout is a tuple layout.
+/// item_type could be something like [u8; 8] for example.
+pub fn build_fixed_array_layout_from_type(
+    diagnostics: &mut Vec<PluginDiagnostic>,
+    diagnostic_item: ids::SyntaxStablePtrId,
+    item_type: &str,
+) -> String {
+    let Some((array_item_type, size)) =
+        get_fixed_array_item_type_and_checked_size(diagnostics, diagnostic_item, item_type)
+    else {
+        return "ERROR".to_string();
+    };
+
+    format!(
+        "dojo::meta::Layout::Tuple(
+            {{
+                let mut layouts = array![];
+                let mut i = 0;
+                while i < {size} {{
+                    layouts.append({});
+                    i += 1;
+                }};
+                layouts.span()
+            }}
+        )",
+        build_item_layout_from_type(diagnostics, diagnostic_item, &array_item_type)
+    )
+}
+
+/// Returns the item type and the size of a fixed-size array type, with a diagnostic if the size
+/// is not an integer literal.
+pub fn get_fixed_array_item_type_and_checked_size(
+    diagnostics: &mut Vec<PluginDiagnostic>,
+    diagnostic_item: ids::SyntaxStablePtrId,
+    item_type: &str,
+) -> Option<(String, u32)> {
+    match get_fixed_array_item_type_and_size(item_type) {
+        (array_item_type, Some(size)) => Some((array_item_type, size)),
+        (_, None) => {
+            diagnostics.push(PluginDiagnostic {
+                stable_ptr: diagnostic_item,
+                message: "The size of a fixed-size array must be an integer literal.".into(),
+                severity: Severity::Error,
+            });
+            None
+        }
+    }
+}
+
 /// Build the layout describing the provided type.
 /// item_type could be any type (array, tuple, struct, ...)
 pub fn build_item_layout_from_type(
@@ -188,6 +344,8 @@ pub fn build_item_layout_from_type(
         build_array_layout_from_type(diagnostics, diagnostic_item, item_type)
     } else if is_tuple(item_type) {
         build_tuple_layout_from_type(diagnostics, diagnostic_item, item_type)
+    } else if is_fixed_array(item_type) {
+        build_fixed_array_layout_from_type(diagnostics, diagnostic_item, item_type)
     } else {
         // For Option<T>, T cannot be a tuple
         if is_unsupported_option_type(item_type) {
@@ -216,11 +374,28 @@ pub fn is_custom_layout(layout: &str) -> bool {
     layout.starts_with("dojo::meta::introspect::Introspect::")
 }
 
+/// Builds the fixed layout of a struct deriving `IntrospectPacked`, all the members being packed
+/// together.
+///
+/// The `#[packed]` attribute groups members in a struct layout, so it's rejected here instead of
+/// being ignored.
 pub fn build_packed_struct_layout(
     db: &dyn SyntaxGroup,
     diagnostics: &mut Vec<PluginDiagnostic>,
     struct_ast: &ItemStruct,
 ) -> String {
+    for m in struct_ast.members(db).elements(db) {
+        if let Some(attr) = m.find_attr(db, DOJO_PACKED_ATTR) {
+            diagnostics.push(PluginDiagnostic {
+                stable_ptr: attr.stable_ptr().0,
+                message: "The `#[packed]` attribute can't be used with `IntrospectPacked`, which \
+                          already packs all the members together."
+                    .to_string(),
+                severity: Severity::Error,
+            });
+        }
+    }
+
     let layouts = struct_ast
         .members(db)
         .elements(db)
@@ -349,6 +524,14 @@ pub fn get_packed_field_layout_from_type_clause(
             let tuple_type = expr.as_syntax_node().get_text(db);
             get_packed_tuple_layout_from_type(diagnostics, type_clause.stable_ptr().0, &tuple_type)
         }
+        Expr::FixedSizeArray(expr) => {
+            let array_type = expr.as_syntax_node().get_text(db);
+            get_packed_item_layout_from_type(
+                diagnostics,
+                type_clause.stable_ptr().0,
+                array_type.trim(),
+            )
+        }
         _ => {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: type_clause.stable_ptr().0,
@@ -375,6 +558,14 @@ pub fn get_packed_item_layout_from_type(
         vec!["ERROR".to_string()]
     } else if is_tuple(item_type) {
         get_packed_tuple_layout_from_type(diagnostics, diagnostic_item, item_type)
+    } else if is_fixed_array(item_type) {
+        match get_fixed_array_item_type_and_checked_size(diagnostics, diagnostic_item, item_type) {
+            Some((array_item_type, size)) => {
+                get_packed_item_layout_from_type(diagnostics, diagnostic_item, &array_item_type)
+                    .repeat(size as usize)
+            }
+            None => vec!["ERROR".to_string()],
+        }
     } else {
         // as we cannot verify that an enum/struct custom type is packable,
         // we suppose it is and let the user verify this.
@@ -8,10 +8,10 @@ use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::{Terminal, TypedSyntaxNode};
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 
-mod layout;
+pub(crate) mod layout;
 mod size;
 mod ty;
-mod utils;
+pub(crate) mod utils;
 
 /// Generate the introspect of a Struct
 pub fn handle_introspect_struct(
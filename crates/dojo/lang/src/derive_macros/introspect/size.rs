@@ -3,7 +3,10 @@ use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::helpers::QueryAttrs;
 use cairo_lang_syntax::node::TypedSyntaxNode;
 
-use super::utils::{get_tuple_item_types, is_array, is_byte_array, is_tuple};
+use super::utils::{
+    get_fixed_array_item_type_and_size, get_tuple_item_types, is_array, is_byte_array,
+    is_fixed_array, is_tuple,
+};
 
 pub fn compute_struct_layout_size(
     db: &dyn SyntaxGroup,
@@ -147,6 +150,10 @@ pub fn get_field_size_from_type_clause(
             let tuple_type = expr.as_syntax_node().get_text(db).trim().to_string();
             compute_tuple_size_from_type(&tuple_type)
         }
+        Expr::FixedSizeArray(expr) => {
+            let array_type = expr.as_syntax_node().get_text(db).trim().to_string();
+            compute_fixed_array_size_from_type(&array_type)
+        }
         _ => {
             // field type already checked while building the layout
             vec!["ERROR".to_string()]
@@ -179,6 +186,8 @@ pub fn compute_item_size_from_type(item_type: &String) -> Vec<String> {
         vec!["Option::None".to_string()]
     } else if is_tuple(item_type) {
         compute_tuple_size_from_type(item_type)
+    } else if is_fixed_array(item_type) {
+        compute_fixed_array_size_from_type(item_type)
     } else {
         vec![format!("dojo::meta::introspect::Introspect::<{}>::size()", item_type)]
     }
@@ -190,3 +199,27 @@ pub fn compute_tuple_size_from_type(tuple_type: &str) -> Vec<String> {
         .flat_map(compute_item_size_from_type)
         .collect::<Vec<_>>()
 }
+
+/// The size of a fixed-size array is the size of its item type, multiplied by its size.
+pub fn compute_fixed_array_size_from_type(array_type: &str) -> Vec<String> {
+    let (item_type, size) = get_fixed_array_item_type_and_size(array_type);
+
+    // an invalid size is already reported while building the layout
+    let Some(size) = size else {
+        return vec!["Option::None".to_string()];
+    };
+
+    compute_item_size_from_type(&item_type)
+        .into_iter()
+        .map(|s| match s.parse::<u32>() {
+            Ok(v) => (v * size).to_string(),
+            Err(_) if s == "Option::None" => s,
+            Err(_) => format!(
+                "match {s} {{
+                    Option::Some(size) => Option::Some(size * {size}),
+                    Option::None => Option::None,
+                }}"
+            ),
+        })
+        .collect()
+}
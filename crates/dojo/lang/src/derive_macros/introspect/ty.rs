@@ -5,7 +5,11 @@ use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::helpers::QueryAttrs;
 use cairo_lang_syntax::node::{Terminal, TypedSyntaxNode};
 
-use super::utils::{get_array_item_type, get_tuple_item_types, is_array, is_byte_array, is_tuple};
+use super::utils::{
+    get_array_item_type, get_fixed_array_item_type_and_size, get_tuple_item_types, is_array,
+    is_byte_array, is_fixed_array, is_tuple,
+};
+use crate::attribute_macros::DOJO_PACKED_ATTR;
 
 pub fn build_struct_ty(db: &dyn SyntaxGroup, name: &String, struct_ast: &ItemStruct) -> String {
     let members_ty = struct_ast
@@ -53,7 +57,13 @@ pub fn build_enum_ty(db: &dyn SyntaxGroup, name: &String, enum_ast: &ItemEnum) -
 
 pub fn build_member_ty(db: &dyn SyntaxGroup, member: &Member) -> String {
     let name = member.name(db).text(db).to_string();
-    let attrs = if member.has_attr(db, "key") { vec!["'key'"] } else { vec![] };
+    let attrs = if member.has_attr(db, "key") {
+        vec!["'key'"]
+    } else if member.has_attr(db, DOJO_PACKED_ATTR) {
+        vec!["'packed'"]
+    } else {
+        vec![]
+    };
 
     format!(
         "dojo::meta::introspect::Member {{
@@ -89,6 +99,10 @@ pub fn build_ty_from_type_clause(db: &dyn SyntaxGroup, type_clause: &TypeClause)
             let tuple_type = expr.as_syntax_node().get_text(db).trim().to_string();
             build_tuple_ty_from_type(&tuple_type)
         }
+        Expr::FixedSizeArray(expr) => {
+            let array_type = expr.as_syntax_node().get_text(db).trim().to_string();
+            build_fixed_array_ty_from_type(&array_type)
+        }
         _ => {
             // diagnostic message already handled in layout building
             "ERROR".to_string()
@@ -111,6 +125,8 @@ pub fn build_item_ty_from_type(item_type: &String) -> String {
         "dojo::meta::introspect::Ty::ByteArray".to_string()
     } else if is_tuple(item_type) {
         build_tuple_ty_from_type(item_type)
+    } else if is_fixed_array(item_type) {
+        build_fixed_array_ty_from_type(item_type)
     } else {
         format!("dojo::meta::introspect::Introspect::<{}>::ty()", item_type)
     }
@@ -131,3 +147,21 @@ pub fn build_tuple_ty_from_type(item_type: &str) -> String {
         tuple_items
     )
 }
+
+pub fn build_fixed_array_ty_from_type(item_type: &str) -> String {
+    let (array_item_type, size) = get_fixed_array_item_type_and_size(item_type);
+
+    // an invalid size is already reported while building the layout
+    let Some(size) = size else {
+        return "ERROR".to_string();
+    };
+
+    format!(
+        "dojo::meta::introspect::Ty::FixedArray(
+            array![
+            ({}, {size})
+            ].span()
+        )",
+        build_item_ty_from_type(&array_item_type)
+    )
+}
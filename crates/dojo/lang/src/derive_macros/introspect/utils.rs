@@ -4,6 +4,9 @@ const SPAN_PREFIX: &str = "Span<";
 const SPAN_SUFFIX: &str = ">";
 const ARRAY_PREFIX: &str = "Array<";
 const ARRAY_SUFFIX: &str = ">";
+const FIXED_ARRAY_PREFIX: &str = "[";
+const FIXED_ARRAY_SUFFIX: &str = "]";
+const FIXED_ARRAY_SEPARATOR: char = ';';
 
 /// Check if the provided type is an unsupported `Option<T>`,
 /// because tuples are not supported with Option.
@@ -23,6 +26,22 @@ pub fn is_tuple(ty: &str) -> bool {
     ty.starts_with(TUPLE_PREFIX)
 }
 
+pub fn is_fixed_array(ty: &str) -> bool {
+    ty.starts_with(FIXED_ARRAY_PREFIX)
+}
+
+/// Returns the item type and the size of a fixed-size array, or `None` for the size if it's not
+/// an integer literal.
+/// example [(u8, u16); 4] -> ("(u8,u16)", Some(4))
+pub fn get_fixed_array_item_type_and_size(ty: &str) -> (String, Option<u32>) {
+    let inner = extract_composite_inner_type(ty, FIXED_ARRAY_PREFIX, FIXED_ARRAY_SUFFIX);
+
+    match inner.rsplit_once(FIXED_ARRAY_SEPARATOR) {
+        Some((item_type, size)) => (item_type.to_string(), size.parse().ok()),
+        None => (inner, None),
+    }
+}
+
 pub fn get_array_item_type(ty: &str) -> String {
     if ty.starts_with(ARRAY_PREFIX) {
         extract_composite_inner_type(ty, ARRAY_PREFIX, ARRAY_SUFFIX)
@@ -207,6 +226,20 @@ fn test_extract_composite_inner_type_with_arrays_bad_ty() {
     let _ = extract_composite_inner_type("u8, u16", ARRAY_PREFIX, ARRAY_SUFFIX);
 }
 
+#[test]
+fn test_get_fixed_array_item_type_and_size() {
+    let test_cases = [
+        ("[u8; 8]", ("u8", Some(8))),
+        ("[[u8; 8]; 8]", ("[u8;8]", Some(8))),
+        ("[(u8, u16); 4] // comment", ("(u8,u16)", Some(4))),
+        ("[u8; SIZE]", ("u8", None)),
+    ];
+
+    for (ty, (item_type, size)) in test_cases {
+        assert_eq!(get_fixed_array_item_type_and_size(ty), (item_type.to_string(), size));
+    }
+}
+
 #[test]
 fn test_extract_composite_inner_type_with_spans() {
     let test_cases = [
@@ -15,6 +15,8 @@ use cairo_lang_syntax::node::{ast, Terminal, TypedSyntaxNode};
 use introspect::{handle_introspect_enum, handle_introspect_struct};
 use print::{handle_print_enum, handle_print_struct};
 
+use crate::lints::{self, LintConfig};
+
 pub mod introspect;
 pub mod print;
 
@@ -27,6 +29,7 @@ pub fn dojo_derive_all(
     db: &dyn SyntaxGroup,
     attrs: Vec<Attribute>,
     item_ast: &ast::ModuleItem,
+    lint_config: &LintConfig,
 ) -> PluginResult {
     if attrs.is_empty() {
         return PluginResult::default();
@@ -36,7 +39,8 @@ pub fn dojo_derive_all(
 
     let derive_attr_names = extract_derive_attr_names(db, &mut diagnostics, attrs);
 
-    let (rewrite_nodes, derive_diagnostics) = handle_derive_attrs(db, &derive_attr_names, item_ast);
+    let (rewrite_nodes, derive_diagnostics) =
+        handle_derive_attrs(db, &derive_attr_names, item_ast, lint_config);
 
     diagnostics.extend(derive_diagnostics);
 
@@ -67,6 +71,7 @@ pub fn handle_derive_attrs(
     db: &dyn SyntaxGroup,
     attrs: &[String],
     item_ast: &ast::ModuleItem,
+    lint_config: &LintConfig,
 ) -> (Vec<RewriteNode>, Vec<PluginDiagnostic>) {
     let mut rewrite_nodes = Vec::new();
     let mut diagnostics = Vec::new();
@@ -95,6 +100,7 @@ pub fn handle_derive_attrs(
                             struct_ast.clone(),
                             true,
                         ));
+                        diagnostics.extend(lints::check_packed_struct(db, lint_config, struct_ast));
                     }
                     _ => continue,
                 }
@@ -0,0 +1,436 @@
+//! Rewrites of the legacy `get!`, `set!`, `delete!` and `emit!` macros to the `WorldStorage` API,
+//! and of the legacy `spawn_test_world!` and `get_models_test_class_hashes!` macros to the
+//! `spawn_test_world` function of `dojo_cairo_test`.
+//!
+//! Those macros have been removed, the plugin only recognizes them to emit a diagnostic with the
+//! code to use instead, and `sozo fix` applies the same rewrites to the sources.
+
+use cairo_lang_defs::plugin::{InlinePluginResult, PluginDiagnostic};
+use cairo_lang_diagnostics::Severity;
+use cairo_lang_filesystem::span::TextSpan;
+use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, SyntaxNode, Terminal, TypedStablePtr, TypedSyntaxNode};
+
+use super::extract_models;
+use super::utils::extract_namespaces;
+
+/// The names of the legacy macros.
+pub const LEGACY_MACROS: [&str; 6] =
+    ["get", "set", "delete", "emit", "spawn_test_world", "get_models_test_class_hashes"];
+
+/// The rewrite of a legacy macro usage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyMacroRewrite {
+    /// The name of the rewritten macro.
+    pub macro_name: String,
+    /// The span of the code to replace, which is the whole statement when the macro is used
+    /// as a statement, the macro expression otherwise.
+    pub span: TextSpan,
+    /// The replacement code, one line per statement.
+    pub lines: Vec<String>,
+}
+
+impl LegacyMacroRewrite {
+    /// Returns the replacement code, using `indent` for the lines after the first one.
+    pub fn replacement(&self, indent: &str) -> String {
+        self.lines.join(&format!("\n{indent}"))
+    }
+}
+
+/// Returns the diagnostic of a legacy macro usage, with the code to use instead.
+pub fn legacy_macro_diagnostic(
+    db: &dyn SyntaxGroup,
+    syntax: &ast::ExprInlineMacro,
+) -> InlinePluginResult {
+    let name = macro_name(db, syntax);
+
+    let removal = match name.as_str() {
+        "spawn_test_world" | "get_models_test_class_hashes" => format!(
+            "The `{name}!` macro has been removed in favor of the `spawn_test_world` function of \
+             `dojo_cairo_test`, which takes the namespaces to register with their resources \
+             (`NamespaceDef` and `TestResource`). The models are listed as \
+             `TestResource::Model(m_<Model>::TEST_CLASS_HASH)`."
+        ),
+        _ => format!(
+            "The `{name}!` macro has been removed in favor of the `WorldStorage` API \
+             (`read_model`, `read_member`, `write_model`, `erase_model` and `emit_event`), where \
+             the world is a `WorldStorage`."
+        ),
+    };
+
+    let suggestion = match legacy_macro_rewrite(db, syntax) {
+        Some(rewrite) => format!(
+            " Replace it with:\n\n    {}\n\nor run `sozo fix` to apply this rewrite.",
+            rewrite.replacement("    ")
+        ),
+        None => String::new(),
+    };
+
+    InlinePluginResult {
+        code: None,
+        diagnostics: vec![PluginDiagnostic {
+            stable_ptr: syntax.stable_ptr().untyped(),
+            message: format!("{removal}{suggestion}"),
+            severity: Severity::Error,
+        }],
+    }
+}
+
+/// Computes the rewrite of a legacy macro usage, if the macro arguments are valid.
+pub fn legacy_macro_rewrite(
+    db: &dyn SyntaxGroup,
+    syntax: &ast::ExprInlineMacro,
+) -> Option<LegacyMacroRewrite> {
+    let name = macro_name(db, syntax);
+
+    let ast::WrappedArgList::ParenthesizedArgList(arg_list) = syntax.arguments(db) else {
+        return None;
+    };
+
+    let args = arg_list
+        .arguments(db)
+        .elements(db)
+        .into_iter()
+        .map(|arg| match arg.arg_clause(db) {
+            ast::ArgClause::Unnamed(clause) => Some(clause.value(db)),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let parent = syntax.as_syntax_node().parent();
+    let macro_span = syntax.as_syntax_node().span_without_trivia(db);
+
+    let (span, lines) = match (name.as_str(), args.as_slice()) {
+        ("get", [world, keys, models]) => {
+            let world = text(db, &world.as_syntax_node());
+            let keys = text(db, &keys.as_syntax_node());
+            let models = extract_models(db, models).ok()?;
+
+            if models.is_empty() {
+                return None;
+            }
+
+            let read = format!("{world}.read_model({keys})");
+
+            if let Some((expr, member)) = parent.as_ref().and_then(|p| member_access(db, p)) {
+                if let [model] = models.as_slice() {
+                    let ptr = format!("dojo::model::Model::<{model}>::ptr_from_keys({keys})");
+                    let read = format!("{world}.read_member({ptr}, selector!(\"{member}\"))");
+
+                    return Some(LegacyMacroRewrite {
+                        macro_name: name,
+                        span: expr.span_without_trivia(db),
+                        lines: vec![read],
+                    });
+                }
+            }
+
+            match parent.as_ref().and_then(|p| let_patterns(db, p, syntax, models.len())) {
+                Some((statement, patterns)) => {
+                    let lines = patterns
+                        .iter()
+                        .zip(models.iter())
+                        .map(|(pattern, model)| format!("let {pattern}: {model} = {read};"))
+                        .collect();
+
+                    (statement.span_without_trivia(db), lines)
+                }
+                None => {
+                    let reads = models
+                        .iter()
+                        .map(|model| format!("{{ let __{model}: {model} = {read}; __{model} }}"))
+                        .collect::<Vec<_>>();
+
+                    let expr = if reads.len() == 1 {
+                        reads[0].clone()
+                    } else {
+                        format!("({})", reads.join(", "))
+                    };
+
+                    (macro_span, vec![expr])
+                }
+            }
+        }
+        ("set" | "delete" | "emit", [world, values]) => {
+            let world = text(db, &world.as_syntax_node());
+            let method = match name.as_str() {
+                "set" => "write_model",
+                "delete" => "erase_model",
+                _ => "emit_event",
+            };
+
+            let calls = bundle(db, values)
+                .iter()
+                .map(|value| format!("{world}.{method}(@{value});"))
+                .collect::<Vec<_>>();
+
+            if calls.is_empty() {
+                return None;
+            }
+
+            match parent.filter(|p| p.kind(db) == SyntaxKind::StatementExpr) {
+                Some(statement) => (statement.span_without_trivia(db), calls),
+                None => (macro_span, vec![format!("{{ {} }}", calls.join(" "))]),
+            }
+        }
+        ("spawn_test_world", [namespaces]) => {
+            let namespaces = extract_namespaces(db, namespaces).ok()?;
+
+            if namespaces.is_empty() {
+                return None;
+            }
+
+            // The resources can't be known from the macro, they are left to the user.
+            let defs = namespaces
+                .iter()
+                .map(|ns| {
+                    format!(
+                        "dojo_cairo_test::NamespaceDef {{ namespace: \"{ns}\", resources: \
+                         [].span() }}"
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let spawn = format!("dojo_cairo_test::spawn_test_world([{}].span())", defs.join(", "));
+
+            (macro_span, vec![spawn])
+        }
+        _ => return None,
+    };
+
+    Some(LegacyMacroRewrite { macro_name: name, span, lines })
+}
+
+/// Parses a Cairo source file and returns the rewrites of all the legacy macros it contains.
+pub fn legacy_macro_rewrites_from_source(content: &str) -> Vec<LegacyMacroRewrite> {
+    let db = SimpleParserDatabase::default();
+    let Ok(node) = db.parse_virtual(content) else {
+        return vec![];
+    };
+
+    let mut rewrites = vec![];
+    collect_rewrites(&db, &node, &mut rewrites);
+
+    rewrites
+}
+
+/// Applies the rewrites to the content they have been computed from.
+pub fn apply_rewrites(content: &str, rewrites: &[LegacyMacroRewrite]) -> String {
+    let offset = |position: &str| content.len() - position.len();
+
+    let mut rewrites = rewrites
+        .iter()
+        .map(|r| {
+            let start = offset(r.span.start.take_from(content));
+            let end = offset(r.span.end.take_from(content));
+            (start, end, r)
+        })
+        .collect::<Vec<_>>();
+
+    // Apply from the end, to keep the offsets of the remaining rewrites valid.
+    rewrites.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+
+    let mut content = content.to_string();
+
+    for (start, end, rewrite) in rewrites {
+        let line_start = content[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let indent = content[line_start..start]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect::<String>();
+
+        content.replace_range(start..end, &rewrite.replacement(&indent));
+    }
+
+    content
+}
+
+fn collect_rewrites(
+    db: &dyn SyntaxGroup,
+    node: &SyntaxNode,
+    rewrites: &mut Vec<LegacyMacroRewrite>,
+) {
+    if node.kind(db) == SyntaxKind::ExprInlineMacro {
+        let syntax = ast::ExprInlineMacro::from_syntax_node(db, node.clone());
+
+        if LEGACY_MACROS.contains(&macro_name(db, &syntax).as_str()) {
+            if let Some(rewrite) = legacy_macro_rewrite(db, &syntax) {
+                rewrites.push(rewrite);
+                return;
+            }
+        }
+    }
+
+    for child in node.get_children(db).iter() {
+        collect_rewrites(db, child, rewrites);
+    }
+}
+
+/// Returns the `let` statement of which the macro is the right-hand side, and its patterns if
+/// there is one pattern per model.
+fn let_patterns(
+    db: &dyn SyntaxGroup,
+    parent: &SyntaxNode,
+    syntax: &ast::ExprInlineMacro,
+    n_models: usize,
+) -> Option<(SyntaxNode, Vec<String>)> {
+    if parent.kind(db) != SyntaxKind::StatementLet {
+        return None;
+    }
+
+    let statement = ast::StatementLet::from_syntax_node(db, parent.clone());
+
+    if statement.rhs(db).as_syntax_node() != syntax.as_syntax_node() {
+        return None;
+    }
+
+    let patterns = match statement.pattern(db) {
+        ast::Pattern::Tuple(tuple) => tuple
+            .patterns(db)
+            .elements(db)
+            .iter()
+            .map(|p| text(db, &p.as_syntax_node()))
+            .collect::<Vec<_>>(),
+        pattern => vec![text(db, &pattern.as_syntax_node())],
+    };
+
+    (patterns.len() == n_models).then_some((parent.clone(), patterns))
+}
+
+/// Returns the member access expression of which the macro is the left-hand side, and the name
+/// of the accessed member.
+fn member_access(db: &dyn SyntaxGroup, parent: &SyntaxNode) -> Option<(SyntaxNode, String)> {
+    if parent.kind(db) != SyntaxKind::ExprBinary {
+        return None;
+    }
+
+    let expr = ast::ExprBinary::from_syntax_node(db, parent.clone());
+
+    if !matches!(expr.op(db), ast::BinaryOperator::Dot(_)) {
+        return None;
+    }
+
+    let ast::Expr::Path(path) = expr.rhs(db) else {
+        return None;
+    };
+
+    match path.elements(db).as_slice() {
+        [ast::PathSegment::Simple(segment)] => {
+            Some((parent.clone(), segment.ident(db).text(db).to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the values passed to a `set!`, `delete!` or `emit!` macro.
+fn bundle(db: &dyn SyntaxGroup, values: &ast::Expr) -> Vec<String> {
+    match values {
+        ast::Expr::Parenthesized(parens) => vec![text(db, &parens.expr(db).as_syntax_node())],
+        ast::Expr::Tuple(list) => list
+            .expressions(db)
+            .elements(db)
+            .iter()
+            .map(|expr| text(db, &expr.as_syntax_node()))
+            .collect(),
+        expr => vec![text(db, &expr.as_syntax_node())],
+    }
+}
+
+fn macro_name(db: &dyn SyntaxGroup, syntax: &ast::ExprInlineMacro) -> String {
+    text(db, &syntax.path(db).as_syntax_node())
+}
+
+fn text(db: &dyn SyntaxGroup, node: &SyntaxNode) -> String {
+    node.get_text_without_trivia(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_statements() {
+        let content = r#"
+fn f(world: WorldStorage, player: ContractAddress) {
+    let (position, moves) = get!(world, player, (Position, Moves));
+    let config = get!(world, (player, 1), PlayerConfig);
+    set!(world, (position, moves));
+    delete!(world, Moves { player, remaining: 0 });
+    emit!(world, (Moved { player, direction }));
+}
+"#;
+
+        let rewrites = legacy_macro_rewrites_from_source(content);
+        assert_eq!(rewrites.len(), 5);
+
+        assert_eq!(
+            apply_rewrites(content, &rewrites),
+            r#"
+fn f(world: WorldStorage, player: ContractAddress) {
+    let position: Position = world.read_model(player);
+    let moves: Moves = world.read_model(player);
+    let config: PlayerConfig = world.read_model((player, 1));
+    world.write_model(@position);
+    world.write_model(@moves);
+    world.erase_model(@Moves { player, remaining: 0 });
+    world.emit_event(@Moved { player, direction });
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_expressions() {
+        let content = r#"
+fn f(world: WorldStorage, player: ContractAddress) -> u8 {
+    get!(world, player, Moves).remaining
+}
+"#;
+
+        let rewrites = legacy_macro_rewrites_from_source(content);
+
+        assert_eq!(
+            apply_rewrites(content, &rewrites),
+            r#"
+fn f(world: WorldStorage, player: ContractAddress) -> u8 {
+    world.read_member(dojo::model::Model::<Moves>::ptr_from_keys(player), selector!("remaining"))
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_spawn_test_world() {
+        let content = r#"
+fn f() {
+    let world = spawn_test_world!(["ns"]);
+}
+"#;
+
+        let rewrites = legacy_macro_rewrites_from_source(content);
+
+        assert_eq!(
+            apply_rewrites(content, &rewrites),
+            r#"
+fn f() {
+    let world = dojo_cairo_test::spawn_test_world([dojo_cairo_test::NamespaceDef { namespace: "ns", resources: [].span() }].span());
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_invalid_macros_are_not_rewritten() {
+        let content = r#"
+fn f(world: WorldStorage) {
+    let a = get!(world, player);
+    let hashes = get_models_test_class_hashes!(["ns"]);
+    println!("{}", 1);
+}
+"#;
+
+        assert!(legacy_macro_rewrites_from_source(content).is_empty());
+    }
+}
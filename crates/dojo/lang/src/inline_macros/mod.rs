@@ -13,6 +13,7 @@ pub mod delete;
 pub mod emit;
 pub mod get;
 pub mod get_models_test_class_hashes;
+pub mod legacy;
 pub mod selector_from_tag;
 pub mod set;
 pub mod spawn_test_world;
@@ -27,8 +28,6 @@ pub use selector_from_tag::SelectorFromTagMacro;
 pub use set::SetMacro;
 pub use spawn_test_world::SpawnTestWorld;
 
-const CAIRO_ERR_MSG_LEN: usize = 31;
-
 pub fn extract_models(
     db: &dyn SyntaxGroup,
     expression: &ast::Expr,
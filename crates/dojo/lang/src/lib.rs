@@ -9,6 +9,7 @@ pub mod aux_data;
 pub mod cairo_plugin;
 pub mod derive_macros;
 pub mod inline_macros;
+pub mod lints;
 pub mod semantics;
 pub mod syntax;
 
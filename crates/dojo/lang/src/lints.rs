@@ -0,0 +1,483 @@
+//! Lints of the Dojo plugin.
+//!
+//! Lints report code which compiles but is likely to be a mistake or to be inefficient. They are
+//! emitted as plugin diagnostics, with a severity depending on the lint level, which can be
+//! configured in the `Scarb.toml` of the package:
+//!
+//! ```toml
+//! [tool.dojo.lints]
+//! expensive_key = "allow"
+//! unbounded_array_member = "deny"
+//! ```
+//!
+//! The plugin is instantiated by the compiler without any configuration, hence it reads the levels
+//! from the `Scarb.toml` of the package of the linted items, once per package. Unknown lints and
+//! invalid levels are reported as warnings and ignored.
+//!
+//! Some mistakes are not covered by lints: a model without any value member is already rejected
+//! with an error by the model attribute, and whether the keys of an event are used to filter it
+//! is only known by the clients of the world.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use cairo_lang_defs::plugin::PluginDiagnostic;
+use cairo_lang_diagnostics::Severity;
+use cairo_lang_filesystem::ids::{FileId, FileLongId};
+use cairo_lang_syntax::node::ast::{FunctionWithBody, ItemStruct};
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::helpers::QueryAttrs;
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{SyntaxNode, Terminal, TypedStablePtr, TypedSyntaxNode};
+
+use crate::aux_data::Member;
+use crate::derive_macros::introspect::utils::{is_array, is_byte_array};
+
+/// The manifest of a package, from which the lint levels are read.
+const MANIFEST_FILE_NAME: &str = "Scarb.toml";
+
+/// Maximum number of bits packed in a single felt.
+const PACKING_MAX_BITS: u32 = 251;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// A model key is serialized into several felts (`u256`, `ByteArray`), which makes the
+    /// computation of the entity id more expensive.
+    ExpensiveKey,
+    /// A model member is an `Array` or a `Span`, which makes the cost of reading and writing the
+    /// model grow with its length.
+    UnboundedArrayMember,
+    /// An `IntrospectPacked` struct doesn't use less storage slots than its unpacked version.
+    InefficientPacking,
+    /// A parameter of `dojo_init` is never used.
+    UnusedInitParam,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::ExpensiveKey,
+        Lint::UnboundedArrayMember,
+        Lint::InefficientPacking,
+        Lint::UnusedInitParam,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::ExpensiveKey => "expensive_key",
+            Lint::UnboundedArrayMember => "unbounded_array_member",
+            Lint::InefficientPacking => "inefficient_packing",
+            Lint::UnusedInitParam => "unused_init_param",
+        }
+    }
+
+    /// The level of the lint when not configured. Arrays are legit in most models, hence the
+    /// lint on array members is opt-in.
+    pub fn default_level(&self) -> LintLevel {
+        match self {
+            Lint::UnboundedArrayMember => LintLevel::Allow,
+            _ => LintLevel::Warn,
+        }
+    }
+}
+
+impl FromStr for Lint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Lint::ALL.into_iter().find(|l| l.name() == s).ok_or_else(|| {
+            anyhow!(
+                "Unknown lint `{s}`, expected one of: {}.",
+                Lint::ALL.map(|l| l.name()).join(", ")
+            )
+        })
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl FromStr for LintLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            _ => Err(anyhow!("Invalid lint level `{s}`, expected `allow`, `warn` or `deny`.")),
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintLevel::Allow => write!(f, "allow"),
+            LintLevel::Warn => write!(f, "warn"),
+            LintLevel::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// The configured lint levels, lints not configured use their default level.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    levels: HashMap<Lint, LintLevel>,
+}
+
+impl LintConfig {
+    pub fn with_level(mut self, lint: Lint, level: LintLevel) -> Self {
+        self.levels.insert(lint, level);
+        self
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels.get(&lint).copied().unwrap_or(lint.default_level())
+    }
+
+    /// Reads the lint levels from the `[tool.dojo.lints]` section of a package manifest.
+    ///
+    /// The invalid entries are ignored and returned along with the config, to be reported without
+    /// failing the compilation. An invalid manifest is reported by Scarb, and uses the default
+    /// levels.
+    pub fn from_manifest(content: &str) -> (Self, Vec<String>) {
+        let Ok(manifest) = toml::from_str::<toml::Table>(content) else {
+            return (Self::default(), vec![]);
+        };
+
+        let Some(lints) = manifest
+            .get("tool")
+            .and_then(|tool| tool.get("dojo"))
+            .and_then(|dojo| dojo.get("lints"))
+        else {
+            return (Self::default(), vec![]);
+        };
+
+        let Some(lints) = lints.as_table() else {
+            return (Self::default(), vec!["`[tool.dojo.lints]` must be a table.".to_string()]);
+        };
+
+        let mut levels = HashMap::new();
+        let mut errors = vec![];
+
+        for (name, level) in lints {
+            let level = level
+                .as_str()
+                .ok_or_else(|| anyhow!("Invalid level of lint `{name}`, expected a string."))
+                .and_then(LintLevel::from_str);
+
+            match (name.parse::<Lint>(), level) {
+                (Ok(lint), Ok(level)) => {
+                    levels.insert(lint, level);
+                }
+                (Err(e), _) | (_, Err(e)) => errors.push(e.to_string()),
+            }
+        }
+
+        (Self { levels }, errors)
+    }
+
+    /// Returns the diagnostic of the lint, if the lint is not allowed.
+    pub fn diagnostic(
+        &self,
+        lint: Lint,
+        stable_ptr: SyntaxStablePtrId,
+        message: String,
+    ) -> Option<PluginDiagnostic> {
+        let severity = match self.level(lint) {
+            LintLevel::Allow => return None,
+            LintLevel::Warn => Severity::Warning,
+            LintLevel::Deny => Severity::Error,
+        };
+
+        Some(PluginDiagnostic { stable_ptr, message: format!("{message} [{lint}]"), severity })
+    }
+}
+
+/// The lint configs of the packages, each read once from the manifest of the package.
+#[derive(Debug, Default)]
+pub struct LintConfigs {
+    /// The manifest of the package of each directory containing linted items.
+    manifests: Mutex<HashMap<PathBuf, Option<PathBuf>>>,
+    configs: Mutex<HashMap<PathBuf, Arc<LintConfig>>>,
+}
+
+impl LintConfigs {
+    /// Returns the lint config of the package of the given item.
+    ///
+    /// The invalid entries of the config are returned as diagnostics on the item the first time
+    /// the config is read, so that they are reported once.
+    pub fn get(
+        &self,
+        db: &dyn SyntaxGroup,
+        stable_ptr: SyntaxStablePtrId,
+    ) -> (Arc<LintConfig>, Vec<PluginDiagnostic>) {
+        let Some(manifest_path) =
+            file_path(db, stable_ptr.file_id(db)).and_then(|path| self.manifest_path(&path))
+        else {
+            return (Arc::default(), vec![]);
+        };
+
+        let mut configs = self.configs.lock().unwrap();
+
+        if let Some(config) = configs.get(&manifest_path) {
+            return (config.clone(), vec![]);
+        }
+
+        let content = fs::read_to_string(&manifest_path).unwrap_or_default();
+        let (config, errors) = LintConfig::from_manifest(&content);
+        let config = Arc::new(config);
+
+        configs.insert(manifest_path.clone(), config.clone());
+
+        let diagnostics = errors
+            .into_iter()
+            .map(|error| PluginDiagnostic {
+                stable_ptr,
+                message: format!(
+                    "Invalid `[tool.dojo.lints]` in {}: {error}",
+                    manifest_path.display()
+                ),
+                severity: Severity::Warning,
+            })
+            .collect();
+
+        (config, diagnostics)
+    }
+
+    /// Returns the manifest of the package containing the given file, which is the closest
+    /// `Scarb.toml` in its ancestors.
+    fn manifest_path(&self, file_path: &Path) -> Option<PathBuf> {
+        let dir = file_path.parent()?;
+        let mut manifests = self.manifests.lock().unwrap();
+
+        manifests
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                dir.ancestors().map(|d| d.join(MANIFEST_FILE_NAME)).find(|path| path.is_file())
+            })
+            .clone()
+    }
+}
+
+/// Returns the path of the file on disk, or of the file the virtual file was generated from.
+fn file_path(db: &dyn SyntaxGroup, file_id: FileId) -> Option<PathBuf> {
+    match db.lookup_intern_file(file_id) {
+        FileLongId::OnDisk(path) => Some(path),
+        FileLongId::Virtual(file) => file_path(db, file.parent?),
+        FileLongId::External(_) => None,
+    }
+}
+
+/// Lints the members of a model.
+pub fn check_model_members(
+    db: &dyn SyntaxGroup,
+    config: &LintConfig,
+    struct_ast: &ItemStruct,
+    members: &[Member],
+) -> Vec<PluginDiagnostic> {
+    let members_ast = struct_ast.members(db).elements(db);
+
+    members
+        .iter()
+        .zip(members_ast.iter())
+        .filter_map(|(member, member_ast)| {
+            let stable_ptr = member_ast.name(db).stable_ptr().untyped();
+
+            if member.key && (member.ty == "u256" || is_byte_array(&member.ty)) {
+                config.diagnostic(
+                    Lint::ExpensiveKey,
+                    stable_ptr,
+                    format!(
+                        "The key `{}` of type `{}` is serialized into several felts, which makes \
+                         the entity id more expensive to compute. Prefer a single felt key.",
+                        member.name, member.ty
+                    ),
+                )
+            } else if !member.key && is_array(&member.ty) {
+                config.diagnostic(
+                    Lint::UnboundedArrayMember,
+                    stable_ptr,
+                    format!(
+                        "The member `{}` is unbounded, the cost of reading and writing the model \
+                         grows with its length.",
+                        member.name
+                    ),
+                )
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Lints an `IntrospectPacked` struct, whose packing is inefficient when it doesn't use less
+/// felts than the unpacked struct. Only structs made of primitive types are checked, since
+/// the size of custom types is not known by the plugin.
+pub fn check_packed_struct(
+    db: &dyn SyntaxGroup,
+    config: &LintConfig,
+    struct_ast: &ItemStruct,
+) -> Option<PluginDiagnostic> {
+    let sizes = struct_ast
+        .members(db)
+        .elements(db)
+        .iter()
+        .filter(|m| !m.has_attr(db, "key"))
+        .map(|m| primitive_bits(m.type_clause(db).ty(db).as_syntax_node().get_text(db).trim()))
+        .collect::<Option<Vec<_>>>()?
+        .concat();
+
+    if sizes.is_empty() || packed_felts(&sizes) < sizes.len() {
+        return None;
+    }
+
+    config.diagnostic(
+        Lint::InefficientPacking,
+        struct_ast.name(db).stable_ptr().untyped(),
+        format!(
+            "Packing `{}` doesn't save any felt, since none of its members fit together in a \
+             single felt. Derive `Introspect` instead of `IntrospectPacked`.",
+            struct_ast.name(db).text(db)
+        ),
+    )
+}
+
+/// Lints the parameters of the `dojo_init` function, which must all be used.
+pub fn check_init_params(
+    db: &dyn SyntaxGroup,
+    config: &LintConfig,
+    fn_ast: &FunctionWithBody,
+) -> Vec<PluginDiagnostic> {
+    let body = fn_ast.body(db).as_syntax_node();
+
+    fn_ast
+        .declaration(db)
+        .signature(db)
+        .parameters(db)
+        .elements(db)
+        .iter()
+        .filter_map(|param| {
+            let name = param.name(db).text(db);
+
+            if name == "self" || name.starts_with('_') || is_identifier_used(db, &body, &name) {
+                return None;
+            }
+
+            config.diagnostic(
+                Lint::UnusedInitParam,
+                param.name(db).stable_ptr().untyped(),
+                format!(
+                    "The parameter `{name}` of `dojo_init` is never used. Remove it or prefix \
+                     it with an underscore."
+                ),
+            )
+        })
+        .collect()
+}
+
+fn is_identifier_used(db: &dyn SyntaxGroup, node: &SyntaxNode, name: &str) -> bool {
+    if node.kind(db) == SyntaxKind::TokenIdentifier {
+        return node.get_text(db) == name;
+    }
+
+    node.get_children(db).iter().any(|child| is_identifier_used(db, child, name))
+}
+
+/// Returns the sizes in bits of the felts of a primitive type, `None` if the type is not a
+/// primitive one.
+fn primitive_bits(ty: &str) -> Option<Vec<u32>> {
+    let bits = match ty {
+        "bool" => 1,
+        "u8" | "i8" => 8,
+        "u16" | "i16" => 16,
+        "u32" | "i32" | "usize" => 32,
+        "u64" | "i64" => 64,
+        "u128" | "i128" => 128,
+        "EthAddress" | "starknet::EthAddress" => 160,
+        "bytes31" => 248,
+        "felt252" | "ContractAddress" | "starknet::ContractAddress" | "ClassHash"
+        | "starknet::ClassHash" => 252,
+        "u256" => return Some(vec![128, 128]),
+        _ => return None,
+    };
+
+    Some(vec![bits])
+}
+
+/// Returns the number of felts used to pack the given sizes, in order.
+fn packed_felts(sizes: &[u32]) -> usize {
+    let mut felts = 0;
+    let mut remaining = 0;
+
+    for &size in sizes {
+        if size > remaining {
+            felts += 1;
+            remaining = PACKING_MAX_BITS;
+        }
+
+        remaining = remaining.saturating_sub(size);
+    }
+
+    felts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_config_from_manifest() {
+        let manifest = r#"
+            [package]
+            name = "test_package"
+
+            [tool.dojo.lints]
+            expensive_key = "allow"
+            unused_init_param = "deny"
+            unknown = "allow"
+            inefficient_packing = "forbid"
+        "#;
+
+        let (config, errors) = LintConfig::from_manifest(manifest);
+
+        assert_eq!(config.level(Lint::ExpensiveKey), LintLevel::Allow);
+        assert_eq!(config.level(Lint::UnusedInitParam), LintLevel::Deny);
+        assert_eq!(config.level(Lint::InefficientPacking), LintLevel::Warn);
+        assert_eq!(config.level(Lint::UnboundedArrayMember), LintLevel::Allow);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.starts_with("Unknown lint `unknown`")));
+        assert!(errors.iter().any(|e| e.starts_with("Invalid lint level `forbid`")));
+
+        assert_eq!(
+            LintConfig::from_manifest("[package]\nname = \"test_package\"\n"),
+            (LintConfig::default(), vec![])
+        );
+    }
+
+    #[test]
+    fn test_packed_felts() {
+        assert_eq!(packed_felts(&[]), 0);
+        assert_eq!(packed_felts(&[8, 16, 32, 128]), 1);
+        assert_eq!(packed_felts(&[128, 128]), 2);
+        assert_eq!(packed_felts(&[252, 8]), 2);
+        assert_eq!(packed_felts(&[1, 250, 1]), 2);
+    }
+}
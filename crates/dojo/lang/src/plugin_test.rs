@@ -86,7 +86,7 @@ pub fn test_expand_plugin(
     test_expand_plugin_inner(
         inputs,
         args,
-        &[Arc::new(BuiltinDojoPlugin), Arc::new(StarkNetPlugin::default())],
+        &[Arc::new(BuiltinDojoPlugin::default()), Arc::new(StarkNetPlugin::default())],
     )
 }
 
@@ -0,0 +1,394 @@
+//! Static analysis of the models and events read and written by a Dojo contract.
+//!
+//! The analysis is syntactic: the `WorldStorage` calls of the contract are collected, and the
+//! model or event they target is inferred from struct constructors, typed bindings, function
+//! parameters and `Model::<M>` paths. Targets that can't be inferred are ignored.
+
+use std::collections::{BTreeMap, HashMap};
+
+use cairo_lang_syntax::node::ast::{self, OptionTypeClause};
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{SyntaxNode, Terminal, TypedSyntaxNode};
+use dojo_types::system::Dependency;
+
+use super::utils::get_parameter_info;
+
+/// The containers unwrapped to find the model or event type of the batch methods.
+const CONTAINER_TYPES: [&str; 3] = ["Array", "Span", "ModelPtr"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+}
+
+/// Where the model or event targeted by a call is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    /// The type annotation of the `let` statement the call result is bound to.
+    LetType,
+    /// The argument at the given position.
+    Arg(usize),
+}
+
+/// A `WorldStorage` method: its access, its target and whether it works on model values.
+type Method = (Access, Target, bool);
+
+fn world_storage_method(name: &str) -> Option<Method> {
+    let method = match name {
+        "read_model" | "read_models" => (Access::Read, Target::LetType, false),
+        "read_member" | "read_member_of_models" => (Access::Read, Target::Arg(0), false),
+        "read_value" | "read_values" | "read_value_from_id" | "read_values_from_ids" => {
+            (Access::Read, Target::LetType, true)
+        }
+        "write_model" | "write_models" | "erase_model" | "erase_models" | "erase_model_ptr"
+        | "erase_models_ptrs" | "write_member" | "write_member_of_models" | "emit_event"
+        | "emit_events" => (Access::Write, Target::Arg(0), false),
+        "write_value" | "write_values" | "write_value_from_id" | "write_values_from_ids" => {
+            (Access::Write, Target::Arg(1), true)
+        }
+        _ => return None,
+    };
+
+    Some(method)
+}
+
+/// A call whose targets are resolved once all the bindings of the function are known.
+#[derive(Debug)]
+struct Call {
+    access: Access,
+    is_value: bool,
+    types: Vec<String>,
+    bindings: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct FunctionScope {
+    bindings: HashMap<String, Vec<String>>,
+    calls: Vec<Call>,
+}
+
+#[derive(Debug, Default)]
+struct DependenciesCollector {
+    dependencies: BTreeMap<String, Dependency>,
+}
+
+impl DependenciesCollector {
+    fn add(&mut self, name: String, access: Access) {
+        let dependency = self.dependencies.entry(name.clone()).or_insert(Dependency {
+            name,
+            read: false,
+            write: false,
+        });
+
+        match access {
+            Access::Read => dependency.read = true,
+            Access::Write => dependency.write = true,
+        }
+    }
+
+    fn visit(&mut self, db: &dyn SyntaxGroup, node: &SyntaxNode) {
+        if node.kind(db) == SyntaxKind::FunctionWithBody {
+            let mut scope = FunctionScope::default();
+            visit_function_node(db, node, &mut scope, None);
+            self.resolve(scope);
+            return;
+        }
+
+        for child in node.get_children(db).iter() {
+            self.visit(db, child);
+        }
+    }
+
+    fn resolve(&mut self, scope: FunctionScope) {
+        for call in scope.calls {
+            let bound_types = call
+                .bindings
+                .iter()
+                .filter_map(|binding| scope.bindings.get(binding))
+                .flatten()
+                .cloned();
+
+            for ty in call.types.iter().cloned().chain(bound_types) {
+                if let Some(name) = type_name(&ty, call.is_value) {
+                    self.add(name, call.access);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the models and events read and written by the given contract items, by type path as
+/// written in the contract.
+pub fn contract_dependencies(
+    db: &dyn SyntaxGroup,
+    items: impl IntoIterator<Item = SyntaxNode>,
+) -> Vec<Dependency> {
+    let mut collector = DependenciesCollector::default();
+
+    for item in items {
+        collector.visit(db, &item);
+    }
+
+    collector.dependencies.into_values().collect()
+}
+
+fn visit_function_node(
+    db: &dyn SyntaxGroup,
+    node: &SyntaxNode,
+    scope: &mut FunctionScope,
+    let_type: Option<String>,
+) {
+    match node.kind(db) {
+        SyntaxKind::Param => {
+            let param = ast::Param::from_syntax_node(db, node.clone());
+            let (name, _, ty) = get_parameter_info(db, param);
+            scope.bindings.entry(name).or_default().push(ty);
+        }
+        SyntaxKind::StatementLet => {
+            let statement = ast::StatementLet::from_syntax_node(db, node.clone());
+            let rhs = statement.rhs(db);
+
+            let ty = match statement.type_clause(db) {
+                OptionTypeClause::TypeClause(clause) => Some(text(db, &clause.ty(db))),
+                OptionTypeClause::Empty(_) => inferred_type(db, &rhs),
+            };
+
+            if let (ast::Pattern::Identifier(pattern), Some(ty)) = (statement.pattern(db), &ty) {
+                let name = pattern.name(db).text(db).to_string();
+                scope.bindings.entry(name).or_default().push(ty.clone());
+            }
+
+            visit_function_node(db, &rhs.as_syntax_node(), scope, ty);
+            return;
+        }
+        SyntaxKind::ExprBinary => {
+            let expr = ast::ExprBinary::from_syntax_node(db, node.clone());
+
+            if let (ast::BinaryOperator::Dot(_), ast::Expr::FunctionCall(call)) =
+                (expr.op(db), expr.rhs(db))
+            {
+                record_call(db, &call, scope, let_type);
+            }
+        }
+        _ => {}
+    }
+
+    for child in node.get_children(db).iter() {
+        visit_function_node(db, child, scope, None);
+    }
+}
+
+fn record_call(
+    db: &dyn SyntaxGroup,
+    call: &ast::ExprFunctionCall,
+    scope: &mut FunctionScope,
+    let_type: Option<String>,
+) {
+    let Some(ast::PathSegment::Simple(segment)) = call.path(db).elements(db).last().cloned()
+    else {
+        return;
+    };
+
+    let Some((access, target, is_value)) = world_storage_method(&segment.ident(db).text(db))
+    else {
+        return;
+    };
+
+    let mut call_targets = Call { access, is_value, types: vec![], bindings: vec![] };
+
+    match target {
+        Target::LetType => call_targets.types.extend(let_type),
+        Target::Arg(index) => {
+            let args = call.arguments(db).arguments(db).elements(db);
+
+            if let Some(ast::ArgClause::Unnamed(arg)) = args.get(index).map(|a| a.arg_clause(db))
+            {
+                collect_targets(db, &arg.value(db).as_syntax_node(), &mut call_targets);
+            }
+        }
+    }
+
+    scope.calls.push(call_targets);
+}
+
+/// Collects the types and the bindings an argument may refer to.
+fn collect_targets(db: &dyn SyntaxGroup, node: &SyntaxNode, call: &mut Call) {
+    match node.kind(db) {
+        SyntaxKind::ExprStructCtorCall => {
+            let ctor = ast::ExprStructCtorCall::from_syntax_node(db, node.clone());
+            call.types.push(text(db, &ctor.path(db)));
+            return;
+        }
+        SyntaxKind::ExprPath => {
+            let path = ast::ExprPath::from_syntax_node(db, node.clone());
+
+            match &path.elements(db)[..] {
+                [ast::PathSegment::Simple(segment)] => {
+                    call.bindings.push(segment.ident(db).text(db).to_string());
+                }
+                segments => call.types.extend(model_generic_arg(db, segments)),
+            }
+
+            return;
+        }
+        SyntaxKind::ExprFunctionCall => {
+            let function_call = ast::ExprFunctionCall::from_syntax_node(db, node.clone());
+
+            // `Model::<M>::ptr_from_keys(...)` and similar, the arguments are the keys.
+            if let Some(ty) = model_generic_arg(db, &function_call.path(db).elements(db)) {
+                call.types.push(ty);
+                return;
+            }
+        }
+        _ => {}
+    }
+
+    for child in node.get_children(db).iter() {
+        collect_targets(db, child, call);
+    }
+}
+
+/// Returns the `M` of a `Model::<M>` path, if any.
+fn model_generic_arg(db: &dyn SyntaxGroup, segments: &[ast::PathSegment]) -> Option<String> {
+    segments.iter().find_map(|segment| match segment {
+        ast::PathSegment::WithGenericArgs(segment) if segment.ident(db).text(db) == "Model" => {
+            let args = segment.generic_args(db).generic_args(db).elements(db);
+            args.first().map(|arg| text(db, arg))
+        }
+        _ => None,
+    })
+}
+
+/// Infers the type of an untyped binding, if the expression is a (snapshot of a) struct
+/// constructor or a `Model::<M>` function call.
+fn inferred_type(db: &dyn SyntaxGroup, expr: &ast::Expr) -> Option<String> {
+    match expr {
+        ast::Expr::StructCtorCall(ctor) => Some(text(db, &ctor.path(db))),
+        ast::Expr::FunctionCall(call) => model_generic_arg(db, &call.path(db).elements(db)),
+        ast::Expr::Unary(unary) if matches!(unary.op(db), ast::UnaryOperator::At(_)) => {
+            inferred_type(db, &unary.expr(db))
+        }
+        _ => None,
+    }
+}
+
+fn text(db: &dyn SyntaxGroup, node: &impl TypedSyntaxNode) -> String {
+    node.as_syntax_node().get_text_without_trivia(db)
+}
+
+/// Extracts the model or event path from a type, unwrapping the snapshots and the containers of
+/// the batch methods. The module path is kept as written, to tell apart the structs with the same
+/// name. Returns `None` for types that can't be a model or an event.
+fn type_name(ty: &str, is_value: bool) -> Option<String> {
+    let mut ty = ty.trim().trim_start_matches('@').trim();
+
+    while let (Some(start), true) = (ty.find('<'), ty.ends_with('>')) {
+        let container = ty[..start].rsplit("::").next().unwrap_or_default().trim();
+
+        if !CONTAINER_TYPES.contains(&container) {
+            break;
+        }
+
+        ty = ty[start + 1..ty.len() - 1].trim().trim_start_matches('@').trim();
+    }
+
+    let ty = ty.split('<').next().unwrap_or_default().trim();
+    let (module_path, name) = match ty.rsplit_once("::") {
+        Some((module_path, name)) => (Some(module_path), name),
+        None => (None, ty),
+    };
+    let name = if is_value { name.strip_suffix("Value").unwrap_or(name) } else { name };
+
+    if !name.starts_with(|c: char| c.is_ascii_uppercase())
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+
+    Some(match module_path {
+        Some(module_path) => format!("{module_path}::{name}"),
+        None => name.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_parser::utils::SimpleParserDatabase;
+
+    use super::*;
+
+    const CONTRACT: &str = r#"
+        #[dojo::contract]
+        pub mod actions {
+            use dojo::model::{Model, ModelStorage, ModelValueStorage};
+            use dojo::event::EventStorage;
+
+            #[abi(embed_v0)]
+            impl ActionsImpl of IActions<ContractState> {
+                fn spawn(ref self: ContractState) {
+                    let mut world = self.world_default();
+                    world.write_model(@Moves { player, remaining: 99 });
+
+                    let position = Position { player, vec: Vec2 { x: 10, y: 10 } };
+                    world.write_model(@position);
+                }
+
+                fn move(ref self: ContractState, direction: Direction) {
+                    let mut world = self.world_default();
+                    let position: Position = world.read_model(player);
+                    let mut moves: MovesValue = world.read_value_from_id(move_id);
+                    world.write_value_from_id(move_id, @moves);
+                    world.emit_event(@Moved { player, direction });
+                }
+
+                fn rename(ref self: ContractState, name: ByteArray) {
+                    let mut world = self.world_default();
+                    let ptr = Model::<PlayerConfig>::ptr_from_keys(player);
+                    world.write_member(ptr, selector!("name"), name);
+                }
+
+                fn reset(ref self: ContractState, configs: Array<@PlayerConfig>) {
+                    let mut world = self.world_default();
+                    let positions: Array<Position> = world.read_models([player].span());
+                    world.erase_models(configs.span());
+                }
+            }
+        }
+    "#;
+
+    fn dependency(name: &str, read: bool, write: bool) -> Dependency {
+        Dependency { name: name.to_string(), read, write }
+    }
+
+    #[test]
+    fn test_contract_dependencies() {
+        let db = SimpleParserDatabase::default();
+        let node = db.parse_virtual(CONTRACT).unwrap();
+
+        assert_eq!(
+            contract_dependencies(&db, [node]),
+            vec![
+                dependency("Moved", false, true),
+                dependency("Moves", true, true),
+                dependency("PlayerConfig", false, true),
+                dependency("Position", true, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("@Position", false), Some("Position".to_string()));
+        assert_eq!(
+            type_name("models::Position", false),
+            Some("models::Position".to_string())
+        );
+        assert_eq!(type_name("models::MovesValue", true), Some("models::Moves".to_string()));
+        assert_eq!(type_name("Array<@Position>", false), Some("Position".to_string()));
+        assert_eq!(type_name("Span<ModelPtr<Position>>", false), Some("Position".to_string()));
+        assert_eq!(type_name("MovesValue", true), Some("Moves".to_string()));
+        assert_eq!(type_name("felt252", false), None);
+    }
+}
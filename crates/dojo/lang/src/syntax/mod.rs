@@ -1,3 +1,4 @@
+pub mod dependencies;
 pub mod self_param;
 pub mod utils;
 pub mod world_param;
@@ -45,6 +45,13 @@ pub fn is_name_valid(name: &str) -> bool {
     Regex::new(r"^[a-zA-Z0-9_]+$").unwrap().is_match(name)
 }
 
+/// Returns the name of the model storing the index `index_name` of the model `model_name`,
+/// declared with the `#[index]` attribute on the model members.
+pub fn get_index_model_name(model_name: &str, index_name: &str) -> String {
+    let index_name = index_name.split('_').map(capitalize).collect::<String>();
+    format!("{model_name}{index_name}Index")
+}
+
 /// Get the namespace and the name of a world element from its tag.
 pub fn split_tag(tag: &str) -> Result<(String, String)> {
     let parts: Vec<&str> = tag.split(TAG_SEPARATOR).collect();
@@ -127,6 +134,12 @@ mod tests {
         assert!(split_tag("inv-alid-namespace").is_err());
     }
 
+    #[test]
+    fn test_get_index_model_name() {
+        assert_eq!(get_index_model_name("Position", "tile"), "PositionTileIndex");
+        assert_eq!(get_index_model_name("Position", "grid_cell"), "PositionGridCellIndex");
+    }
+
     #[test]
     fn test_get_filename_from_tag_success() {
         assert_eq!(get_filename_from_tag("dojo-world"), "dojo-world");
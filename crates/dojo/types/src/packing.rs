@@ -118,6 +118,7 @@ pub fn parse_ty(data: &[Felt]) -> Result<Ty, ParseError> {
         3 => parse_tuple(&data[1..]),
         4 => parse_array(&data[1..]),
         5 => parse_byte_array(),
+        6 => parse_fixed_array(&data[1..]),
         _ => Err(ParseError::invalid_schema_with_msg(&format!(
             "Unsupported member type variant `{}`.",
             member_type
@@ -204,9 +205,10 @@ fn parse_member(data: &[Felt]) -> Result<schema::Member, ParseError> {
     let attributes = &data[slice_start..slice_end];
 
     let key = attributes.contains(&cairo_short_string_to_felt("key")?);
+    let packed = attributes.contains(&cairo_short_string_to_felt("packed")?);
     let ty = parse_ty(&data[slice_end..])?;
 
-    Ok(schema::Member { name, ty, key })
+    Ok(schema::Member { name, ty, key, packed })
 }
 
 fn parse_enum(data: &[Felt]) -> Result<Ty, ParseError> {
@@ -301,6 +303,27 @@ fn parse_array(data: &[Felt]) -> Result<Ty, ParseError> {
     Ok(Ty::Array(vec![item_ty]))
 }
 
+fn parse_fixed_array(data: &[Felt]) -> Result<Ty, ParseError> {
+    // The item type and the size are given by the first (and unique) element of the span:
+    // the span length, the item type and the size.
+    if data.len() < 3 {
+        return Err(ParseError::invalid_schema_with_msg(&format!(
+            "The function parse_fixed_array expects at least three felts: span len, item type \
+             and size. Input of size {} found.",
+            data.len()
+        )));
+    }
+
+    let size_felt = data[data.len() - 1];
+    let size: u32 = size_felt.to_u32().ok_or_else(|| PrimitiveError::ValueOutOfRange {
+        r#type: type_name::<u32>(),
+        value: size_felt,
+    })?;
+
+    let item_ty = parse_ty(&data[1..data.len() - 1])?;
+    Ok(Ty::FixedArray(vec![item_ty], size))
+}
+
 fn parse_byte_array() -> Result<Ty, ParseError> {
     Ok(Ty::ByteArray("".to_string()))
 }
@@ -346,6 +369,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_fixed_array_with_valid_value() {
+        let data = [
+            Felt::from(6),
+            Felt::ONE,
+            Felt::ZERO,
+            cairo_short_string_to_felt("u8").unwrap(),
+            Felt::from(8),
+        ];
+
+        assert_eq!(
+            parse_ty(&data).unwrap(),
+            Ty::FixedArray(vec![Ty::Primitive(Primitive::U8(None))], 8)
+        );
+    }
+
     #[test]
     fn parse_array_with_invalid_value() {
         let data = [Felt::default()];
@@ -20,6 +20,9 @@ pub struct Member {
     #[serde(rename = "member_type")]
     pub ty: Ty,
     pub key: bool,
+    /// Whether the member is declared `#[packed]`, sharing the field of its packed group.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub packed: bool,
 }
 
 impl Member {
@@ -51,6 +54,11 @@ pub enum Ty {
     Tuple(Vec<Ty>),
     Array(Vec<Ty>),
     ByteArray(String),
+    /// A fixed-size array `[T; N]`, as its items and its size.
+    ///
+    /// Like [`Ty::Array`], the schema holds one item used as the template of the items, which
+    /// are filled when the values are deserialized.
+    FixedArray(Vec<Ty>, u32),
 }
 
 impl Ty {
@@ -68,6 +76,10 @@ impl Ty {
                 }
             }
             Ty::ByteArray(_) => "ByteArray".to_string(),
+            Ty::FixedArray(ty, size) => {
+                let inner = ty.first().map(|inner| inner.name()).unwrap_or_default();
+                format!("[{inner}; {size}]")
+            }
         }
     }
 
@@ -75,6 +87,20 @@ impl Ty {
         TyIter { stack: vec![self] }
     }
 
+    /// Returns the size in bits of a type which can be declared `#[packed]` in a struct, to be
+    /// bit-packed with the next `#[packed]` members. Returns `None` otherwise.
+    pub fn packed_member_bits(&self) -> Option<u8> {
+        match self {
+            Ty::Primitive(Primitive::Bool(_)) => Some(1),
+            Ty::Primitive(Primitive::U8(_)) => Some(8),
+            Ty::Primitive(Primitive::U16(_)) => Some(16),
+            Ty::Primitive(Primitive::U32(_)) => Some(32),
+            Ty::Primitive(Primitive::U64(_)) => Some(64),
+            Ty::Primitive(Primitive::U128(_)) => Some(128),
+            _ => None,
+        }
+    }
+
     /// If the `Ty` is a primitive, returns the associated [`Primitive`]. Returns `None`
     /// otherwise.
     pub fn as_primitive(&self) -> Option<&Primitive> {
@@ -116,6 +142,15 @@ impl Ty {
         }
     }
 
+    /// If the `Ty` is a fixed-size array, returns the associated [`Vec<Ty>`] and size. Returns
+    /// `None` otherwise.
+    pub fn as_fixed_array(&self) -> Option<(&Vec<Ty>, u32)> {
+        match self {
+            Ty::FixedArray(tys, size) => Some((tys, *size)),
+            _ => None,
+        }
+    }
+
     /// If the `Ty` is a byte array, returns the associated [`String`]. Returns `None` otherwise.
     pub fn as_byte_array(&self) -> Option<&String> {
         match self {
@@ -167,6 +202,12 @@ impl Ty {
 
                     felts.extend(ByteArray::cairo_serialize(&bytearray))
                 }
+                // The size of a fixed-size array is known, so it's not serialized.
+                Ty::FixedArray(items_ty, _) => {
+                    for item_ty in items_ty {
+                        serialize_inner(item_ty, felts)?;
+                    }
+                }
             }
             Ok(())
         }
@@ -230,6 +271,16 @@ impl Ty {
 
                 *bytes = ByteArray::to_string(&bytearray)?;
             }
+            Ty::FixedArray(items_ty, size) => {
+                let item_ty = items_ty[0].clone();
+                items_ty.clear();
+
+                for _ in 0..*size {
+                    let mut cur_item_ty = item_ty.clone();
+                    cur_item_ty.deserialize(felts)?;
+                    items_ty.push(cur_item_ty);
+                }
+            }
         }
         Ok(())
     }
@@ -249,6 +300,7 @@ impl Ty {
                                 name: m1.name.clone(),
                                 ty: diff_ty,
                                 key: m1.key,
+                                packed: m1.packed,
                             })
                         } else {
                             // Member doesn't exist in s2
@@ -313,6 +365,13 @@ impl Ty {
                     Some(Ty::Array(a1.clone()))
                 }
             }
+            (Ty::FixedArray(a1, s1), Ty::FixedArray(a2, s2)) => {
+                if a1 == a2 && s1 == s2 {
+                    None
+                } else {
+                    Some(Ty::FixedArray(a1.clone(), *s1))
+                }
+            }
             (Ty::ByteArray(b1), Ty::ByteArray(b2)) => {
                 if b1 == b2 {
                     None
@@ -369,7 +428,7 @@ impl Ty {
                     option.name.clone(): option.ty.to_json_value()?
                 }))
             }
-            Ty::Array(items) => {
+            Ty::Array(items) | Ty::FixedArray(items, _) => {
                 let values: Result<Vec<_>, _> = items.iter().map(|ty| ty.to_json_value()).collect();
                 Ok(json!(values?))
             }
@@ -490,6 +549,19 @@ impl Ty {
                     items.push(item);
                 }
             }
+            (Ty::FixedArray(items, size), JsonValue::Array(values)) => {
+                if values.len() != *size as usize {
+                    return Err(PrimitiveError::TypeMismatch);
+                }
+
+                let template = items[0].clone();
+                items.clear();
+                for value in values {
+                    let mut item = template.clone();
+                    item.from_json_value(value)?;
+                    items.push(item);
+                }
+            }
             (Ty::Tuple(items), JsonValue::Array(values)) => {
                 if items.len() != values.len() {
                     return Err(PrimitiveError::TypeMismatch);
@@ -559,6 +631,7 @@ impl std::fmt::Display for Ty {
                     Some(format!("tuple({})", tuple.iter().map(|ty| ty.name()).join(", ")))
                 }
                 Ty::Array(items_ty) => Some(format!("Array<{}>", items_ty[0].name())),
+                Ty::FixedArray(..) => Some(ty.name()),
                 Ty::ByteArray(_) => Some("ByteArray".to_string()),
                 _ => None,
             })
@@ -584,6 +657,54 @@ impl Struct {
     pub fn keys(&self) -> Vec<Member> {
         self.children.iter().filter(|m| m.key).cloned().collect()
     }
+
+    /// Groups the non-key members by the field storing them.
+    ///
+    /// Consecutive `#[packed]` members share a single field, named after the first member of the
+    /// group. The other members have their own field.
+    pub fn field_members(&self) -> Vec<Vec<&Member>> {
+        let mut groups: Vec<Vec<&Member>> = vec![];
+        let mut in_group = false;
+
+        for member in self.children.iter().filter(|m| !m.key) {
+            match groups.last_mut() {
+                Some(group) if in_group && member.packed => group.push(member),
+                _ => groups.push(vec![member]),
+            }
+
+            in_group = member.packed;
+        }
+
+        groups
+    }
+
+    /// Deserializes the members of a model record or an event, the key members from `keys` and
+    /// the other members from `values`, whatever their order of declaration.
+    ///
+    /// The members which have no value left, like the members added by an upgrade when the
+    /// record or the event has been written before it, are left unset. Their names are returned.
+    pub fn deserialize_members(
+        &mut self,
+        keys: &[Felt],
+        values: &[Felt],
+    ) -> Result<Vec<String>, PrimitiveError> {
+        let mut keys = keys.to_vec();
+        let mut values = values.to_vec();
+        let mut missing = vec![];
+
+        for member in &mut self.children {
+            let felts = if member.key { &mut keys } else { &mut values };
+
+            if felts.is_empty() {
+                missing.push(member.name.clone());
+                continue;
+            }
+
+            member.ty.deserialize(felts)?;
+        }
+
+        Ok(missing)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -753,6 +874,7 @@ mod tests {
                     name: "i8_field".to_string(),
                     ty: Ty::Primitive(Primitive::I8(Some(-42))),
                     key: false,
+                    packed: false,
                 },
                 "  i8_field: i8 = -42",
             ),
@@ -761,6 +883,7 @@ mod tests {
                     name: "i16_field".to_string(),
                     ty: Ty::Primitive(Primitive::I16(Some(-1000))),
                     key: false,
+                    packed: false,
                 },
                 "  i16_field: i16 = -1000",
             ),
@@ -769,6 +892,7 @@ mod tests {
                     name: "i32_field".to_string(),
                     ty: Ty::Primitive(Primitive::I32(Some(-100000))),
                     key: false,
+                    packed: false,
                 },
                 "  i32_field: i32 = -100000",
             ),
@@ -777,6 +901,7 @@ mod tests {
                     name: "i64_field".to_string(),
                     ty: Ty::Primitive(Primitive::I64(Some(-1000000000))),
                     key: false,
+                    packed: false,
                 },
                 "  i64_field: i64 = -1000000000",
             ),
@@ -785,6 +910,7 @@ mod tests {
                     name: "i128_field".to_string(),
                     ty: Ty::Primitive(Primitive::I128(Some(-1000000000000000000))),
                     key: false,
+                    packed: false,
                 },
                 "  i128_field: i128 = -1000000000000000000",
             ),
@@ -793,6 +919,7 @@ mod tests {
                     name: "u8_field".to_string(),
                     ty: Ty::Primitive(Primitive::U8(Some(255))),
                     key: false,
+                    packed: false,
                 },
                 "  u8_field: u8 = 255",
             ),
@@ -801,6 +928,7 @@ mod tests {
                     name: "u16_field".to_string(),
                     ty: Ty::Primitive(Primitive::U16(Some(65535))),
                     key: false,
+                    packed: false,
                 },
                 "  u16_field: u16 = 65535",
             ),
@@ -809,6 +937,7 @@ mod tests {
                     name: "u32_field".to_string(),
                     ty: Ty::Primitive(Primitive::U32(Some(4294967295))),
                     key: false,
+                    packed: false,
                 },
                 "  u32_field: u32 = 4294967295",
             ),
@@ -817,6 +946,7 @@ mod tests {
                     name: "u64_field".to_string(),
                     ty: Ty::Primitive(Primitive::U64(Some(18446744073709551615))),
                     key: false,
+                    packed: false,
                 },
                 "  u64_field: u64 = 18446744073709551615",
             ),
@@ -827,6 +957,7 @@ mod tests {
                         340282366920938463463374607431768211455,
                     ))),
                     key: false,
+                    packed: false,
                 },
                 "  u128_field: u128 = 340282366920938463463374607431768211455",
             ),
@@ -835,6 +966,7 @@ mod tests {
                     name: "u256_field".to_string(),
                     ty: Ty::Primitive(Primitive::U256(Some(U256::from_u128(123456789_u128)))),
                     key: false,
+                    packed: false,
                 },
                 "  u256_field: u256 = \
                  00000000000000000000000000000000000000000000000000000000075BCD15",
@@ -844,6 +976,7 @@ mod tests {
                     name: "bool_field".to_string(),
                     ty: Ty::Primitive(Primitive::Bool(Some(true))),
                     key: false,
+                    packed: false,
                 },
                 "  bool_field: bool = true",
             ),
@@ -854,6 +987,7 @@ mod tests {
                         Felt::from_hex("0x123abc").unwrap(),
                     ))),
                     key: false,
+                    packed: false,
                 },
                 "  felt252_field: felt252 = 0x123abc",
             ),
@@ -869,6 +1003,7 @@ mod tests {
                         ],
                     }),
                     key: false,
+                    packed: false,
                 },
                 "  enum_field: TestEnum = OptionB",
             ),
@@ -889,16 +1024,19 @@ mod tests {
                     name: "field1".to_string(),
                     ty: Ty::Primitive(Primitive::U32(None)),
                     key: false,
+                    packed: false,
                 },
                 Member {
                     name: "field2".to_string(),
                     ty: Ty::Primitive(Primitive::U32(None)),
                     key: false,
+                    packed: false,
                 },
                 Member {
                     name: "field3".to_string(),
                     ty: Ty::Primitive(Primitive::U32(None)),
                     key: false,
+                    packed: false,
                 },
             ],
         });
@@ -909,6 +1047,7 @@ mod tests {
                 name: "field1".to_string(),
                 ty: Ty::Primitive(Primitive::U32(None)),
                 key: false,
+                packed: false,
             }],
         });
 
@@ -951,4 +1090,86 @@ mod tests {
         let same_struct = struct2.diff(&struct2);
         assert!(same_struct.is_none());
     }
+
+    #[test]
+    fn test_fixed_array() {
+        let row = Ty::FixedArray(vec![Ty::Primitive(Primitive::U8(None))], 2);
+        let mut grid = Ty::FixedArray(vec![row], 2);
+        assert_eq!(grid.name(), "[[u8; 2]; 2]");
+
+        let mut felts = (1..=4).map(Felt::from).collect::<Vec<_>>();
+        grid.deserialize(&mut felts).unwrap();
+        assert!(felts.is_empty());
+
+        assert_eq!(grid.serialize().unwrap(), (1..=4).map(Felt::from).collect::<Vec<_>>());
+        assert_eq!(grid.to_json_value().unwrap(), json!([[1, 2], [3, 4]]));
+
+        let mut template = Ty::FixedArray(vec![Ty::Primitive(Primitive::U8(None))], 2);
+        assert!(template.from_json_value(json!([1, 2, 3])).is_err());
+        template.from_json_value(json!([5, 6])).unwrap();
+        assert_eq!(template.as_fixed_array().unwrap().0.len(), 2);
+    }
+
+    #[test]
+    fn test_struct_field_members() {
+        let member = |name: &str, ty: Ty, key: bool, packed: bool| Member {
+            name: name.to_string(),
+            ty,
+            key,
+            packed,
+        };
+        let s = Struct {
+            name: "Flags".to_string(),
+            children: vec![
+                member("alive", Ty::Primitive(Primitive::Bool(None)), false, true),
+                member("player", Ty::Primitive(Primitive::ContractAddress(None)), true, false),
+                member("level", Ty::Primitive(Primitive::U8(None)), false, true),
+                member("amount", Ty::Primitive(Primitive::U256(None)), false, false),
+                member("xp", Ty::Primitive(Primitive::U32(None)), false, true),
+                member("name", Ty::ByteArray("".to_string()), false, false),
+                member("rank", Ty::Primitive(Primitive::U8(None)), false, false),
+            ],
+        };
+
+        let names = |groups: Vec<Vec<&Member>>| {
+            groups
+                .iter()
+                .map(|g| g.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", "))
+                .collect::<Vec<_>>()
+        };
+
+        // The keys don't split a group, and a group may have a single member.
+        assert_eq!(names(s.field_members()), vec!["alive, level", "amount", "xp", "name", "rank"]);
+    }
+
+    #[test]
+    fn test_struct_deserialize_members() {
+        let member = |name: &str, key: bool| Member {
+            name: name.to_string(),
+            ty: Ty::Primitive(Primitive::U32(None)),
+            key,
+            packed: false,
+        };
+
+        // The key member is not declared first.
+        let schema = Struct {
+            name: "Tile".to_string(),
+            children: vec![member("owner", false), member("x", true), member("level", false)],
+        };
+
+        let mut tile = schema.clone();
+        let missing = tile.deserialize_members(&[Felt::from(1)], &[2.into(), 3.into()]).unwrap();
+
+        assert!(missing.is_empty());
+        assert_eq!(tile.get("x"), Some(&Ty::Primitive(Primitive::U32(Some(1)))));
+        assert_eq!(tile.get("owner"), Some(&Ty::Primitive(Primitive::U32(Some(2)))));
+        assert_eq!(tile.get("level"), Some(&Ty::Primitive(Primitive::U32(Some(3)))));
+
+        // Written before `level` was added.
+        let mut tile = schema.clone();
+        let missing = tile.deserialize_members(&[Felt::from(1)], &[2.into()]).unwrap();
+
+        assert_eq!(missing, vec!["level".to_string()]);
+        assert_eq!(tile.get("level"), Some(&Ty::Primitive(Primitive::U32(None))));
+    }
 }
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-/// Represents a system's model dependency.
+/// Represents a system's model or event dependency.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Dependency {
-    /// Name of the model.
+    /// Name of the model or event, or its tag once resolved.
     pub name: String,
     pub read: bool,
     pub write: bool,
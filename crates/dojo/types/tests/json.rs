@@ -7,8 +7,18 @@ fn serialize_ty_to_json() {
     let ty = Ty::Struct(Struct {
         name: "Position".into(),
         children: vec![
-            Member { name: "x".into(), key: false, ty: Ty::Primitive(Primitive::U8(Some(128))) },
-            Member { name: "y".into(), key: false, ty: Ty::Primitive(Primitive::U64(Some(2048))) },
+            Member {
+                name: "x".into(),
+                key: false,
+                ty: Ty::Primitive(Primitive::U8(Some(128))),
+                packed: false,
+            },
+            Member {
+                name: "y".into(),
+                key: false,
+                ty: Ty::Primitive(Primitive::U64(Some(2048))),
+                packed: false,
+            },
             Member {
                 name: "kind".into(),
                 key: false,
@@ -20,6 +30,7 @@ fn serialize_ty_to_json() {
                         EnumOption { name: "Kind2".into(), ty: Ty::Tuple(vec![]) },
                     ],
                 }),
+                packed: false,
             },
         ],
     });
@@ -149,8 +160,18 @@ fn deserialize_ty_from_json() {
     let expected_value = Ty::Struct(Struct {
         name: "Position".into(),
         children: vec![
-            Member { name: "x".into(), key: false, ty: Ty::Primitive(Primitive::U8(Some(128))) },
-            Member { name: "y".into(), key: false, ty: Ty::Primitive(Primitive::U64(Some(2048))) },
+            Member {
+                name: "x".into(),
+                key: false,
+                ty: Ty::Primitive(Primitive::U8(Some(128))),
+                packed: false,
+            },
+            Member {
+                name: "y".into(),
+                key: false,
+                ty: Ty::Primitive(Primitive::U64(Some(2048))),
+                packed: false,
+            },
             Member {
                 name: "kind".into(),
                 key: false,
@@ -162,6 +183,7 @@ fn deserialize_ty_from_json() {
                         EnumOption { name: "Kind2".into(), ty: Ty::Tuple(vec![]) },
                     ],
                 }),
+                packed: false,
             },
         ],
     });
@@ -169,3 +191,30 @@ fn deserialize_ty_from_json() {
     let actual_value: Ty = serde_json::from_value(json).unwrap();
     assert_eq!(actual_value, expected_value)
 }
+
+#[test]
+fn serialize_fixed_array_ty_to_json() {
+    let ty = Ty::FixedArray(vec![Ty::Primitive(Primitive::U8(Some(1)))], 8);
+
+    let value = serde_json::to_value(&ty).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "fixedarray",
+            "content": [
+                [
+                    {
+                        "type": "primitive",
+                        "content": {
+                            "scalar_type": "u8",
+                            "value": 1
+                        }
+                    }
+                ],
+                8
+            ]
+        })
+    );
+
+    assert_eq!(serde_json::from_value::<Ty>(value).unwrap(), ty);
+}
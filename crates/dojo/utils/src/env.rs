@@ -7,3 +7,5 @@ pub const DOJO_WORLD_ADDRESS_ENV_VAR: &str = "DOJO_WORLD_ADDRESS";
 pub const IPFS_URL_ENV_VAR: &str = "DOJO_IPFS_URL";
 pub const IPFS_USERNAME_ENV_VAR: &str = "DOJO_IPFS_USERNAME";
 pub const IPFS_PASSWORD_ENV_VAR: &str = "DOJO_IPFS_PASSWORD";
+pub const DOJO_PAYMASTER_ADDRESS_ENV_VAR: &str = "DOJO_PAYMASTER_ADDRESS";
+pub const DOJO_PAYMASTER_PRIVATE_KEY_ENV_VAR: &str = "DOJO_PAYMASTER_PRIVATE_KEY";
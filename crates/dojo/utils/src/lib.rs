@@ -7,6 +7,7 @@ pub use tx::declarer::*;
 pub use tx::deployer::*;
 pub use tx::error::TransactionError;
 pub use tx::invoker::*;
+pub use tx::outside_execution::*;
 pub use tx::waiter::*;
 pub use tx::*;
 
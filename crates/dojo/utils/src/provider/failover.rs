@@ -0,0 +1,385 @@
+//! A JSON-RPC transport spreading the requests over several RPC endpoints.
+//!
+//! The requests are sent to the active endpoint, and fail over to the next one when the endpoint
+//! errors or throttles the request. Idempotent reads are retried with an exponential backoff once
+//! all the endpoints failed, and a client-side requests per second budget can be enforced to
+//! avoid being throttled in the first place.
+
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use starknet::providers::jsonrpc::{
+    HttpTransport, HttpTransportError, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport,
+};
+use starknet::providers::{ProviderRequestData, Url};
+use tracing::{debug, warn};
+
+/// JSON-RPC error codes used by the providers to signal a throttled request.
+const RATE_LIMITED_ERROR_CODES: [i64; 2] = [-32005, 429];
+
+/// A [`JsonRpcTransport`] with failover, retries and rate limiting over several endpoints.
+#[derive(Debug, Clone)]
+pub struct FailoverTransport {
+    client: Client,
+    endpoints: Vec<Endpoint>,
+    /// The index of the endpoint the requests are sent to first.
+    active: Arc<AtomicUsize>,
+    max_retries: u32,
+    backoff: Duration,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+#[derive(Debug, Clone)]
+struct Endpoint {
+    url: Url,
+    transport: HttpTransport,
+}
+
+impl FailoverTransport {
+    /// The default number of retries of the idempotent requests.
+    pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+    /// The default delay before the first retry, doubled at each retry.
+    pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+
+    /// Creates a new transport with a single endpoint.
+    pub fn new(url: Url, client: Client) -> Self {
+        Self {
+            endpoints: vec![Endpoint::new(url, &client)],
+            client,
+            active: Arc::new(AtomicUsize::new(0)),
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+            backoff: Self::DEFAULT_BACKOFF,
+            rate_limiter: None,
+        }
+    }
+
+    /// Adds an endpoint to fail over to, after the ones already added.
+    pub fn with_fallback(mut self, url: Url) -> Self {
+        self.endpoints.push(Endpoint::new(url, &self.client));
+        self
+    }
+
+    /// Sets the number of retries of the idempotent requests, once all the endpoints failed.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry, doubled at each retry.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Limits the number of requests sent per second, over all the endpoints.
+    pub fn with_rate_limit(mut self, requests_per_second: NonZeroU32) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(requests_per_second)));
+        self
+    }
+
+    /// Adds a header to the requests sent to all the endpoints.
+    pub fn add_header(&mut self, name: String, value: String) {
+        for endpoint in self.endpoints.iter_mut() {
+            endpoint.transport.add_header(name.clone(), value.clone());
+        }
+    }
+
+    /// Returns the URLs of the endpoints, in failover order.
+    pub fn urls(&self) -> impl Iterator<Item = &Url> {
+        self.endpoints.iter().map(|e| &e.url)
+    }
+
+    /// Returns the URL of the endpoint the requests are currently sent to.
+    pub fn active_url(&self) -> &Url {
+        &self.endpoints[self.active.load(Ordering::Relaxed)].url
+    }
+
+    /// Sends a request to the endpoints until one serves it.
+    ///
+    /// Requests that are not idempotent are only sent once to the active endpoint, to never
+    /// submit a transaction twice.
+    async fn dispatch<'a, T, F, Fut>(
+        &'a self,
+        method: &str,
+        idempotent: bool,
+        send: F,
+        is_throttled: fn(&T) -> bool,
+    ) -> Result<T, HttpTransportError>
+    where
+        F: Fn(&'a HttpTransport) -> Fut + Send + Sync,
+        Fut: Future<Output = Result<T, HttpTransportError>> + Send,
+        T: Send,
+    {
+        let (attempts, tries) = if idempotent {
+            (self.max_retries + 1, self.endpoints.len())
+        } else {
+            (1, 1)
+        };
+
+        let mut last_result = None;
+
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                let delay = self.backoff.saturating_mul(2u32.saturating_pow(attempt - 1));
+                debug!(method, attempt, ?delay, "Retrying RPC request.");
+                tokio::time::sleep(delay).await;
+            }
+
+            let active = self.active.load(Ordering::Relaxed);
+
+            for i in 0..tries {
+                let index = (active + i) % self.endpoints.len();
+                let endpoint = &self.endpoints[index];
+
+                if let Some(rate_limiter) = &self.rate_limiter {
+                    rate_limiter.acquire().await;
+                }
+
+                match send(&endpoint.transport).await {
+                    Ok(res) if !is_throttled(&res) => {
+                        if index != active {
+                            warn!(
+                                from = %self.endpoints[active].url,
+                                to = %endpoint.url,
+                                "Failing over to another RPC endpoint."
+                            );
+                            self.active.store(index, Ordering::Relaxed);
+                        }
+
+                        debug!(endpoint = %endpoint.url, method, "RPC request served.");
+                        return Ok(res);
+                    }
+                    Ok(res) => {
+                        warn!(endpoint = %endpoint.url, method, "RPC request throttled.");
+                        last_result = Some(Ok(res));
+                    }
+                    Err(error) => {
+                        warn!(endpoint = %endpoint.url, method, %error, "RPC request failed.");
+                        last_result = Some(Err(error));
+                    }
+                }
+            }
+        }
+
+        last_result.expect("at least one request is sent")
+    }
+}
+
+impl Endpoint {
+    fn new(url: Url, client: &Client) -> Self {
+        Self { transport: HttpTransport::new_with_client(url.clone(), client.clone()), url }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl JsonRpcTransport for FailoverTransport {
+    type Error = HttpTransportError;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let method_name = format!("{:?}", method);
+        let params = &params;
+
+        self.dispatch(
+            &method_name,
+            !is_write(&method),
+            |transport| transport.send_request(method, params),
+            is_throttled,
+        )
+        .await
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        let requests = requests.as_ref();
+        let idempotent = !requests.iter().any(is_write_request);
+
+        self.dispatch(
+            "batch",
+            idempotent,
+            |transport| transport.send_requests(requests),
+            |responses: &Vec<JsonRpcResponse<serde_json::Value>>| {
+                responses.iter().any(is_throttled)
+            },
+        )
+        .await
+    }
+}
+
+/// Spaces the requests evenly to stay within a requests per second budget.
+#[derive(Debug)]
+struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: NonZeroU32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests_per_second.get(),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until a request can be sent.
+    async fn acquire(&self) {
+        let wait = {
+            let mut next_slot = self.next_slot.lock().expect("poisoned lock");
+            let now = Instant::now();
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + self.interval;
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+fn is_write(method: &JsonRpcMethod) -> bool {
+    matches!(
+        method,
+        JsonRpcMethod::AddInvokeTransaction
+            | JsonRpcMethod::AddDeclareTransaction
+            | JsonRpcMethod::AddDeployAccountTransaction
+    )
+}
+
+fn is_write_request(request: &ProviderRequestData) -> bool {
+    matches!(
+        request,
+        ProviderRequestData::AddInvokeTransaction(_)
+            | ProviderRequestData::AddDeclareTransaction(_)
+            | ProviderRequestData::AddDeployAccountTransaction(_)
+    )
+}
+
+fn is_throttled<T>(response: &JsonRpcResponse<T>) -> bool {
+    match response {
+        JsonRpcResponse::Success { .. } => false,
+        JsonRpcResponse::Error { error, .. } => RATE_LIMITED_ERROR_CODES.contains(&error.code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+
+    use starknet::core::types::Felt;
+    use starknet::providers::{JsonRpcClient, Provider};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const CHAIN_ID_RESPONSE: &str = r#"{"jsonrpc":"2.0","id":1,"result":"0x534e5f5345504f4c4941"}"#;
+
+    /// Starts an RPC endpoint stub answering every request with the given status and body, and
+    /// returns its URL and the number of requests it received.
+    async fn endpoint_stub(status: &'static str, body: &'static str) -> (Url, Arc<AtomicU32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 8192];
+                let _ = stream.read(&mut buf).await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let response = format!(
+                    "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: \
+                     {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn transport(url: Url) -> FailoverTransport {
+        FailoverTransport::new(url, Client::new()).with_backoff(Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_the_next_endpoint() {
+        let (throttled, throttled_requests) = endpoint_stub("429 Too Many Requests", "").await;
+        let (healthy, healthy_requests) = endpoint_stub("200 OK", CHAIN_ID_RESPONSE).await;
+
+        let transport = transport(throttled).with_fallback(healthy.clone());
+        let provider = JsonRpcClient::new(transport.clone());
+
+        let chain_id = provider.chain_id().await.unwrap();
+        assert_eq!(chain_id, Felt::from_hex_unchecked("0x534e5f5345504f4c4941"));
+        assert_eq!(transport.active_url(), &healthy);
+
+        // The next requests are directly sent to the endpoint that served the last one.
+        provider.chain_id().await.unwrap();
+        assert_eq!(throttled_requests.load(Ordering::SeqCst), 1);
+        assert_eq!(healthy_requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_reads_until_max_retries() {
+        let (url, requests) = endpoint_stub("503 Service Unavailable", "").await;
+        let provider = JsonRpcClient::new(transport(url).with_max_retries(2));
+
+        assert!(provider.chain_id().await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_throttled_json_rpc_errors() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"Limit exceeded"}}"#;
+        let (url, requests) = endpoint_stub("200 OK", body).await;
+        let provider = JsonRpcClient::new(transport(url).with_max_retries(1));
+
+        assert!(provider.chain_id().await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn writes_are_not_idempotent() {
+        assert!(is_write(&JsonRpcMethod::AddInvokeTransaction));
+        assert!(!is_write(&JsonRpcMethod::GetEvents));
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_spaces_requests() {
+        let rate_limiter = RateLimiter::new(NonZeroU32::new(20).unwrap());
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            rate_limiter.acquire().await;
+        }
+
+        // The first request is sent immediately, the 4 others are spaced by 50ms.
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
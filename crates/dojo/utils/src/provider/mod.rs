@@ -1,3 +1,6 @@
+mod failover;
+
+pub use failover::*;
 use starknet::core::types::{BlockId, BlockTag};
 use starknet::providers::Provider;
 use tracing::trace;
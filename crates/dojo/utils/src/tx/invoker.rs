@@ -1,11 +1,16 @@
 //! Invoker to invoke contracts.
 
+use std::time::Duration;
+
 use starknet::accounts::ConnectedAccount;
-use starknet::core::types::Call;
+use starknet::core::types::{Call, Felt};
 use tracing::trace;
 
 use super::TransactionResult;
-use crate::{TransactionError, TransactionExt, TransactionWaiter, TxnConfig};
+use crate::{
+    OutsideExecution, SignedOutsideExecution, TransactionError, TransactionExt, TransactionWaiter,
+    TxnConfig,
+};
 
 #[derive(Debug)]
 pub struct Invoker<A>
@@ -98,6 +103,30 @@ where
         Ok(TransactionResult::Hash(tx.transaction_hash))
     }
 
+    /// Builds a SNIP-9 outside execution with all the calls added to the invoker.
+    ///
+    /// The returned payload has to be signed by the account on which behalf the calls are
+    /// executed, and then submitted by `caller` (or any account if
+    /// [`crate::ANY_CALLER`] is used) with [`Invoker::relay`].
+    pub fn outside_execution(&self, caller: Felt, validity: Duration) -> OutsideExecution {
+        OutsideExecution::new(caller, self.calls.clone(), validity)
+    }
+
+    /// Submits a signed outside execution, the invoker's account acting as the relayer
+    /// and paying the fees of the transaction.
+    pub async fn relay(
+        &self,
+        outside_execution: &SignedOutsideExecution,
+    ) -> Result<TransactionResult, TransactionError<A::SignError>> {
+        trace!(
+            signer = format!("{:#066x}", outside_execution.signer_address),
+            relayer = format!("{:#066x}", self.account.address()),
+            "Relay outside execution."
+        );
+
+        self.invoke(outside_execution.to_call()).await
+    }
+
     /// Invokes all the calls individually, usually used for debugging if a multicall failed.
     ///
     /// The order of the calls is the same as the order of the calls added to the invoker.
@@ -2,6 +2,7 @@ pub mod declarer;
 pub mod deployer;
 pub mod error;
 pub mod invoker;
+pub mod outside_execution;
 pub mod waiter;
 
 use std::fmt;
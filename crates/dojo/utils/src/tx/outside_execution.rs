@@ -0,0 +1,298 @@
+//! SNIP-9 (V2) outside execution, used to have a relayer (paymaster) account submit, and pay for,
+//! calls signed by another account.
+//!
+//! Reference: <https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-9.md>
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{Call, Felt};
+use starknet::core::utils::{cairo_short_string_to_felt, get_selector_from_name};
+use starknet::macros::{selector, short_string};
+use starknet::signers::Signer;
+use starknet_crypto::poseidon_hash_many;
+
+/// The `caller` value to use when any account is allowed to submit the outside execution.
+pub const ANY_CALLER: Felt = short_string!("ANY_CALLER");
+
+/// The entrypoint exposed by SNIP-9 V2 compliant accounts.
+pub const EXECUTE_FROM_OUTSIDE_V2: &str = "execute_from_outside_v2";
+
+/// The default validity window of an outside execution.
+pub const DEFAULT_VALIDITY: Duration = Duration::from_secs(60 * 60);
+
+/// SNIP-12 (revision 1) type hash of the `StarknetDomain` struct.
+const STARKNET_DOMAIN_TYPE_HASH: Felt = selector!(
+    "\"StarknetDomain\"(\"name\":\"shortstring\",\"version\":\"shortstring\",\"chainId\":\"\
+     shortstring\",\"revision\":\"shortstring\")"
+);
+
+/// SNIP-12 (revision 1) type hash of the `OutsideExecution` struct.
+const OUTSIDE_EXECUTION_TYPE_HASH: Felt = selector!(
+    "\"OutsideExecution\"(\"Caller\":\"ContractAddress\",\"Nonce\":\"felt\",\"Execute \
+     After\":\"u128\",\"Execute Before\":\"u128\",\"Calls\":\"Call*\")\"Call\"(\"To\":\"\
+     ContractAddress\",\"Selector\":\"selector\",\"Calldata\":\"felt*\")"
+);
+
+/// SNIP-12 (revision 1) type hash of the `Call` struct.
+const CALL_TYPE_HASH: Felt = selector!(
+    "\"Call\"(\"To\":\"ContractAddress\",\"Selector\":\"selector\",\"Calldata\":\"felt*\")"
+);
+
+/// A call part of an outside execution.
+///
+/// Mirrors [`Call`], which can't be (de)serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutsideCall {
+    pub to: Felt,
+    pub selector: Felt,
+    pub calldata: Vec<Felt>,
+}
+
+impl From<Call> for OutsideCall {
+    fn from(call: Call) -> Self {
+        Self { to: call.to, selector: call.selector, calldata: call.calldata }
+    }
+}
+
+impl From<OutsideCall> for Call {
+    fn from(call: OutsideCall) -> Self {
+        Self { to: call.to, selector: call.selector, calldata: call.calldata }
+    }
+}
+
+/// The SNIP-9 V2 `OutsideExecution` struct.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutsideExecution {
+    /// The only address allowed to submit the outside execution, or [`ANY_CALLER`].
+    pub caller: Felt,
+    /// A nonce that must not have been used before by the signer account.
+    /// V2 nonces are not sequential.
+    pub nonce: Felt,
+    /// The timestamp (in seconds) after which the execution is valid.
+    pub execute_after: u64,
+    /// The timestamp (in seconds) before which the execution is valid.
+    pub execute_before: u64,
+    /// The calls to execute on behalf of the signer account.
+    pub calls: Vec<OutsideCall>,
+}
+
+impl OutsideExecution {
+    /// Creates a new outside execution valid from now and for the given duration.
+    ///
+    /// The nonce is derived from the current time, which is enough to avoid collisions
+    /// for a single signer.
+    pub fn new(caller: Felt, calls: Vec<Call>, validity: Duration) -> Self {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards");
+
+        Self {
+            caller,
+            nonce: Felt::from(now.as_nanos()),
+            // Gives some slack for the clock of the sequencer.
+            execute_after: now.as_secs().saturating_sub(60),
+            execute_before: (now + validity).as_secs(),
+            calls: calls.into_iter().map(OutsideCall::from).collect(),
+        }
+    }
+
+    /// Computes the SNIP-12 (revision 1) message hash to be signed by `signer_address`.
+    pub fn message_hash(&self, chain_id: Felt, signer_address: Felt) -> Felt {
+        poseidon_hash_many(&[
+            cairo_short_string_to_felt("StarkNet Message").unwrap(),
+            domain_hash(chain_id),
+            signer_address,
+            self.struct_hash(),
+        ])
+    }
+
+    /// Signs the outside execution with the given signer, on behalf of `signer_address`.
+    pub async fn sign<S>(
+        self,
+        signer: &S,
+        signer_address: Felt,
+        chain_id: Felt,
+    ) -> Result<SignedOutsideExecution, S::SignError>
+    where
+        S: Signer + Sync,
+    {
+        let hash = self.message_hash(chain_id, signer_address);
+        let signature = signer.sign_hash(&hash).await?;
+
+        Ok(SignedOutsideExecution {
+            signer_address,
+            outside_execution: self,
+            signature: vec![signature.r, signature.s],
+        })
+    }
+
+    /// Serializes the struct as expected by the Cairo `Serde` implementation.
+    fn serialize(&self) -> Vec<Felt> {
+        let mut felts = vec![
+            self.caller,
+            self.nonce,
+            self.execute_after.into(),
+            self.execute_before.into(),
+            self.calls.len().into(),
+        ];
+
+        for call in &self.calls {
+            felts.push(call.to);
+            felts.push(call.selector);
+            felts.push(call.calldata.len().into());
+            felts.extend(call.calldata.iter());
+        }
+
+        felts
+    }
+
+    fn struct_hash(&self) -> Felt {
+        let calls_hash = poseidon_hash_many(
+            &self
+                .calls
+                .iter()
+                .map(|c| {
+                    poseidon_hash_many(&[
+                        CALL_TYPE_HASH,
+                        c.to,
+                        c.selector,
+                        poseidon_hash_many(&c.calldata),
+                    ])
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        poseidon_hash_many(&[
+            OUTSIDE_EXECUTION_TYPE_HASH,
+            self.caller,
+            self.nonce,
+            self.execute_after.into(),
+            self.execute_before.into(),
+            calls_hash,
+        ])
+    }
+}
+
+/// An outside execution signed by the account on which behalf the calls are executed.
+///
+/// This is the payload that is handed over to the relayer, and can be stored as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedOutsideExecution {
+    /// The account that signed the outside execution, and executes the calls.
+    pub signer_address: Felt,
+    pub outside_execution: OutsideExecution,
+    pub signature: Vec<Felt>,
+}
+
+impl SignedOutsideExecution {
+    /// Returns the call to `execute_from_outside_v2` the relayer has to submit.
+    pub fn to_call(&self) -> Call {
+        let mut calldata = self.outside_execution.serialize();
+        calldata.push(self.signature.len().into());
+        calldata.extend(self.signature.iter());
+
+        Call {
+            to: self.signer_address,
+            selector: get_selector_from_name(EXECUTE_FROM_OUTSIDE_V2).unwrap(),
+            calldata,
+        }
+    }
+}
+
+/// Computes the SNIP-12 (revision 1) domain hash of SNIP-9 V2.
+fn domain_hash(chain_id: Felt) -> Felt {
+    poseidon_hash_many(&[
+        STARKNET_DOMAIN_TYPE_HASH,
+        short_string!("Account.execute_from_outside"),
+        Felt::TWO,
+        chain_id,
+        Felt::ONE,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::signers::{LocalWallet, SigningKey};
+
+    use super::*;
+
+    fn outside_execution() -> OutsideExecution {
+        OutsideExecution {
+            caller: ANY_CALLER,
+            nonce: Felt::from(1),
+            execute_after: 0,
+            execute_before: 1_000,
+            calls: vec![OutsideCall {
+                to: Felt::from(0x123),
+                selector: selector!("spawn"),
+                calldata: vec![Felt::ONE, Felt::TWO],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_outside_execution_call() {
+        let signed = SignedOutsideExecution {
+            signer_address: Felt::from(0xabc),
+            outside_execution: outside_execution(),
+            signature: vec![Felt::from(7), Felt::from(8)],
+        };
+
+        let call = signed.to_call();
+
+        assert_eq!(call.to, Felt::from(0xabc));
+        assert_eq!(call.selector, selector!("execute_from_outside_v2"));
+        assert_eq!(
+            call.calldata,
+            vec![
+                ANY_CALLER,
+                Felt::ONE,
+                Felt::ZERO,
+                Felt::from(1_000),
+                Felt::ONE,
+                Felt::from(0x123),
+                selector!("spawn"),
+                Felt::TWO,
+                Felt::ONE,
+                Felt::TWO,
+                Felt::TWO,
+                Felt::from(7),
+                Felt::from(8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_message_hash_depends_on_signer_and_chain() {
+        let oe = outside_execution();
+        let hash = oe.message_hash(short_string!("SN_SEPOLIA"), Felt::from(0xabc));
+
+        assert_ne!(hash, oe.message_hash(short_string!("SN_MAIN"), Felt::from(0xabc)));
+        assert_ne!(hash, oe.message_hash(short_string!("SN_SEPOLIA"), Felt::from(0xdef)));
+    }
+
+    #[tokio::test]
+    async fn test_sign_outside_execution() {
+        let signing_key = SigningKey::from_secret_scalar(Felt::from(0x1337));
+        let signer = LocalWallet::from_signing_key(signing_key.clone());
+        let chain_id = short_string!("KATANA");
+
+        let signed =
+            outside_execution().sign(&signer, Felt::from(0xabc), chain_id).await.unwrap();
+
+        let hash = signed.outside_execution.message_hash(chain_id, Felt::from(0xabc));
+        let public_key = signing_key.verifying_key();
+
+        assert!(public_key
+            .verify(
+                &hash,
+                &starknet::core::crypto::Signature {
+                    r: signed.signature[0],
+                    s: signed.signature[1]
+                }
+            )
+            .unwrap());
+
+        let json = serde_json::to_string(&signed).unwrap();
+        assert_eq!(serde_json::from_str::<SignedOutsideExecution>(&json).unwrap(), signed);
+    }
+}
@@ -5,6 +5,7 @@ use super::IpfsConfig;
 #[derive(Default, Deserialize, Clone, Debug)]
 pub struct Environment {
     pub rpc_url: Option<String>,
+    pub rpc_fallback_urls: Option<Vec<String>>,
     pub account_address: Option<String>,
     pub private_key: Option<String>,
     pub keystore_path: Option<String>,
@@ -6,6 +6,7 @@ pub mod migration_config;
 pub mod namespace_config;
 pub mod profile_config;
 pub mod resource_config;
+pub mod seed_config;
 pub mod world_config;
 
 pub use environment::Environment;
@@ -14,4 +15,5 @@ pub use metadata_config::WorldMetadata;
 pub use namespace_config::NamespaceConfig;
 pub use profile_config::ProfileConfig;
 pub use resource_config::ResourceConfig;
+pub use seed_config::{SeedAction, SeedConfig};
 pub use world_config::WorldConfig;
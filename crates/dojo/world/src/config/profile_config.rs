@@ -11,6 +11,7 @@ use super::environment::Environment;
 use super::migration_config::MigrationConfig;
 use super::namespace_config::NamespaceConfig;
 use super::resource_config::ResourceConfig;
+use super::seed_config::SeedConfig;
 use super::world_config::WorldConfig;
 
 /// External contract configuration for the Profile config.
@@ -44,6 +45,8 @@ pub struct ProfileConfig {
     pub init_call_args: Option<HashMap<String, Vec<String>>>,
     /// A mapping `<tag, version>` of libraries
     pub lib_versions: Option<HashMap<String, String>>,
+    /// The seeds applied after the migration, with `sozo migrate --seed`.
+    pub seed: Option<Vec<SeedConfig>>,
 }
 
 impl ProfileConfig {
@@ -103,7 +106,14 @@ impl ProfileConfig {
     /// - for a same external contract name we should have:
     ///   + only one external_contracts block if instance name is not set OR,
     ///   + one or several external_contracts blocks with different instance names.
+    /// - a seed must either call a system or write a model record.
     pub fn validate(&self) -> Result<()> {
+        for (index, seed) in self.seed.iter().flatten().enumerate() {
+            if let Err(e) = seed.action() {
+                bail!("Invalid [[seed]] block #{}: {}", index + 1, e);
+            }
+        }
+
         if let Some(contracts) = &self.external_contracts {
             let mut map = HashMap::<String, Vec<Option<String>>>::new();
 
@@ -245,6 +255,12 @@ mod tests {
 
         [lib_versions]
         "ns1-lib" = "0.0.0"
+
+        [[seed]]
+        contract = "ns1-actions"
+        entrypoint = "spawn"
+        model = "ns1-m1"
+        keys = [ "0x1" ]
         "#;
 
         let config = toml::from_str::<ProfileConfig>(content).unwrap();
@@ -328,7 +344,12 @@ mod tests {
         assert_eq!(
             config.lib_versions,
             Some(HashMap::from([("ns1-lib".to_string(), "0.0.0".to_string())]))
-        )
+        );
+
+        let seeds = config.seed.unwrap();
+        assert_eq!(seeds.len(), 1);
+        assert_eq!(seeds[0].contract, Some("ns1-actions".to_string()));
+        assert_eq!(seeds[0].guard(), Some(("ns1-m1", &vec!["0x1".to_string()])));
     }
 
     #[test]
@@ -0,0 +1,125 @@
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+/// A seed applied after the migration, which is either a system call or the write of a model
+/// record. The values are typed with the calldata decoder prefixes.
+///
+/// A seed is skipped if the record of `model` with `keys` already exists, a record being
+/// considered as existing when one of its values is not zero, or if a previous seed with the
+/// same record is applied in the same run.
+///
+/// ```toml
+/// [[seed]]
+/// contract = "ns-actions"
+/// entrypoint = "spawn"
+/// calldata = ["0x1"]
+/// model = "ns-Position"
+/// keys = ["0x1"]
+///
+/// [[seed]]
+/// model = "ns-Config"
+/// keys = ["0"]
+/// values = ["100", "sstr:'default'"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SeedConfig {
+    /// The tag of the contract to call.
+    pub contract: Option<String>,
+    /// The system of the contract to call.
+    pub entrypoint: Option<String>,
+    /// The calldata of the system call.
+    #[serde(default)]
+    pub calldata: Vec<String>,
+    /// The tag of the model whose record is checked, and written if `values` is set.
+    pub model: Option<String>,
+    /// The keys of the model record.
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// The values of the model record to write. The records of models with `#[index]` members
+    /// can't be written this way, since their indexes are only maintained by the systems.
+    pub values: Option<Vec<String>>,
+}
+
+/// The action performed by a seed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeedAction<'a> {
+    /// A call to a system of a Dojo contract.
+    Call { contract: &'a str, entrypoint: &'a str, calldata: &'a Vec<String> },
+    /// A write of a model record.
+    SetRecord { model: &'a str, keys: &'a Vec<String>, values: &'a Vec<String> },
+}
+
+impl SeedConfig {
+    /// Returns the action of the seed, validating the combination of its fields.
+    pub fn action(&self) -> Result<SeedAction<'_>> {
+        match (&self.contract, &self.entrypoint, &self.model, &self.values) {
+            (Some(contract), Some(entrypoint), _, None) => {
+                Ok(SeedAction::Call { contract, entrypoint, calldata: &self.calldata })
+            }
+            (None, None, Some(model), Some(values)) => {
+                if self.keys.is_empty() {
+                    bail!("The seed writing a record of `{model}` must have `keys`.");
+                }
+
+                Ok(SeedAction::SetRecord { model, keys: &self.keys, values })
+            }
+            _ => bail!(
+                "A seed must either call a system with `contract` and `entrypoint`, or write a \
+                 model record with `model`, `keys` and `values`."
+            ),
+        }
+    }
+
+    /// Returns the model and the keys of the record whose existence means the seed has already
+    /// been applied, if any.
+    pub fn guard(&self) -> Option<(&str, &Vec<String>)> {
+        match &self.model {
+            Some(model) if !self.keys.is_empty() => Some((model, &self.keys)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_action() {
+        let call = SeedConfig {
+            contract: Some("ns-actions".to_string()),
+            entrypoint: Some("spawn".to_string()),
+            calldata: vec!["0x1".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            call.action().unwrap(),
+            SeedAction::Call {
+                contract: "ns-actions",
+                entrypoint: "spawn",
+                calldata: &vec!["0x1".to_string()]
+            }
+        );
+        assert_eq!(call.guard(), None);
+
+        let set_record = SeedConfig {
+            model: Some("ns-Config".to_string()),
+            keys: vec!["0".to_string()],
+            values: Some(vec!["100".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(matches!(set_record.action().unwrap(), SeedAction::SetRecord { .. }));
+        assert_eq!(set_record.guard(), Some(("ns-Config", &vec!["0".to_string()])));
+
+        let no_keys = SeedConfig { keys: vec![], ..set_record.clone() };
+        assert!(no_keys.action().is_err());
+
+        let both = SeedConfig { contract: Some("ns-actions".to_string()), ..set_record };
+        assert!(both.action().is_err());
+
+        assert!(SeedConfig::default().action().is_err());
+    }
+}
@@ -472,6 +472,7 @@ pub enum Ty {
     Tuple(Vec<Ty>),
     Array(Vec<Ty>),
     ByteArray,
+    FixedArray(Vec<(Ty, u32)>),
 }
 impl cainome::cairo_serde::CairoSerde for Ty {
     type RustType = Self;
@@ -485,6 +486,7 @@ impl cainome::cairo_serde::CairoSerde for Ty {
             Ty::Tuple(val) => Vec::<Ty>::cairo_serialized_size(val) + 1,
             Ty::Array(val) => Vec::<Ty>::cairo_serialized_size(val) + 1,
             Ty::ByteArray => 1,
+            Ty::FixedArray(val) => Vec::<(Ty, u32)>::cairo_serialized_size(val) + 1,
             _ => 0,
         }
     }
@@ -521,6 +523,12 @@ impl cainome::cairo_serde::CairoSerde for Ty {
                 temp
             }
             Ty::ByteArray => usize::cairo_serialize(&5usize),
+            Ty::FixedArray(val) => {
+                let mut temp = vec![];
+                temp.extend(usize::cairo_serialize(&6usize));
+                temp.extend(Vec::<(Ty, u32)>::cairo_serialize(val));
+                temp
+            }
             _ => vec![],
         }
     }
@@ -540,6 +548,9 @@ impl cainome::cairo_serde::CairoSerde for Ty {
             3usize => Ok(Ty::Tuple(Vec::<Ty>::cairo_deserialize(__felts, __offset + 1)?)),
             4usize => Ok(Ty::Array(Vec::<Ty>::cairo_deserialize(__felts, __offset + 1)?)),
             5usize => Ok(Ty::ByteArray),
+            6usize => {
+                Ok(Ty::FixedArray(Vec::<(Ty, u32)>::cairo_deserialize(__felts, __offset + 1)?))
+            }
             _ => {
                 return Err(cainome::cairo_serde::Error::Deserialize(format!(
                     "Index not handle for enum {}",
@@ -174,6 +174,7 @@ mod tests {
                 tag: "ns-test_contract".to_string(),
                 systems: vec!["system_1".to_string()],
                 selector: felt!("0x3333"),
+                dependencies: vec![],
             }],
             libraries: vec![DojoLibrary {
                 class_hash: felt!("0x9999"),
@@ -9,6 +9,7 @@ pub mod abigen {
 pub mod contract_info;
 pub mod model;
 pub mod naming;
+pub mod storage;
 pub mod world;
 
 pub use abigen::world::{WorldContract, WorldContractReader};
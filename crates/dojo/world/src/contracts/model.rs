@@ -1,19 +1,23 @@
 use std::str::FromStr as _;
 
 use async_trait::async_trait;
-use cainome::cairo_serde::{CairoSerde as _, ContractAddress, Error as CainomeError};
+use cainome::cairo_serde::{ByteArray, CairoSerde as _, ContractAddress, Error as CainomeError};
 use dojo_types::packing::{PackingError, ParseError};
 use dojo_types::primitive::{Primitive, PrimitiveError};
 use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
-use starknet::core::types::{BlockId, Felt};
+use starknet::core::types::{
+    BlockId, ContractExecutionError, Felt, FunctionCall, StarknetError,
+};
 use starknet::core::utils::{
     cairo_short_string_to_felt, parse_cairo_short_string, CairoShortStringToFeltError,
     NonAsciiNameError, ParseCairoShortStringError,
 };
+use starknet::macros::selector;
 use starknet::providers::{Provider, ProviderError};
 
 pub use super::abigen::model::ModelContractReader;
 use super::abigen::world::{Layout, ModelIndex};
+use super::storage::{StorageError, StorageReader};
 use super::{abigen, naming};
 use crate::contracts::WorldContractReader;
 
@@ -41,6 +45,8 @@ pub enum ModelError {
     Packing(#[from] PackingError),
     #[error(transparent)]
     Cainome(#[from] CainomeError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
     #[error("{0}")]
     TagError(String),
 }
@@ -129,13 +135,7 @@ where
     }
 
     pub async fn entity_storage(&self, keys: &[Felt]) -> Result<Vec<Felt>, ModelError> {
-        // As the dojo::model::Layout type has been pasted
-        // in both `model` and `world` ABI by abigen, the compiler sees both types
-        // as different even if they are strictly identical.
-        // Here is a trick reading the model layout as raw FieldElement
-        // and deserialize it to a world::Layout.
-        let raw_layout = self.model_reader.layout().raw_call().await?;
-        let layout = Layout::cairo_deserialize(raw_layout.as_slice(), 0)?;
+        let layout = self.world_layout().await?;
 
         Ok(self
             .world_reader
@@ -144,6 +144,28 @@ where
             .await?)
     }
 
+    /// Reads the values of an entity directly from the storage slots of the world, instead of
+    /// calling the world.
+    pub async fn entity_storage_from_slots(&self, keys: &[Felt]) -> Result<Vec<Felt>, ModelError> {
+        let layout = self.world_layout().await?;
+
+        let reader = StorageReader::new(self.world_reader.address, self.world_reader.provider())
+            .with_block(self.world_reader.block_id);
+
+        Ok(reader.entity(self.selector, keys, &layout).await?)
+    }
+
+    /// Returns the layout of the model, as the layout type of the world ABI.
+    pub async fn world_layout(&self) -> Result<Layout, ModelError> {
+        // As the dojo::model::Layout type has been pasted
+        // in both `model` and `world` ABI by abigen, the compiler sees both types
+        // as different even if they are strictly identical.
+        // Here is a trick reading the model layout as raw FieldElement
+        // and deserialize it to a world::Layout.
+        let raw_layout = self.model_reader.layout().raw_call().await?;
+        Ok(Layout::cairo_deserialize(raw_layout.as_slice(), 0)?)
+    }
+
     pub async fn entity(&self, keys: &[Felt]) -> Result<Ty, ModelError> {
         let mut schema = self.schema().await?;
         let values = self.entity_storage(keys).await?;
@@ -155,6 +177,52 @@ where
         Ok(schema)
     }
 
+    /// Returns the entity indexed by the given values of the index `index_name`, declared with
+    /// the `#[index]` attribute on the model members, or `None` if no entity is indexed.
+    ///
+    /// The values are the serialized values of the indexed members, in declaration order.
+    pub async fn entity_by_index(
+        &self,
+        index_name: &str,
+        values: &[Felt],
+    ) -> Result<Option<Ty>, ModelError> {
+        let index_model = dojo_types::naming::get_index_model_name(&self.name, index_name);
+        let index =
+            ModelRPCReader::new_from_world(&self.namespace, &index_model, self.world_reader).await?;
+
+        // The index only stores the keys of the entity, as a `Span<felt252>`.
+        let storage = index.entity_storage(values).await?;
+        let keys = storage.get(1..).unwrap_or_default();
+
+        if keys.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.entity(keys).await?))
+    }
+
+    /// Returns the names of the models storing the indexes of the model, declared with the
+    /// `#[index]` attribute on its members. The index models are in the namespace of the model.
+    ///
+    /// The models compiled before the introduction of the indexes don't expose them, and have no
+    /// index.
+    pub async fn index_models(&self) -> Result<Vec<String>, ModelError> {
+        let call = FunctionCall {
+            contract_address: self.contract_address,
+            entry_point_selector: selector!("indexes"),
+            calldata: vec![],
+        };
+
+        match self.world_reader.provider().call(call, self.model_reader.block_id).await {
+            Ok(res) => Ok(Vec::<ByteArray>::cairo_deserialize(&res, 0)?
+                .iter()
+                .map(ByteArray::to_string)
+                .collect::<Result<_, _>>()?),
+            Err(e) if is_entrypoint_not_found(&e) => Ok(vec![]),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     pub async fn set_block(&mut self, block_id: BlockId) {
         self.model_reader.set_block(block_id);
     }
@@ -206,6 +274,25 @@ where
     }
 }
 
+/// Returns true if the error is raised by calling an entrypoint the contract doesn't expose.
+pub fn is_entrypoint_not_found(error: &ProviderError) -> bool {
+    let ProviderError::StarknetError(StarknetError::ContractError(data)) = error else {
+        return false;
+    };
+
+    let mut revert_error = &data.revert_error;
+    while let ContractExecutionError::Nested(inner) = revert_error {
+        revert_error = &inner.error;
+    }
+
+    match revert_error {
+        ContractExecutionError::Message(message) => {
+            message.contains("ENTRYPOINT_NOT_FOUND") || message.contains("not found in contract")
+        }
+        ContractExecutionError::Nested(_) => false,
+    }
+}
+
 fn parse_schema(ty: &abigen::model::Ty) -> Result<Ty, ParseError> {
     match ty {
         abigen::model::Ty::Primitive(primitive) => {
@@ -229,6 +316,9 @@ fn parse_schema(ty: &abigen::model::Ty) -> Result<Ty, ParseError> {
                         name: parse_cairo_short_string(&child.name)?,
                         ty: parse_schema(&child.ty)?,
                         key: child.attrs.contains(&cairo_short_string_to_felt("key").unwrap()),
+                        packed: child
+                            .attrs
+                            .contains(&cairo_short_string_to_felt("packed").unwrap()),
                     })
                 })
                 .collect::<Result<Vec<_>, ParseError>>()?;
@@ -271,5 +361,287 @@ fn parse_schema(ty: &abigen::model::Ty) -> Result<Ty, ParseError> {
             Ok(Ty::Array(values))
         }
         abigen::model::Ty::ByteArray => Ok(Ty::ByteArray("".to_string())),
+        abigen::model::Ty::FixedArray(values) => {
+            let (item, size) = values.first().ok_or_else(ParseError::invalid_schema)?;
+
+            Ok(Ty::FixedArray(vec![parse_schema(item)?], *size))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dojo_types::primitive::Primitive;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use starknet::core::types::{ContractErrorData, InnerContractExecutionError};
+    use starknet::core::utils::get_selector_from_name;
+    use starknet::macros::{felt, short_string};
+    use starknet::providers::jsonrpc::{JsonRpcMethod, JsonRpcResponse, JsonRpcTransport};
+    use starknet::providers::{JsonRpcClient, ProviderRequestData};
+
+    use super::*;
+    use crate::contracts::abigen::world::FieldLayout;
+
+    const WORLD_ADDRESS: Felt = felt!("0x1");
+    const PLAYER_ADDRESS: Felt = felt!("0x10");
+    const INDEX_ADDRESS: Felt = felt!("0x11");
+
+    /// A transport answering the `starknet_call` requests registered with `with_call`.
+    #[derive(Debug, Default)]
+    struct StubTransport {
+        calls: HashMap<(Felt, Felt, Vec<Felt>), Vec<Felt>>,
+    }
+
+    impl StubTransport {
+        fn with_call(
+            mut self,
+            to: Felt,
+            entrypoint: &str,
+            calldata: Vec<Felt>,
+            result: Vec<Felt>,
+        ) -> Self {
+            let selector = get_selector_from_name(entrypoint).unwrap();
+            self.calls.insert((to, selector, calldata), result);
+            self
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcTransport for StubTransport {
+        type Error = serde_json::Error;
+
+        async fn send_request<P, R>(
+            &self,
+            method: JsonRpcMethod,
+            params: P,
+        ) -> Result<JsonRpcResponse<R>, Self::Error>
+        where
+            P: Serialize + Send + Sync,
+            R: DeserializeOwned + Send,
+        {
+            assert!(matches!(method, JsonRpcMethod::Call), "unexpected method {method:?}");
+
+            let params = serde_json::to_value(params)?;
+            let call: FunctionCall = serde_json::from_value(params["request"].clone())?;
+            let result = self
+                .calls
+                .get(&(call.contract_address, call.entry_point_selector, call.calldata.clone()))
+                .unwrap_or_else(|| panic!("unexpected call {call:?}"));
+
+            let response = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": result });
+            serde_json::from_value(response)
+        }
+
+        async fn send_requests<R>(
+            &self,
+            _requests: R,
+        ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+        where
+            R: AsRef<[ProviderRequestData]> + Send + Sync,
+        {
+            unimplemented!("batch requests")
+        }
+    }
+
+    fn member(name: &str, ty: &str, key: bool) -> abigen::model::Member {
+        abigen::model::Member {
+            name: cairo_short_string_to_felt(name).unwrap(),
+            attrs: if key { vec![short_string!("key")] } else { vec![] },
+            ty: abigen::model::Ty::Primitive(cairo_short_string_to_felt(ty).unwrap()),
+        }
+    }
+
+    fn field(name: &str, layout: Layout) -> FieldLayout {
+        FieldLayout { selector: get_selector_from_name(name).unwrap(), layout }
+    }
+
+    /// Registers the calls reading the model `name` and its record of the given keys.
+    fn with_model(
+        transport: StubTransport,
+        name: &str,
+        address: Felt,
+        schema: abigen::model::Struct,
+        layout: Layout,
+        records: Vec<(Vec<Felt>, Vec<Felt>)>,
+    ) -> StubTransport {
+        let selector = naming::compute_selector_from_names("ns", name);
+        let resource = abigen::world::Resource::Model((ContractAddress(address), felt!("0x2")));
+
+        let mut transport = transport
+            .with_call(
+                WORLD_ADDRESS,
+                "resource",
+                vec![selector],
+                abigen::world::Resource::cairo_serialize(&resource),
+            )
+            .with_call(address, "schema", vec![], abigen::model::Struct::cairo_serialize(&schema))
+            .with_call(address, "layout", vec![], Layout::cairo_serialize(&layout));
+
+        for (keys, values) in records {
+            let calldata = [
+                vec![selector],
+                ModelIndex::cairo_serialize(&ModelIndex::Keys(keys)),
+                Layout::cairo_serialize(&layout),
+            ]
+            .concat();
+
+            transport = transport.with_call(
+                WORLD_ADDRESS,
+                "entity",
+                calldata,
+                Vec::<Felt>::cairo_serialize(&values),
+            );
+        }
+
+        transport
+    }
+
+    #[tokio::test]
+    async fn test_entity_by_index() {
+        let player_schema = abigen::model::Struct {
+            name: short_string!("Player"),
+            attrs: vec![],
+            children: vec![
+                member("id", "u32", true),
+                member("name", "felt252", false),
+                member("level", "u8", false),
+            ],
+        };
+        let player_layout = Layout::Struct(vec![
+            field("name", Layout::Fixed(vec![251])),
+            field("level", Layout::Fixed(vec![8])),
+        ]);
+
+        let index_schema = abigen::model::Struct {
+            name: short_string!("PlayerNameIndex"),
+            attrs: vec![],
+            children: vec![member("name", "felt252", true)],
+        };
+        let index_layout = Layout::Struct(vec![field(
+            "entity_keys",
+            Layout::Array(vec![Layout::Fixed(vec![251])]),
+        )]);
+
+        let transport = with_model(
+            StubTransport::default(),
+            "Player",
+            PLAYER_ADDRESS,
+            player_schema,
+            player_layout,
+            vec![(vec![felt!("7")], vec![short_string!("bob"), felt!("3")])],
+        );
+
+        // The index stores the serialized keys of the entity, the length being the first felt. An
+        // unknown value reads the default record, without keys.
+        let transport = with_model(
+            transport,
+            "PlayerNameIndex",
+            INDEX_ADDRESS,
+            index_schema,
+            index_layout,
+            vec![
+                (vec![short_string!("bob")], vec![felt!("1"), felt!("7")]),
+                (vec![short_string!("alice")], vec![felt!("0")]),
+            ],
+        );
+
+        // The index `level` isn't declared by the model.
+        let transport = transport.with_call(
+            WORLD_ADDRESS,
+            "resource",
+            vec![naming::compute_selector_from_names("ns", "PlayerLevelIndex")],
+            abigen::world::Resource::cairo_serialize(&abigen::world::Resource::Unregistered),
+        );
+
+        let world = WorldContractReader::new(WORLD_ADDRESS, JsonRpcClient::new(transport));
+        let player = ModelRPCReader::new_from_world("ns", "Player", &world).await.unwrap();
+
+        let entity = player.entity_by_index("name", &[short_string!("bob")]).await.unwrap();
+        assert_eq!(
+            entity,
+            Some(Ty::Struct(Struct {
+                name: "Player".to_string(),
+                children: vec![
+                    Member {
+                        name: "id".to_string(),
+                        ty: Ty::Primitive(Primitive::U32(Some(7))),
+                        key: true,
+                        packed: false,
+                    },
+                    Member {
+                        name: "name".to_string(),
+                        ty: Ty::Primitive(Primitive::Felt252(Some(short_string!("bob")))),
+                        key: false,
+                        packed: false,
+                    },
+                    Member {
+                        name: "level".to_string(),
+                        ty: Ty::Primitive(Primitive::U8(Some(3))),
+                        key: false,
+                        packed: false,
+                    },
+                ],
+            }))
+        );
+
+        let entity = player.entity_by_index("name", &[short_string!("alice")]).await.unwrap();
+        assert_eq!(entity, None);
+
+        let err = player.entity_by_index("level", &[felt!("3")]).await.unwrap_err();
+        assert!(matches!(err, ModelError::ModelNotFound), "unexpected error {err:?}");
+    }
+
+    #[tokio::test]
+    async fn test_index_models() {
+        let schema = abigen::model::Struct {
+            name: short_string!("Player"),
+            attrs: vec![],
+            children: vec![member("id", "u32", true), member("name", "felt252", false)],
+        };
+        let layout = Layout::Struct(vec![field("name", Layout::Fixed(vec![251]))]);
+
+        let index_models = vec![ByteArray::from_string("PlayerNameIndex").unwrap()];
+        let transport =
+            with_model(StubTransport::default(), "Player", PLAYER_ADDRESS, schema, layout, vec![])
+                .with_call(
+                    PLAYER_ADDRESS,
+                    "indexes",
+                    vec![],
+                    Vec::<ByteArray>::cairo_serialize(&index_models),
+                );
+
+        let world = WorldContractReader::new(WORLD_ADDRESS, JsonRpcClient::new(transport));
+        let player = ModelRPCReader::new_from_world("ns", "Player", &world).await.unwrap();
+
+        assert_eq!(player.index_models().await.unwrap(), vec!["PlayerNameIndex".to_string()]);
+    }
+
+    #[test]
+    fn test_is_entrypoint_not_found() {
+        let contract_error = |revert_error| {
+            ProviderError::StarknetError(StarknetError::ContractError(ContractErrorData {
+                revert_error,
+            }))
+        };
+
+        let not_found = ContractExecutionError::Message(
+            "Entry point EntryPointSelector(0x1) not found in contract.".to_string(),
+        );
+        assert!(is_entrypoint_not_found(&contract_error(not_found.clone())));
+
+        let nested = ContractExecutionError::Nested(InnerContractExecutionError {
+            contract_address: felt!("0x1"),
+            class_hash: felt!("0x2"),
+            selector: felt!("0x3"),
+            error: Box::new(not_found),
+        });
+        assert!(is_entrypoint_not_found(&contract_error(nested)));
+
+        let panicked = ContractExecutionError::Message("Failure: 'out of gas'".to_string());
+        assert!(!is_entrypoint_not_found(&contract_error(panicked)));
+        assert!(!is_entrypoint_not_found(&ProviderError::RateLimited));
     }
 }
@@ -44,7 +44,8 @@ async fn test_model(sequencer: &RunnerCtx) {
                 Member {
                     name: "player".to_string(),
                     ty: Ty::Primitive(Primitive::ContractAddress(None)),
-                    key: true
+                    key: true,
+                    packed: false,
                 },
                 Member {
                     name: "vec".to_string(),
@@ -54,16 +55,19 @@ async fn test_model(sequencer: &RunnerCtx) {
                             Member {
                                 name: "x".to_string(),
                                 ty: Ty::Primitive(Primitive::U32(None)),
-                                key: false
+                                key: false,
+                                packed: false,
                             },
                             Member {
                                 name: "y".to_string(),
                                 ty: Ty::Primitive(Primitive::U32(None)),
-                                key: false
+                                key: false,
+                                packed: false,
                             }
                         ]
                     }),
-                    key: false
+                    key: false,
+                    packed: false,
                 }
             ]
         })
@@ -89,12 +93,14 @@ async fn test_model(sequencer: &RunnerCtx) {
                 Member {
                     name: "player".to_string(),
                     ty: Ty::Primitive(Primitive::ContractAddress(None)),
-                    key: true
+                    key: true,
+                    packed: false,
                 },
                 Member {
                     name: "remaining".to_string(),
                     ty: Ty::Primitive(Primitive::U8(None)),
-                    key: false
+                    key: false,
+                    packed: false,
                 },
                 Member {
                     name: "last_direction".to_string(),
@@ -109,7 +115,8 @@ async fn test_model(sequencer: &RunnerCtx) {
                             EnumOption { name: "Down".to_string(), ty: Ty::Tuple(vec![]) },
                         ]
                     }),
-                    key: false
+                    key: false,
+                    packed: false,
                 }
             ]
         })
@@ -0,0 +1,804 @@
+//! Off-chain implementation of the world storage addressing scheme.
+//!
+//! This mirrors `dojo::storage` from the Cairo core library, to read the model records
+//! directly from the world contract storage with `starknet_getStorageAt`, instead of calling the
+//! `entity` entrypoint of the world.
+//!
+//! A record is stored from the base address `poseidon('dojo_storage', model_selector, key)`,
+//! where `key` is the entity id for the record itself, and is combined with a sub-key for each
+//! nested layout:
+//! - struct fields: `poseidon(key, field_selector)`,
+//! - tuple items and array items: `poseidon(key, index)`,
+//! - enum variant data: `poseidon(key, variant)`.
+//!
+//! Packed values are stored in consecutive slots from the base address, by chunks of 256 slots.
+
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Member, Ty};
+use futures::future::try_join_all;
+use futures::{stream, StreamExt, TryStreamExt};
+use num_bigint::BigUint;
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::core::utils::normalize_address;
+use starknet::macros::short_string;
+use starknet::providers::{Provider, ProviderError};
+use starknet_crypto::poseidon_hash_many;
+
+use super::abigen::world::{FieldLayout, Layout};
+
+/// The domain of the world storage keys.
+pub const DOJO_STORAGE: Felt = short_string!("dojo_storage");
+
+/// The domain used to compute the base address of the chunks of a record.
+pub const DOJO_STORAGE_CHUNK: Felt = short_string!("DojoStorageChunk");
+
+/// The number of bits available in a felt to pack values.
+pub const PACKING_MAX_BITS: u8 = 251;
+
+/// The maximum length of an array in the world storage.
+pub const MAX_ARRAY_LENGTH: u64 = 4_294_967_295;
+
+/// The number of slots of a chunk.
+const CHUNK_SIZE: usize = 256;
+
+/// The minimum internal size of an empty `ByteArray`.
+const MIN_BYTE_ARRAY_SIZE: u64 = 3;
+
+/// The default number of storage reads in flight when reading several entities.
+pub const DEFAULT_CONCURRENCY: usize = 64;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+    #[error("Invalid array length {0:#x}.")]
+    InvalidArrayLength(Felt),
+    #[error("Invalid enum variant {0:#x}.")]
+    InvalidVariant(Felt),
+    #[error("Model layout is expected to be a fixed or a struct layout.")]
+    UnexpectedModelLayout,
+    #[error("Member {0:#x} not found in the model layout.")]
+    MemberNotFound(Felt),
+    #[error("Not enough packed values to unpack the layout.")]
+    Unpacking,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type ReadFuture<'a, T> = futures::future::BoxFuture<'a, Result<T, StorageError>>;
+#[cfg(target_arch = "wasm32")]
+type ReadFuture<'a, T> = futures::future::LocalBoxFuture<'a, Result<T, StorageError>>;
+
+/// A storage slot occupied by a record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageSlot {
+    /// The paths of the values stored in the slot, from the model members.
+    pub path: String,
+    /// The address of the slot in the world contract storage.
+    pub address: Felt,
+}
+
+/// Computes the entity id from the serialized keys of a record.
+pub fn entity_id(keys: &[Felt]) -> Felt {
+    poseidon_hash_many(keys)
+}
+
+/// Combines a key with a sub-key, for the nested layouts.
+pub fn combine_key(parent_key: Felt, child_key: Felt) -> Felt {
+    poseidon_hash_many(&[parent_key, child_key])
+}
+
+/// Computes the base storage address of a value, identified by its model and its key.
+pub fn base_address(model_selector: Felt, key: Felt) -> Felt {
+    normalize_address(poseidon_hash_many(&[DOJO_STORAGE, model_selector, key]))
+}
+
+/// Returns the storage addresses of `count` consecutive slots starting at `base`.
+///
+/// The slots are grouped by chunks of 256, the first chunk starting at the base address, the
+/// following ones at `poseidon(base, chunk, 'DojoStorageChunk')`.
+pub fn slot_addresses(base: Felt, count: usize) -> Vec<Felt> {
+    let mut chunk_base = base;
+
+    (0..count)
+        .map(|i| {
+            let index_in_chunk = i % CHUNK_SIZE;
+
+            if i > 0 && index_in_chunk == 0 {
+                let chunk = Felt::from(i / CHUNK_SIZE);
+                chunk_base =
+                    normalize_address(poseidon_hash_many(&[base, chunk, DOJO_STORAGE_CHUNK]));
+            }
+
+            chunk_base + Felt::from(index_in_chunk)
+        })
+        .collect()
+}
+
+/// Returns, for each value of a fixed layout, the index of the felt it is packed into.
+pub fn packed_indices(layout: &[u8]) -> Vec<usize> {
+    let mut index = 0;
+    let mut partial = 0_usize;
+
+    layout
+        .iter()
+        .map(|item| {
+            let item_size = *item as usize;
+            partial += item_size;
+
+            if partial > PACKING_MAX_BITS as usize {
+                index += 1;
+                partial = item_size;
+            }
+
+            index
+        })
+        .collect()
+}
+
+/// Computes the number of felts used to store the values of a fixed layout once packed.
+pub fn packed_size(layout: &[u8]) -> usize {
+    let mut size = 1;
+    let mut partial = 0_usize;
+
+    for item in layout {
+        let item_size = *item as usize;
+        partial += item_size;
+
+        if partial > PACKING_MAX_BITS as usize {
+            size += 1;
+            partial = item_size;
+        }
+    }
+
+    size
+}
+
+/// Unpacks the values of a fixed layout.
+///
+/// Values using the full [`PACKING_MAX_BITS`] are returned as stored, the others are masked
+/// to their size, as done by `dojo::storage::packing::unpack`.
+pub fn unpack(packed: &[Felt], layout: &[u8]) -> Result<Vec<Felt>, StorageError> {
+    let mut packed = packed.iter();
+    let mut unpacking = BigUint::default();
+    let mut offset = PACKING_MAX_BITS;
+    let mut unpacked = Vec::with_capacity(layout.len());
+
+    for size in layout.iter().copied() {
+        let remaining_bits = PACKING_MAX_BITS - offset;
+
+        if remaining_bits < size {
+            let value = packed.next().ok_or(StorageError::Unpacking)?;
+            offset = size;
+
+            if size == PACKING_MAX_BITS {
+                unpacked.push(*value);
+                continue;
+            }
+
+            unpacking = BigUint::from_bytes_be(&value.to_bytes_be());
+            unpacked.push(to_felt(&unpacking & mask(size)));
+            continue;
+        }
+
+        unpacked.push(to_felt((&unpacking >> offset) & mask(size)));
+        offset += size;
+    }
+
+    Ok(unpacked)
+}
+
+fn mask(size: u8) -> BigUint {
+    (BigUint::from(1_u8) << size) - 1_u8
+}
+
+// Masked values are always lower than 2**251, the conversion can't overflow.
+fn to_felt(value: BigUint) -> Felt {
+    Felt::from_bytes_be_slice(&value.to_bytes_be())
+}
+
+/// Reads model records from the world contract storage.
+#[derive(Debug)]
+pub struct StorageReader<P> {
+    world_address: Felt,
+    provider: P,
+    block_id: BlockId,
+    concurrency: usize,
+}
+
+impl<P> StorageReader<P>
+where
+    P: Provider + Sync + Send,
+{
+    pub fn new(world_address: Felt, provider: P) -> Self {
+        Self {
+            world_address,
+            provider,
+            block_id: BlockId::Tag(BlockTag::Pending),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Sets the block at which the storage is read.
+    pub fn with_block(mut self, block_id: BlockId) -> Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Sets the maximum number of entities read concurrently by [`Self::entities`].
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Reads the values of a record from its serialized keys.
+    ///
+    /// The returned values are the same as the ones returned by the `entity` entrypoint of
+    /// the world.
+    pub async fn entity(
+        &self,
+        model_selector: Felt,
+        keys: &[Felt],
+        layout: &Layout,
+    ) -> Result<Vec<Felt>, StorageError> {
+        self.entity_from_id(model_selector, entity_id(keys), layout).await
+    }
+
+    /// Reads the values of a record from its entity id.
+    pub async fn entity_from_id(
+        &self,
+        model_selector: Felt,
+        entity_id: Felt,
+        layout: &Layout,
+    ) -> Result<Vec<Felt>, StorageError> {
+        match layout {
+            Layout::Fixed(_) | Layout::Struct(_) => {
+                self.read_layout(model_selector, entity_id, layout).await
+            }
+            _ => Err(StorageError::UnexpectedModelLayout),
+        }
+    }
+
+    /// Reads the values of several records from their serialized keys, concurrently.
+    ///
+    /// The values are returned in the same order as the keys.
+    pub async fn entities(
+        &self,
+        model_selector: Felt,
+        keys: &[Vec<Felt>],
+        layout: &Layout,
+    ) -> Result<Vec<Vec<Felt>>, StorageError> {
+        stream::iter(keys)
+            .map(|keys| self.entity(model_selector, keys, layout))
+            .buffered(self.concurrency)
+            .try_collect()
+            .await
+    }
+
+    /// Reads the values of a model member of a record, from its entity id.
+    pub async fn member(
+        &self,
+        model_selector: Felt,
+        entity_id: Felt,
+        member_selector: Felt,
+        layout: &Layout,
+    ) -> Result<Vec<Felt>, StorageError> {
+        let Layout::Struct(fields) = layout else {
+            return Err(StorageError::UnexpectedModelLayout);
+        };
+
+        let field = find_field_layout(member_selector, fields)
+            .ok_or(StorageError::MemberNotFound(member_selector))?;
+
+        self.read_layout(model_selector, combine_key(entity_id, member_selector), &field.layout)
+            .await
+    }
+
+    /// Lists the storage slots occupied by a record, from its serialized keys.
+    ///
+    /// The lengths of the arrays and the variants of the enums are read from the storage, to know
+    /// which slots are actually used by the record. The `schema` of the model is only used to name
+    /// the slots.
+    pub async fn entity_slots(
+        &self,
+        model_selector: Felt,
+        keys: &[Felt],
+        layout: &Layout,
+        schema: &Ty,
+    ) -> Result<Vec<StorageSlot>, StorageError> {
+        let key = entity_id(keys);
+
+        match layout {
+            Layout::Fixed(layout) => {
+                let members = match schema {
+                    Ty::Struct(s) => s.children.iter().filter(|m| !m.key).collect::<Vec<_>>(),
+                    _ => vec![],
+                };
+                let leaves = members.iter().flat_map(|m| fixed_leaves(&m.ty, &m.name)).collect();
+                Ok(fixed_slots(model_selector, key, layout, "", leaves))
+            }
+            Layout::Struct(fields) => {
+                let members = struct_field_members(Some(schema));
+
+                let reads = fields.iter().enumerate().map(|(i, field)| {
+                    self.field_slots(
+                        model_selector,
+                        key,
+                        field,
+                        members.get(i).cloned().unwrap_or_default(),
+                        String::new(),
+                    )
+                });
+
+                Ok(try_join_all(reads).await?.concat())
+            }
+            _ => Err(StorageError::UnexpectedModelLayout),
+        }
+    }
+
+    /// Reads the raw values of the given storage slots of the world, concurrently.
+    pub async fn storage_at(&self, addresses: Vec<Felt>) -> Result<Vec<Felt>, StorageError> {
+        self.read_slots(addresses).await
+    }
+
+    /// Lists the storage slots of a struct field, from the members stored in the field.
+    ///
+    /// The `#[packed]` members sharing a field are named after each member of the group.
+    fn field_slots<'a>(
+        &'a self,
+        model: Felt,
+        key: Felt,
+        field: &'a FieldLayout,
+        members: Vec<&'a Member>,
+        path: String,
+    ) -> ReadFuture<'a, Vec<StorageSlot>> {
+        let key = combine_key(key, field.selector);
+
+        match (&field.layout, members.as_slice()) {
+            (Layout::Fixed(layout), [_, _, ..]) => {
+                let leaves = members
+                    .iter()
+                    .flat_map(|m| fixed_leaves(&m.ty, &join_path(&path, &m.name)))
+                    .collect();
+
+                Box::pin(async move { Ok(fixed_slots(model, key, layout, &path, leaves)) })
+            }
+            _ => {
+                let member = members.first().copied();
+                let name = member
+                    .map(|m| m.name.clone())
+                    .unwrap_or_else(|| format!("{:#x}", field.selector));
+
+                self.layout_slots(
+                    model,
+                    key,
+                    &field.layout,
+                    member.map(|m| &m.ty),
+                    join_path(&path, &name),
+                )
+            }
+        }
+    }
+
+    /// Lists the storage slots of a layout stored at `key`, named from `path`.
+    fn layout_slots<'a>(
+        &'a self,
+        model: Felt,
+        key: Felt,
+        layout: &'a Layout,
+        ty: Option<&'a Ty>,
+        path: String,
+    ) -> ReadFuture<'a, Vec<StorageSlot>> {
+        Box::pin(async move {
+            match layout {
+                Layout::Fixed(layout) => {
+                    let leaves = ty.map(|ty| fixed_leaves(ty, &path)).unwrap_or_default();
+                    Ok(fixed_slots(model, key, layout, &path, leaves))
+                }
+                Layout::Struct(fields) => {
+                    let members = struct_field_members(ty);
+
+                    let reads = fields.iter().enumerate().map(|(i, field)| {
+                        self.field_slots(
+                            model,
+                            key,
+                            field,
+                            members.get(i).cloned().unwrap_or_default(),
+                            path.clone(),
+                        )
+                    });
+
+                    Ok(try_join_all(reads).await?.concat())
+                }
+                Layout::Tuple(items) => {
+                    // Fixed-size arrays are stored as tuples.
+                    let reads = items.iter().enumerate().map(|(i, item)| {
+                        let (item_ty, item_path) = match ty {
+                            Some(Ty::Tuple(items)) => {
+                                (items.get(i), join_path(&path, &i.to_string()))
+                            }
+                            Some(Ty::FixedArray(items, _)) => {
+                                (items.get(i).or(items.first()), format!("{path}[{i}]"))
+                            }
+                            _ => (None, join_path(&path, &i.to_string())),
+                        };
+
+                        self.layout_slots(
+                            model,
+                            combine_key(key, Felt::from(i)),
+                            item,
+                            item_ty,
+                            item_path,
+                        )
+                    });
+
+                    Ok(try_join_all(reads).await?.concat())
+                }
+                Layout::Array(item) => {
+                    let length = self.read_length(model, key).await?;
+                    let item = item.first().ok_or(StorageError::UnexpectedModelLayout)?;
+                    let item_ty = match ty {
+                        Some(Ty::Array(items)) => items.first(),
+                        _ => None,
+                    };
+
+                    let reads = (0..length).map(|i| {
+                        self.layout_slots(
+                            model,
+                            combine_key(key, Felt::from(i)),
+                            item,
+                            item_ty,
+                            format!("{path}[{i}]"),
+                        )
+                    });
+
+                    let mut slots = vec![StorageSlot {
+                        path: format!("{path} (length)"),
+                        address: base_address(model, key),
+                    }];
+                    slots.extend(try_join_all(reads).await?.concat());
+                    Ok(slots)
+                }
+                Layout::ByteArray => {
+                    let length = self.read_length(model, key).await?;
+
+                    if length > MAX_ARRAY_LENGTH - MIN_BYTE_ARRAY_SIZE {
+                        return Err(StorageError::InvalidArrayLength(Felt::from(length)));
+                    }
+
+                    let size = (length + MIN_BYTE_ARRAY_SIZE) as usize;
+                    let paths = std::iter::once(format!("{path}.data_len"))
+                        .chain((0..length).map(|i| format!("{path}.data[{i}]")))
+                        .chain([
+                            format!("{path}.pending_word"),
+                            format!("{path}.pending_word_len"),
+                        ]);
+
+                    Ok(slot_addresses(base_address(model, key), size)
+                        .into_iter()
+                        .zip(paths)
+                        .map(|(address, path)| StorageSlot { path, address })
+                        .collect())
+                }
+                Layout::Enum(variants) => {
+                    let variant = self.read_fixed(model, key, &[8]).await?[0];
+
+                    let layout = find_field_layout(variant, variants)
+                        .ok_or(StorageError::InvalidVariant(variant))?;
+
+                    let option = match ty {
+                        Some(Ty::Enum(e)) => {
+                            usize::try_from(variant).ok().and_then(|i| e.options.get(i))
+                        }
+                        _ => None,
+                    };
+                    let name = option
+                        .map(|o| o.name.clone())
+                        .unwrap_or_else(|| format!("{variant:#x}"));
+
+                    let mut slots = vec![StorageSlot {
+                        path: format!("{path} (variant)"),
+                        address: base_address(model, key),
+                    }];
+                    slots.extend(
+                        self.layout_slots(
+                            model,
+                            combine_key(key, variant),
+                            &layout.layout,
+                            option.map(|o| &o.ty),
+                            join_path(&path, &name),
+                        )
+                        .await?,
+                    );
+                    Ok(slots)
+                }
+            }
+        })
+    }
+
+    /// Reads the values of a layout stored at `key`, as `dojo::storage::layout::read_layout`.
+    fn read_layout<'a>(
+        &'a self,
+        model: Felt,
+        key: Felt,
+        layout: &'a Layout,
+    ) -> ReadFuture<'a, Vec<Felt>> {
+        Box::pin(async move {
+            match layout {
+                Layout::Fixed(layout) => self.read_fixed(model, key, layout).await,
+                Layout::Struct(fields) => {
+                    let reads = fields.iter().map(|field| {
+                        self.read_layout(model, combine_key(key, field.selector), &field.layout)
+                    });
+
+                    Ok(try_join_all(reads).await?.concat())
+                }
+                Layout::Tuple(items) => {
+                    let reads = items.iter().enumerate().map(|(i, item)| {
+                        self.read_layout(model, combine_key(key, Felt::from(i)), item)
+                    });
+
+                    Ok(try_join_all(reads).await?.concat())
+                }
+                Layout::Array(item) => {
+                    let length = self.read_length(model, key).await?;
+                    let item = item.first().ok_or(StorageError::UnexpectedModelLayout)?;
+
+                    let reads = (0..length).map(|i| {
+                        self.read_layout(model, combine_key(key, Felt::from(i)), item)
+                    });
+
+                    let mut values = vec![Felt::from(length)];
+                    values.extend(try_join_all(reads).await?.concat());
+                    Ok(values)
+                }
+                Layout::ByteArray => {
+                    let length = self.read_length(model, key).await?;
+
+                    if length > MAX_ARRAY_LENGTH - MIN_BYTE_ARRAY_SIZE {
+                        return Err(StorageError::InvalidArrayLength(Felt::from(length)));
+                    }
+
+                    let size = (length + MIN_BYTE_ARRAY_SIZE) as usize;
+                    self.read_slots(slot_addresses(base_address(model, key), size)).await
+                }
+                Layout::Enum(variants) => {
+                    let variant = self.read_fixed(model, key, &[8]).await?[0];
+
+                    let layout = find_field_layout(variant, variants)
+                        .ok_or(StorageError::InvalidVariant(variant))?;
+
+                    let mut values = vec![variant];
+                    values.extend(
+                        self.read_layout(model, combine_key(key, variant), &layout.layout).await?,
+                    );
+                    Ok(values)
+                }
+            }
+        })
+    }
+
+    /// Reads the length of an array stored at `key`.
+    async fn read_length(&self, model: Felt, key: Felt) -> Result<u64, StorageError> {
+        let length = self.read_fixed(model, key, &[PACKING_MAX_BITS]).await?[0];
+
+        match u64::try_from(length) {
+            Ok(length) if length <= MAX_ARRAY_LENGTH => Ok(length),
+            _ => Err(StorageError::InvalidArrayLength(length)),
+        }
+    }
+
+    /// Reads the packed values of a fixed layout stored at `key`, and unpacks them.
+    async fn read_fixed(
+        &self,
+        model: Felt,
+        key: Felt,
+        layout: &[u8],
+    ) -> Result<Vec<Felt>, StorageError> {
+        // Empty layouts (like enum variants without data) have nothing to unpack.
+        if layout.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let addresses = slot_addresses(base_address(model, key), packed_size(layout));
+        let packed = self.read_slots(addresses).await?;
+
+        unpack(&packed, layout)
+    }
+
+    /// Reads the raw values of the given storage slots of the world, concurrently.
+    async fn read_slots(&self, addresses: Vec<Felt>) -> Result<Vec<Felt>, StorageError> {
+        let reads = addresses.into_iter().map(|address| {
+            self.provider.get_storage_at(self.world_address, address, self.block_id)
+        });
+
+        Ok(try_join_all(reads).await?)
+    }
+}
+
+fn find_field_layout(selector: Felt, fields: &[FieldLayout]) -> Option<&FieldLayout> {
+    fields.iter().find(|field| field.selector == selector)
+}
+
+/// Lists the slots of a fixed layout stored at `key`, named from the paths of its values.
+///
+/// If the paths don't match the layout, the values are named by their index in `path`.
+fn fixed_slots(
+    model: Felt,
+    key: Felt,
+    layout: &[u8],
+    path: &str,
+    leaves: Vec<String>,
+) -> Vec<StorageSlot> {
+    if layout.is_empty() {
+        return vec![];
+    }
+
+    let leaves = if leaves.len() == layout.len() {
+        leaves
+    } else {
+        (0..layout.len()).map(|i| format!("{path}[{i}]")).collect()
+    };
+
+    let mut paths = vec![vec![]; packed_size(layout)];
+    for (leaf, index) in leaves.into_iter().zip(packed_indices(layout)) {
+        paths[index].push(leaf);
+    }
+
+    slot_addresses(base_address(model, key), paths.len())
+        .into_iter()
+        .zip(paths)
+        .map(|(address, paths)| StorageSlot { path: paths.join(", "), address })
+        .collect()
+}
+
+/// Returns the members of a struct type stored in each field of its layout.
+fn struct_field_members(ty: Option<&Ty>) -> Vec<Vec<&Member>> {
+    match ty {
+        Some(Ty::Struct(s)) => s.field_members(),
+        _ => vec![],
+    }
+}
+
+/// Returns the paths of the values of a type stored in a fixed layout.
+fn fixed_leaves(ty: &Ty, path: &str) -> Vec<String> {
+    match ty {
+        Ty::Primitive(Primitive::U256(_)) => {
+            vec![join_path(path, "low"), join_path(path, "high")]
+        }
+        Ty::Struct(s) => s
+            .children
+            .iter()
+            .flat_map(|m| fixed_leaves(&m.ty, &join_path(path, &m.name)))
+            .collect(),
+        Ty::Tuple(items) => items
+            .iter()
+            .enumerate()
+            .flat_map(|(i, item)| fixed_leaves(item, &join_path(path, &i.to_string())))
+            .collect(),
+        Ty::FixedArray(items, size) => (0..*size as usize)
+            .flat_map(|i| match items.get(i).or(items.first()) {
+                Some(item) => fixed_leaves(item, &format!("{path}[{i}]")),
+                None => vec![],
+            })
+            .collect(),
+        Ty::Enum(e) => {
+            // All the variants of a packed enum have the same layout.
+            let data = e
+                .options
+                .iter()
+                .map(|o| fixed_leaves(&o.ty, &format!("{path} (variant data)")))
+                .find(|leaves| !leaves.is_empty())
+                .unwrap_or_default();
+
+            std::iter::once(format!("{path} (variant)")).chain(data).collect()
+        }
+        _ => vec![path.to_string()],
+    }
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() { name.to_string() } else { format!("{path}.{name}") }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::{Member, Struct};
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn test_packed_size() {
+        assert_eq!(packed_size(&[]), 1);
+        assert_eq!(packed_size(&[8, 32, 128]), 1);
+        assert_eq!(packed_size(&[251]), 1);
+        assert_eq!(packed_size(&[251, 251, 8]), 3);
+        assert_eq!(packed_size(&[128, 128]), 2);
+    }
+
+    #[test]
+    fn test_unpack() {
+        // 0x2a (8 bits) | 0x1234 << 8 (32 bits) | 0x1 << 40 (1 bit).
+        let packed = [felt!("0x1000012342a")];
+        assert_eq!(
+            unpack(&packed, &[8, 32, 1]).unwrap(),
+            vec![felt!("0x2a"), felt!("0x1234"), felt!("0x1")]
+        );
+
+        // Values which don't fit in the remaining bits start a new felt.
+        let packed = [felt!("0x7"), felt!("0x3")];
+        assert_eq!(unpack(&packed, &[128, 128]).unwrap(), vec![felt!("0x7"), felt!("0x3")]);
+
+        // Full felts are kept as is, even above 2**251.
+        let felt = Felt::MAX;
+        assert_eq!(unpack(&[felt, felt!("0x1")], &[251, 8]).unwrap(), vec![felt, felt!("0x1")]);
+
+        assert!(matches!(unpack(&[felt!("0x1")], &[251, 251]), Err(StorageError::Unpacking)));
+    }
+
+    #[test]
+    fn test_slot_addresses() {
+        let base = base_address(felt!("0x1234"), entity_id(&[felt!("0x1")]));
+
+        assert_eq!(
+            base,
+            normalize_address(poseidon_hash_many(&[
+                DOJO_STORAGE,
+                felt!("0x1234"),
+                poseidon_hash_many(&[felt!("0x1")]),
+            ]))
+        );
+
+        let addresses = slot_addresses(base, 258);
+        assert_eq!(addresses.len(), 258);
+        assert_eq!(addresses[0], base);
+        assert_eq!(addresses[255], base + Felt::from(255));
+
+        let second_chunk =
+            normalize_address(poseidon_hash_many(&[base, Felt::ONE, DOJO_STORAGE_CHUNK]));
+        assert_eq!(addresses[256], second_chunk);
+        assert_eq!(addresses[257], second_chunk + Felt::ONE);
+    }
+
+    #[test]
+    fn test_fixed_slots_paths() {
+        assert_eq!(packed_indices(&[128, 128, 8, 251]), vec![0, 1, 1, 2]);
+
+        let member =
+            |name: &str, ty: Ty| Member { name: name.to_string(), ty, key: false, packed: false };
+        let vec2 = Ty::Struct(Struct {
+            name: "Vec2".to_string(),
+            children: vec![
+                member("x", Ty::Primitive(Primitive::U32(None))),
+                member("y", Ty::Primitive(Primitive::U32(None))),
+            ],
+        });
+
+        let leaves = [
+            fixed_leaves(&vec2, "vec"),
+            fixed_leaves(&Ty::Primitive(Primitive::U256(None)), "amount"),
+        ]
+        .concat();
+
+        let slots = fixed_slots(felt!("0x1"), felt!("0x2"), &[32, 32, 128, 128], "", leaves);
+        let base = base_address(felt!("0x1"), felt!("0x2"));
+
+        assert_eq!(
+            slots,
+            vec![
+                StorageSlot { path: "vec.x, vec.y, amount.low".to_string(), address: base },
+                StorageSlot { path: "amount.high".to_string(), address: base + Felt::ONE },
+            ]
+        );
+
+        // Values are named by their index if the schema doesn't match the layout.
+        let slots = fixed_slots(felt!("0x1"), felt!("0x2"), &[8, 8], "data", vec![]);
+        assert_eq!(slots[0].path, "data[0], data[1]");
+    }
+
+    #[test]
+    fn test_storage_domains() {
+        assert_eq!(DOJO_STORAGE, felt!("0x646f6a6f5f73746f72616765"));
+        assert_eq!(DOJO_STORAGE_CHUNK, felt!("0x446f6a6f53746f726167654368756e6b"));
+    }
+}
@@ -1,5 +1,6 @@
 //! Manifest data to store the diff result in files.
 
+use dojo_types::system::Dependency;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use starknet::core::serde::unsigned_field_element::UfeHex;
@@ -62,6 +63,9 @@ pub struct DojoContract {
     pub selector: Felt,
     /// Systems of the contract.
     pub systems: Vec<String>,
+    /// Models and events read and written by the contract, by tag.
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
 }
 
 #[serde_as]
@@ -212,6 +216,14 @@ impl Manifest {
     pub fn get_contract_address(&self, tag: &str) -> Option<Felt> {
         self.contracts.iter().find_map(|c| if c.tag == tag { Some(c.address) } else { None })
     }
+
+    /// Sets the models and events read and written by a contract, which are not part of the
+    /// artifacts and are provided by the static analysis of the contracts sources.
+    pub fn set_contract_dependencies(&mut self, tag: &str, dependencies: Vec<Dependency>) {
+        if let Some(contract) = self.contracts.iter_mut().find(|c| c.tag == tag) {
+            contract.dependencies = dependencies;
+        }
+    }
 }
 
 fn resource_diff_to_dojo_contract(diff: &WorldDiff, resource: &ResourceDiff) -> DojoContract {
@@ -232,6 +244,7 @@ fn resource_diff_to_dojo_contract(diff: &WorldDiff, resource: &ResourceDiff) ->
             tag,
             systems: l.systems.clone(),
             selector: resource.dojo_selector(),
+            dependencies: vec![],
         },
         ResourceDiff::Updated(ResourceLocal::Contract(l), ResourceRemote::Contract(r))
         | ResourceDiff::Synced(ResourceLocal::Contract(l), ResourceRemote::Contract(r)) => {
@@ -243,6 +256,7 @@ fn resource_diff_to_dojo_contract(diff: &WorldDiff, resource: &ResourceDiff) ->
                 tag,
                 systems: l.systems.clone(),
                 selector: resource.dojo_selector(),
+                dependencies: vec![],
             }
         }
         _ => unreachable!(),
@@ -209,10 +209,35 @@ impl WorldDiff {
     where
         P: Provider,
     {
-        let is_deployed = match provider
-            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), world_address)
-            .await
-        {
+        Self::new_from_chain_at(
+            world_address,
+            world_local,
+            provider,
+            from_block,
+            None,
+            max_block_range,
+            whitelisted_namespaces,
+        )
+        .await
+    }
+
+    /// Same as [`WorldDiff::new_from_chain`], the remote world being the one as of `to_block` if
+    /// provided, or as of the latest block otherwise.
+    pub async fn new_from_chain_at<P>(
+        world_address: Felt,
+        world_local: WorldLocal,
+        provider: P,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        max_block_range: u64,
+        whitelisted_namespaces: &[String],
+    ) -> Result<Self>
+    where
+        P: Provider,
+    {
+        let block_id = to_block.map_or(BlockId::Tag(BlockTag::Pending), BlockId::Number);
+
+        let is_deployed = match provider.get_class_hash_at(block_id, world_address).await {
             Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
                 trace!(
                     contract_address = format!("{:#066x}", world_address),
@@ -237,10 +262,11 @@ impl WorldDiff {
         };
 
         if is_deployed {
-            let mut world_remote = WorldRemote::from_events(
+            let mut world_remote = WorldRemote::from_events_at(
                 world_address,
                 &provider,
                 from_block,
+                to_block,
                 max_block_range,
                 namespaces,
             )
@@ -260,6 +286,7 @@ impl WorldDiff {
             world_remote
                 .load_external_contract_states(
                     &provider,
+                    block_id,
                     external_contract_classes,
                     external_contracts,
                 )
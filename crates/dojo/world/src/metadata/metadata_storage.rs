@@ -59,6 +59,14 @@ pub trait MetadataStorage {
     ///   The uploaded metadata URI or a Anyhow error.
     async fn upload(&self, service: &mut impl UploadService) -> Result<String>;
 
+    /// Computes the hash of the metadata, which is stored onchain along with the metadata URI.
+    fn metadata_hash(&self) -> Felt
+    where
+        Self: std::hash::Hash,
+    {
+        Felt::from_raw([0, 0, 0, compute_metadata_hash(self)])
+    }
+
     /// Upload metadata using the provided service, only if it has changed.
     ///
     /// # Arguments
@@ -75,8 +83,7 @@ pub trait MetadataStorage {
     where
         Self: std::hash::Hash,
     {
-        let new_hash = compute_metadata_hash(self);
-        let new_hash = Felt::from_raw([0, 0, 0, new_hash]);
+        let new_hash = self.metadata_hash();
 
         if new_hash != current_hash {
             let new_uri = self.upload(service).await?;
@@ -0,0 +1,276 @@
+//! Changes of the remote world between two of its snapshots.
+//!
+//! Comparing the world built from the events up to two different blocks gives the resources
+//! registered or upgraded in between, and the permissions and metadata that have been updated.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use starknet::core::types::Felt;
+
+use super::{ResourceRemote, WorldRemote};
+use crate::constants::WORLD;
+use crate::{ContractAddress, DojoSelector, ResourceType};
+
+/// A permission that can be granted on a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Writer,
+    Owner,
+}
+
+/// A change of the world between two of its snapshots.
+#[derive(Debug, PartialEq)]
+pub enum WorldChange {
+    /// The world has been upgraded.
+    WorldUpgraded { from_class_hash: Felt, to_class_hash: Felt },
+    /// A resource has been registered.
+    ResourceRegistered {
+        selector: DojoSelector,
+        tag: String,
+        resource_type: ResourceType,
+        class_hash: Felt,
+    },
+    /// A resource has been upgraded.
+    ResourceUpgraded {
+        selector: DojoSelector,
+        tag: String,
+        from_class_hash: Felt,
+        to_class_hash: Felt,
+    },
+    /// The metadata of a resource, or of the world, has been updated.
+    MetadataUpdated { selector: DojoSelector, tag: String, from_hash: Felt, to_hash: Felt },
+    /// A permission has been granted or revoked on a resource, or on the world.
+    PermissionUpdated {
+        selector: DojoSelector,
+        tag: String,
+        permission: Permission,
+        contract: ContractAddress,
+        granted: bool,
+    },
+}
+
+impl WorldRemote {
+    /// Returns the changes of the world since the `previous` snapshot of the same world.
+    ///
+    /// The world changes come first, then the resources changes and finally the permissions
+    /// changes, each sorted by resource tag.
+    pub fn changes_since(&self, previous: &WorldRemote) -> Vec<WorldChange> {
+        let mut changes = vec![];
+
+        if let (Some(from), Some(to)) = (previous.class_hashes.last(), self.class_hashes.last()) {
+            if from != to {
+                changes.push(WorldChange::WorldUpgraded {
+                    from_class_hash: *from,
+                    to_class_hash: *to,
+                });
+            }
+        }
+
+        if previous.metadata_hash != self.metadata_hash {
+            changes.push(WorldChange::MetadataUpdated {
+                selector: WORLD,
+                tag: self.resource_label(WORLD),
+                from_hash: previous.metadata_hash,
+                to_hash: self.metadata_hash,
+            });
+        }
+
+        let mut resources = self.resources.values().collect::<Vec<_>>();
+        resources.sort_by_key(|r| resource_label(r));
+
+        for resource in resources {
+            let selector = resource.dojo_selector();
+            let tag = resource_label(resource);
+
+            let Some(previous_resource) = previous.resources.get(&selector) else {
+                changes.push(WorldChange::ResourceRegistered {
+                    selector,
+                    tag,
+                    resource_type: resource.resource_type(),
+                    class_hash: resource.current_class_hash(),
+                });
+                continue;
+            };
+
+            if previous_resource.current_class_hash() != resource.current_class_hash() {
+                changes.push(WorldChange::ResourceUpgraded {
+                    selector,
+                    tag: tag.clone(),
+                    from_class_hash: previous_resource.current_class_hash(),
+                    to_class_hash: resource.current_class_hash(),
+                });
+            }
+
+            if previous_resource.metadata_hash() != resource.metadata_hash() {
+                changes.push(WorldChange::MetadataUpdated {
+                    selector,
+                    tag,
+                    from_hash: previous_resource.metadata_hash(),
+                    to_hash: resource.metadata_hash(),
+                });
+            }
+        }
+
+        for permission in [Permission::Owner, Permission::Writer] {
+            let before = previous.permissions(permission);
+            let after = self.permissions(permission);
+
+            let mut selectors = before.keys().chain(after.keys()).copied().collect::<Vec<_>>();
+            selectors.sort_by_key(|s| self.resource_label(*s));
+            selectors.dedup();
+
+            for selector in selectors {
+                let empty = HashSet::new();
+                let before = before.get(&selector).unwrap_or(&empty);
+                let after = after.get(&selector).unwrap_or(&empty);
+
+                let granted = after.difference(before).map(|c| (*c, true));
+                let revoked = before.difference(after).map(|c| (*c, false));
+
+                for (contract, granted) in granted.chain(revoked).collect::<BTreeSet<_>>() {
+                    changes.push(WorldChange::PermissionUpdated {
+                        selector,
+                        tag: self.resource_label(selector),
+                        permission,
+                        contract,
+                        granted,
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Returns the grantees of a permission, by resource selector, including the resources not
+    /// managed by the local project.
+    fn permissions(
+        &self,
+        permission: Permission,
+    ) -> HashMap<DojoSelector, HashSet<ContractAddress>> {
+        let external = match permission {
+            Permission::Writer => &self.external_writers,
+            Permission::Owner => &self.external_owners,
+        };
+
+        let mut permissions = external.clone();
+
+        for resource in self.resources.values() {
+            let (selector, grantees) = match permission {
+                Permission::Writer => resource.get_writers(),
+                Permission::Owner => resource.get_owners(),
+            };
+
+            permissions.entry(selector).or_default().extend(grantees);
+        }
+
+        permissions
+    }
+
+    /// Returns a label identifying a resource of the world, the tag for known resources.
+    fn resource_label(&self, selector: DojoSelector) -> String {
+        if selector == WORLD {
+            return "World".to_string();
+        }
+
+        self.resources
+            .get(&selector)
+            .map(resource_label)
+            .unwrap_or_else(|| format!("{:#066x}", selector))
+    }
+}
+
+/// Returns the label of a resource, the name for namespaces and the tag otherwise.
+fn resource_label(resource: &ResourceRemote) -> String {
+    match resource {
+        ResourceRemote::Namespace(ns) => ns.name.clone(),
+        _ => resource.tag(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::{CommonRemoteInfo, ContractRemote, ModelRemote};
+
+    fn contract(class_hash: Felt) -> ResourceRemote {
+        ResourceRemote::Contract(ContractRemote {
+            common: CommonRemoteInfo::new(class_hash, "ns", "actions", Felt::ONE),
+            is_initialized: true,
+        })
+    }
+
+    #[test]
+    fn test_changes_since() {
+        let mut previous = WorldRemote { class_hashes: vec![Felt::ONE], ..Default::default() };
+        previous.add_resource(contract(Felt::ONE));
+
+        let mut world = previous.clone();
+        world.class_hashes.push(Felt::TWO);
+        world.resources.values_mut().for_each(|r| r.push_class_hash(Felt::THREE));
+        world.add_resource(ResourceRemote::Model(ModelRemote {
+            common: CommonRemoteInfo::new(Felt::TWO, "ns", "Position", Felt::TWO),
+        }));
+        world.external_owners.insert(WORLD, HashSet::from([Felt::from(0xa)]));
+
+        let actions = previous.resources.keys().next().copied().unwrap();
+        world.resources.get_mut(&actions).unwrap().set_metadata_hash(Felt::from(0x42));
+
+        let position = dojo_types::naming::compute_selector_from_names("ns", "Position");
+
+        assert_eq!(
+            world.changes_since(&previous),
+            vec![
+                WorldChange::WorldUpgraded { from_class_hash: Felt::ONE, to_class_hash: Felt::TWO },
+                WorldChange::ResourceRegistered {
+                    selector: position,
+                    tag: "ns-Position".to_string(),
+                    resource_type: ResourceType::Model,
+                    class_hash: Felt::TWO,
+                },
+                WorldChange::ResourceUpgraded {
+                    selector: actions,
+                    tag: "ns-actions".to_string(),
+                    from_class_hash: Felt::ONE,
+                    to_class_hash: Felt::THREE,
+                },
+                WorldChange::MetadataUpdated {
+                    selector: actions,
+                    tag: "ns-actions".to_string(),
+                    from_hash: Felt::ZERO,
+                    to_hash: Felt::from(0x42),
+                },
+                WorldChange::PermissionUpdated {
+                    selector: WORLD,
+                    tag: "World".to_string(),
+                    permission: Permission::Owner,
+                    contract: Felt::from(0xa),
+                    granted: true,
+                },
+            ]
+        );
+
+        assert!(world.changes_since(&world).is_empty());
+    }
+
+    #[test]
+    fn test_permission_revoked() {
+        let mut previous = WorldRemote::default();
+        previous.add_resource(contract(Felt::ONE));
+        previous.external_writers.insert(Felt::from(0x123), HashSet::from([Felt::ONE]));
+
+        let mut world = previous.clone();
+        world.external_writers.get_mut(&Felt::from(0x123)).unwrap().clear();
+
+        assert_eq!(
+            world.changes_since(&previous),
+            vec![WorldChange::PermissionUpdated {
+                selector: Felt::from(0x123),
+                tag: format!("{:#066x}", 0x123),
+                permission: Permission::Writer,
+                contract: Felt::ONE,
+                granted: false,
+            }]
+        );
+    }
+}
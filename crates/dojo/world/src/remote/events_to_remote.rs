@@ -9,7 +9,7 @@
 use std::collections::{HashMap, HashSet};
 
 use anyhow::Result;
-use starknet::core::types::{BlockId, BlockTag, EventFilter, Felt, StarknetError};
+use starknet::core::types::{BlockId, BlockTag, EmittedEvent, EventFilter, Felt, StarknetError};
 use starknet::macros::felt;
 use starknet::providers::{Provider, ProviderError};
 use tracing::{debug, trace};
@@ -28,7 +28,6 @@ impl WorldRemote {
     /// The `max_block_range` is the maximum number of blocks that will separate the `from_block`
     /// and the `to_block` in the event fetching, which if too high will cause the event fetching
     /// to fail in most of the node providers.
-    #[allow(clippy::field_reassign_with_default)]
     pub async fn from_events<P: Provider>(
         world_address: Felt,
         provider: &P,
@@ -36,25 +35,121 @@ impl WorldRemote {
         max_block_range: u64,
         whitelisted_namespaces: Option<Vec<String>>,
     ) -> Result<Self> {
-        let mut world = Self::default();
+        Self::from_events_at(
+            world_address,
+            provider,
+            from_block,
+            None,
+            max_block_range,
+            whitelisted_namespaces,
+        )
+        .await
+    }
 
-        world.address = world_address;
+    /// Same as [`WorldRemote::from_events`], the world being built as of `to_block` if provided,
+    /// or as of the latest block otherwise.
+    pub async fn from_events_at<P: Provider>(
+        world_address: Felt,
+        provider: &P,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        max_block_range: u64,
+        whitelisted_namespaces: Option<Vec<String>>,
+    ) -> Result<Self> {
+        let block_id = to_block.map_or(BlockId::Tag(BlockTag::Pending), BlockId::Number);
 
-        let chain_id = provider.chain_id().await?;
-        // Katana if it's not `SN_SEPOLIA` or `SN_MAIN`.
-        let is_katana =
-            chain_id != felt!("0x534e5f5345504f4c4941") && chain_id != felt!("0x534e5f4d41494e");
+        if !Self::is_deployed(world_address, provider, block_id).await? {
+            return Ok(Self { address: world_address, ..Default::default() });
+        }
 
-        match provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), world_address).await {
-            Ok(_) => {
-                // The world contract exists, we can continue and fetch the events.
-            }
+        let to_block = match to_block {
+            Some(to_block) => to_block,
+            None => provider.block_number().await?,
+        };
+
+        let events = Self::fetch_events(
+            world_address,
+            provider,
+            from_block.unwrap_or(0),
+            to_block,
+            max_block_range,
+        )
+        .await?;
+
+        Self::from_emitted_events(world_address, &events, None, &whitelisted_namespaces)
+    }
+
+    /// Fetch the events from the world once, and convert them to the remote resources as of each
+    /// of the given blocks.
+    ///
+    /// The worlds are returned in the order of the blocks.
+    pub async fn from_events_at_blocks<P: Provider>(
+        world_address: Felt,
+        provider: &P,
+        from_block: Option<u64>,
+        blocks: &[u64],
+        max_block_range: u64,
+        whitelisted_namespaces: Option<Vec<String>>,
+    ) -> Result<Vec<Self>> {
+        let Some(&last_block) = blocks.iter().max() else {
+            return Ok(vec![]);
+        };
+
+        let events =
+            if Self::is_deployed(world_address, provider, BlockId::Number(last_block)).await? {
+                Self::fetch_events(
+                    world_address,
+                    provider,
+                    from_block.unwrap_or(0),
+                    last_block,
+                    max_block_range,
+                )
+                .await?
+            } else {
+                vec![]
+            };
+
+        blocks
+            .iter()
+            .map(|block| {
+                Self::from_emitted_events(
+                    world_address,
+                    &events,
+                    Some(*block),
+                    &whitelisted_namespaces,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns true if the world contract is deployed at the given block.
+    async fn is_deployed<P: Provider>(
+        world_address: Felt,
+        provider: &P,
+        block_id: BlockId,
+    ) -> Result<bool> {
+        match provider.get_class_hash_at(block_id, world_address).await {
+            Ok(_) => Ok(true),
             Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
                 trace!(%world_address, "No remote world contract found.");
-                return Ok(world);
+                Ok(false)
             }
-            Err(e) => return Err(e.into()),
-        };
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Fetches the management events of the world emitted from `from_block` to `to_block`.
+    async fn fetch_events<P: Provider>(
+        world_address: Felt,
+        provider: &P,
+        from_block: u64,
+        to_block: u64,
+        max_block_range: u64,
+    ) -> Result<Vec<EmittedEvent>> {
+        let chain_id = provider.chain_id().await?;
+        // Katana if it's not `SN_SEPOLIA` or `SN_MAIN`.
+        let is_katana =
+            chain_id != felt!("0x534e5f5345504f4c4941") && chain_id != felt!("0x534e5f4d41494e");
 
         // We only care about management events, not resource events (set, delete, emit).
         let keys = vec![vec![
@@ -76,8 +171,6 @@ impl WorldRemote {
 
         let chunk_size = 500;
 
-        let from_block = from_block.unwrap_or(0);
-        let to_block = provider.block_number().await?;
         let mut current_from = from_block;
         let mut events = Vec::new();
 
@@ -126,11 +219,34 @@ impl WorldRemote {
             "Fetched events for world."
         );
 
-        for event in &events {
+        Ok(events)
+    }
+
+    /// Converts the events of the world to remote resources, only replaying the events emitted up
+    /// to `block` if provided.
+    #[allow(clippy::field_reassign_with_default)]
+    fn from_emitted_events(
+        world_address: Felt,
+        events: &[EmittedEvent],
+        block: Option<u64>,
+        whitelisted_namespaces: &Option<Vec<String>>,
+    ) -> Result<Self> {
+        let mut world = Self::default();
+
+        world.address = world_address;
+
+        let events = events.iter().filter(|event| match (block, event.block_number) {
+            (Some(block), Some(number)) => number <= block,
+            // The events of the pending block are only fetched for the latest block.
+            (Some(_), None) => false,
+            (None, _) => true,
+        });
+
+        for event in events {
             match world::Event::try_from(event) {
                 Ok(ev) => {
                     trace!(?ev, "Processing world event.");
-                    world.match_event(ev, &whitelisted_namespaces)?;
+                    world.match_event(ev, whitelisted_namespaces)?;
                 }
                 Err(e) => {
                     tracing::error!(
@@ -144,18 +260,19 @@ impl WorldRemote {
         Ok(world)
     }
 
-    /// Get the current state of external contracts and external contract
-    /// classes from the blockchain.
+    /// Get the state of external contracts and external contract classes from the blockchain, as
+    /// of the given block.
     pub async fn load_external_contract_states<P: Provider>(
         &mut self,
         provider: &P,
+        block_id: BlockId,
         external_contract_classes: Vec<(String, Felt)>,
         external_contracts: HashMap<String, Felt>,
     ) -> Result<()> {
         // dojo.utils is not wasm compatible, and dojo-world needs to be compatible with wasm.
         // Hence, the is_declared and is_deployed functions are implemented here.
         for (name, hash) in external_contract_classes {
-            match provider.get_class(BlockId::Tag(BlockTag::Pending), hash).await {
+            match provider.get_class(block_id, hash).await {
                 Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {}
                 Ok(_) => {
                     trace!(
@@ -170,7 +287,7 @@ impl WorldRemote {
         }
 
         for (name, address) in external_contracts {
-            match provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), address).await {
+            match provider.get_class_hash_at(block_id, address).await {
                 Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {}
                 Ok(_) => {
                     trace!(
@@ -487,6 +604,30 @@ mod tests {
         assert_eq!(world_remote.class_hashes.len(), 1);
     }
 
+    #[test]
+    fn test_from_emitted_events_at_block() {
+        let upgraded = |block_number: Option<u64>, class_hash: u64| EmittedEvent {
+            from_address: Felt::ONE,
+            keys: vec![world::WorldUpgraded::event_selector()],
+            data: vec![Felt::from(class_hash)],
+            block_hash: None,
+            block_number,
+            transaction_hash: Felt::ZERO,
+        };
+
+        let events = vec![upgraded(Some(10), 1), upgraded(Some(20), 2), upgraded(None, 3)];
+        let class_hashes = |block: Option<u64>| {
+            WorldRemote::from_emitted_events(Felt::ONE, &events, block, &NO_WHITELIST)
+                .unwrap()
+                .class_hashes
+        };
+
+        assert_eq!(class_hashes(Some(9)), vec![]);
+        assert_eq!(class_hashes(Some(15)), vec![Felt::ONE]);
+        assert_eq!(class_hashes(Some(20)), vec![Felt::ONE, Felt::TWO]);
+        assert_eq!(class_hashes(None), vec![Felt::ONE, Felt::TWO, Felt::THREE]);
+    }
+
     #[tokio::test]
     async fn test_namespace_registered_event() {
         let mut world_remote = WorldRemote::default();
@@ -10,10 +10,12 @@ use std::collections::{HashMap, HashSet};
 
 use starknet::core::types::Felt;
 
+mod changes;
 mod events_to_remote;
 mod permissions;
 mod resource;
 
+pub use changes::{Permission, WorldChange};
 pub use resource::*;
 
 use crate::{ContractAddress, DojoSelector};
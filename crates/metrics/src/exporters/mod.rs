@@ -1,3 +1,5 @@
+#[cfg(feature = "otlp")]
+pub mod otlp;
 pub mod prometheus;
 
 /// Trait for metrics recorder whose metrics can be exported.
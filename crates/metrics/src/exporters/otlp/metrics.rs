@@ -0,0 +1,345 @@
+//! OTLP metrics recorder.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use metrics_util::layers::{PrefixLayer, Stack};
+use metrics_util::registry::{AtomicStorage, Registry};
+use reqwest::Client;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use super::{drop_oldest, scope, send, string_attribute, unix_nanos, OtlpConfig};
+use crate::exporters::Exporter;
+use crate::Error;
+
+/// The upper bounds of the histograms buckets, the same as the Prometheus defaults.
+const HISTOGRAM_BOUNDS: [f64; 11] =
+    [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// OTLP aggregation temporalities.
+const TEMPORALITY_DELTA: u8 = 1;
+const TEMPORALITY_CUMULATIVE: u8 = 2;
+
+/// The maximum number of values of a histogram kept until the collector accepts them, the oldest
+/// being dropped.
+const MAX_PENDING_HISTOGRAM_VALUES: usize = 10_000;
+
+struct Inner {
+    registry: Registry<Key, AtomicStorage>,
+    descriptions: RwLock<HashMap<String, (Option<Unit>, SharedString)>>,
+    histograms: Mutex<PendingHistograms>,
+    config: OtlpConfig,
+    start_time: SystemTime,
+}
+
+/// The histograms values drained from the registry and not yet pushed to the collector.
+///
+/// Histograms are exported with delta temporality: the values are only cleared once pushed, so
+/// that a failed push doesn't lose them, and the next push covers the window since the last
+/// successful one.
+struct PendingHistograms {
+    values: HashMap<Key, Vec<f64>>,
+    /// The start of the window of the pending values.
+    since: SystemTime,
+}
+
+impl PendingHistograms {
+    /// Adds the values recorded for a histogram, dropping the oldest ones beyond the maximum.
+    fn extend(&mut self, key: &Key, recorded: &[f64]) {
+        let values = self.values.entry(key.clone()).or_default();
+        values.extend_from_slice(recorded);
+
+        let dropped = drop_oldest(values, MAX_PENDING_HISTOGRAM_VALUES);
+        if dropped > 0 {
+            warn!(
+                target: "metrics",
+                histogram = %key.name(),
+                dropped,
+                "Too many histogram values pending, dropping the oldest ones."
+            );
+        }
+    }
+
+    /// Removes the values pushed, keeping the ones drained after the rendering.
+    fn clear_pushed(&mut self, pushed: HashMap<Key, usize>, pushed_at: SystemTime) {
+        for (key, count) in pushed {
+            if let Some(values) = self.values.get_mut(&key) {
+                values.drain(..count.min(values.len()));
+            }
+        }
+
+        self.values.retain(|_, values| !values.is_empty());
+        self.since = pushed_at;
+    }
+}
+
+/// OTLP exporter recorder.
+///
+/// The metrics are kept in memory and pushed to the collector by the [`Otlp`] handle.
+pub struct OtlpRecorder {
+    inner: Arc<Inner>,
+}
+
+impl fmt::Debug for OtlpRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtlpRecorder").field("config", &self.inner.config).finish_non_exhaustive()
+    }
+}
+
+impl OtlpRecorder {
+    /// Creates a new recorder, without installing it.
+    pub fn new(config: OtlpConfig) -> Self {
+        let inner = Inner {
+            registry: Registry::atomic(),
+            descriptions: RwLock::new(HashMap::new()),
+            histograms: Mutex::new(PendingHistograms {
+                values: HashMap::new(),
+                since: SystemTime::now(),
+            }),
+            config,
+            start_time: SystemTime::now(),
+        };
+
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Returns a handle to the recorder.
+    pub fn handle(&self) -> Otlp {
+        Otlp { inner: self.inner.clone(), client: Client::new() }
+    }
+
+    /// Installs OTLP as the metrics recorder.
+    ///
+    /// The metrics are only pushed to the collector once [`Otlp::run`] is spawned.
+    ///
+    /// ## Arguments
+    ///
+    /// * `prefix` - Apply a prefix to all metrics keys.
+    /// * `config` - The collector configuration.
+    pub fn install(prefix: &str, config: OtlpConfig) -> Result<Otlp, Error> {
+        let endpoint = config.endpoint.clone();
+        let recorder = Self::new(config);
+        let handle = recorder.handle();
+
+        Stack::new(recorder)
+            .push(PrefixLayer::new(prefix))
+            .install()
+            .map_err(|_| Error::GlobalRecorderAlreadyInstalled)?;
+
+        info!(target: "metrics", %prefix, %endpoint, "OTLP recorder installed.");
+
+        Ok(handle)
+    }
+
+    fn describe(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        let mut descriptions = self.inner.descriptions.write().expect("poisoned lock");
+        descriptions.insert(key.as_str().to_string(), (unit, description));
+    }
+}
+
+impl Recorder for OtlpRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        self.inner.registry.get_or_create_counter(key, |c| Counter::from_arc(c.clone()))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        self.inner.registry.get_or_create_gauge(key, |g| Gauge::from_arc(g.clone()))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        self.inner.registry.get_or_create_histogram(key, |h| Histogram::from_arc(h.clone()))
+    }
+}
+
+/// Handle to an [`OtlpRecorder`], used to push the recorded metrics to the collector.
+#[derive(Clone)]
+pub struct Otlp {
+    inner: Arc<Inner>,
+    client: Client,
+}
+
+impl fmt::Debug for Otlp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Otlp").field("config", &self.inner.config).finish_non_exhaustive()
+    }
+}
+
+impl Otlp {
+    /// Pushes the metrics recorded thus far to the collector.
+    ///
+    /// The histograms values are cleared only if the collector accepted them.
+    pub async fn push(&self) -> Result<(), Error> {
+        let rendered = self.render();
+        send(&self.client, &self.inner.config, "metrics", rendered.request.to_string()).await?;
+
+        let mut histograms = self.inner.histograms.lock().expect("poisoned lock");
+        histograms.clear_pushed(rendered.histograms, rendered.time);
+
+        Ok(())
+    }
+
+    /// Pushes the metrics to the collector at the configured interval, forever.
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.inner.config.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(error) = self.push().await {
+                warn!(target: "metrics", %error, "Failed to push metrics to the OTLP collector.");
+            }
+        }
+    }
+
+    /// Renders the metrics as an OTLP `ExportMetricsServiceRequest`.
+    fn render(&self) -> Rendered {
+        let inner = &self.inner;
+        let time = SystemTime::now();
+        let start = unix_nanos(inner.start_time);
+        let now = unix_nanos(time);
+
+        // Group the data points of all the metrics sharing the same name.
+        let mut counters: HashMap<String, Vec<Value>> = HashMap::new();
+        let mut gauges: HashMap<String, Vec<Value>> = HashMap::new();
+        let mut histograms: HashMap<String, Vec<Value>> = HashMap::new();
+
+        inner.registry.visit_counters(|key, counter| {
+            counters.entry(key.name().to_string()).or_default().push(json!({
+                "attributes": attributes(key),
+                "startTimeUnixNano": start,
+                "timeUnixNano": now,
+                "asInt": counter.load(Ordering::Acquire).to_string(),
+            }));
+        });
+
+        inner.registry.visit_gauges(|key, gauge| {
+            gauges.entry(key.name().to_string()).or_default().push(json!({
+                "attributes": attributes(key),
+                "timeUnixNano": now,
+                "asDouble": f64::from_bits(gauge.load(Ordering::Acquire)),
+            }));
+        });
+
+        // The recorded values are moved to the pending ones, cleared after a successful push.
+        let mut pending = inner.histograms.lock().expect("poisoned lock");
+        inner.registry.visit_histograms(|key, bucket| {
+            bucket.clear_with(|chunk| pending.extend(key, chunk));
+        });
+
+        let since = unix_nanos(pending.since);
+        let mut rendered_histograms = HashMap::new();
+
+        for (key, values) in &pending.values {
+            if !values.is_empty() {
+                histograms
+                    .entry(key.name().to_string())
+                    .or_default()
+                    .push(histogram_data_point(key, values, &since, &now));
+                rendered_histograms.insert(key.clone(), values.len());
+            }
+        }
+        drop(pending);
+
+        let descriptions = inner.descriptions.read().expect("poisoned lock");
+        let metric = |name: &str, data: (&str, Value)| {
+            let (unit, description) = match descriptions.get(name) {
+                Some((unit, desc)) => (unit.map(|u| u.as_canonical_label()), &**desc),
+                None => (None, ""),
+            };
+            let unit = unit.unwrap_or_default();
+
+            let mut metric = json!({ "name": name, "description": description, "unit": unit });
+            metric[data.0] = data.1;
+            metric
+        };
+
+        let mut metrics = Vec::new();
+
+        for (name, points) in counters {
+            let sum = json!({
+                "dataPoints": points,
+                "aggregationTemporality": TEMPORALITY_CUMULATIVE,
+                "isMonotonic": true,
+            });
+            metrics.push(metric(&name, ("sum", sum)));
+        }
+
+        for (name, points) in gauges {
+            metrics.push(metric(&name, ("gauge", json!({ "dataPoints": points }))));
+        }
+
+        for (name, points) in histograms {
+            let histogram =
+                json!({ "dataPoints": points, "aggregationTemporality": TEMPORALITY_DELTA });
+            metrics.push(metric(&name, ("histogram", histogram)));
+        }
+
+        let request = json!({
+            "resourceMetrics": [{
+                "resource": inner.config.resource(),
+                "scopeMetrics": [{ "scope": scope(), "metrics": metrics }],
+            }]
+        });
+
+        Rendered { request, histograms: rendered_histograms, time }
+    }
+}
+
+impl Exporter for Otlp {
+    fn export(&self) -> String {
+        self.render().request.to_string()
+    }
+}
+
+/// An OTLP request, with the number of values rendered for each histogram.
+struct Rendered {
+    request: Value,
+    histograms: HashMap<Key, usize>,
+    time: SystemTime,
+}
+
+fn attributes(key: &Key) -> Vec<Value> {
+    key.labels().map(|label| string_attribute(label.key(), label.value())).collect()
+}
+
+fn histogram_data_point(key: &Key, values: &[f64], since: &str, now: &str) -> Value {
+    let mut bucket_counts = [0u64; HISTOGRAM_BOUNDS.len() + 1];
+
+    for value in values {
+        let index = HISTOGRAM_BOUNDS.iter().position(|bound| value <= bound);
+        bucket_counts[index.unwrap_or(HISTOGRAM_BOUNDS.len())] += 1;
+    }
+
+    let sum: f64 = values.iter().sum();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    json!({
+        "attributes": attributes(key),
+        "startTimeUnixNano": since,
+        "timeUnixNano": now,
+        "count": values.len().to_string(),
+        "sum": sum,
+        "min": min,
+        "max": max,
+        "bucketCounts": bucket_counts.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        "explicitBounds": HISTOGRAM_BOUNDS,
+    })
+}
@@ -0,0 +1,353 @@
+//! OpenTelemetry (OTLP) exporter
+//!
+//! Unlike Prometheus which is scraped, OTLP is push based: the recorded metrics (and optionally
+//! the tracing spans) are periodically sent to an OTLP collector using the OTLP/HTTP protocol
+//! with JSON encoding.
+
+mod metrics;
+mod traces;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::Client;
+use serde_json::{json, Value};
+use url::Url;
+
+pub use self::metrics::{Otlp, OtlpRecorder};
+pub use self::traces::{OtlpTraceLayer, SpanPusher};
+use crate::Error;
+
+/// The name of the instrumentation scope reported to the collector.
+const SCOPE_NAME: &str = "dojo-metrics";
+
+/// OTLP exporter configuration.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// The base URL of the collector, the signal paths (`/v1/metrics` and `/v1/traces`) are
+    /// appended to it.
+    pub endpoint: Url,
+    /// The interval at which the data is pushed to the collector.
+    pub interval: Duration,
+    /// The `service.name` resource attribute.
+    pub service_name: String,
+    /// Additional headers sent with each request (e.g. authentication).
+    pub headers: Vec<(String, String)>,
+}
+
+impl OtlpConfig {
+    /// The default push interval.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// Creates a new configuration for the given collector endpoint.
+    pub fn new(endpoint: Url, service_name: impl Into<String>) -> Self {
+        Self {
+            endpoint,
+            interval: Self::DEFAULT_INTERVAL,
+            service_name: service_name.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Sets the interval at which the data is pushed to the collector.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Adds a header to send with each request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Returns the URL of the given signal (e.g. `metrics` or `traces`).
+    fn signal_url(&self, signal: &str) -> Url {
+        let mut url = self.endpoint.clone();
+        let path = format!("{}/v1/{}", url.path().trim_end_matches('/'), signal);
+        url.set_path(&path);
+        url
+    }
+
+    /// Returns the OTLP resource describing the service.
+    fn resource(&self) -> Value {
+        json!({ "attributes": [string_attribute("service.name", &self.service_name)] })
+    }
+}
+
+/// Sends a JSON encoded OTLP payload to the collector.
+async fn send(
+    client: &Client,
+    config: &OtlpConfig,
+    signal: &str,
+    body: String,
+) -> Result<(), Error> {
+    let mut request = client
+        .post(config.signal_url(signal))
+        .header("content-type", "application/json")
+        .body(body);
+
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        return Err(Error::CollectorStatus(response.status().as_u16()));
+    }
+
+    Ok(())
+}
+
+/// Drops the oldest values beyond `max`, the values pending while the collector is unreachable
+/// being kept in memory. Returns the number of dropped values.
+fn drop_oldest<T>(values: &mut Vec<T>, max: usize) -> usize {
+    let dropped = values.len().saturating_sub(max);
+    values.drain(..dropped);
+    dropped
+}
+
+/// Returns the OTLP instrumentation scope.
+fn scope() -> Value {
+    json!({ "name": SCOPE_NAME, "version": env!("CARGO_PKG_VERSION") })
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// Returns the given time as nanoseconds since the UNIX epoch, encoded as expected by OTLP/JSON
+/// for 64 bits integers.
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use http_body_util::{BodyExt, Full};
+    use hyper::server::conn::http1;
+    use hyper::service::service_fn;
+    use hyper::{Request, Response, StatusCode};
+    use hyper_util::rt::TokioIo;
+    use serde_json::Value;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::exporters::Exporter;
+
+    /// Starts an in-process OTLP collector stub, forwarding the path and the JSON body of each
+    /// received request.
+    async fn collector_stub() -> (SocketAddr, mpsc::UnboundedReceiver<(String, Value)>) {
+        failing_collector_stub(0).await
+    }
+
+    /// Starts a collector stub rejecting the first `failures` requests with a 503.
+    async fn failing_collector_stub(
+        failures: usize,
+    ) -> (SocketAddr, mpsc::UnboundedReceiver<(String, Value)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let failures = Arc::new(AtomicUsize::new(failures));
+
+        tokio::spawn(async move {
+            loop {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let tx = tx.clone();
+                let failures = failures.clone();
+
+                tokio::spawn(async move {
+                    let service = service_fn(move |req: Request<hyper::body::Incoming>| {
+                        let tx = tx.clone();
+                        let failures = failures.clone();
+                        async move {
+                            let failed = failures
+                                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                                    n.checked_sub(1)
+                                })
+                                .is_ok();
+
+                            let mut response = Response::new(Full::new(Bytes::from("{}")));
+
+                            if failed {
+                                *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+                            } else {
+                                let path = req.uri().path().to_string();
+                                let body = req.into_body().collect().await.unwrap().to_bytes();
+                                tx.send((path, serde_json::from_slice(&body).unwrap())).unwrap();
+                            }
+
+                            Ok::<_, Infallible>(response)
+                        }
+                    });
+
+                    let io = TokioIo::new(tcp);
+                    let _ = http1::Builder::new().serve_connection(io, service).await;
+                });
+            }
+        });
+
+        (addr, rx)
+    }
+
+    fn config(addr: SocketAddr) -> OtlpConfig {
+        OtlpConfig::new(Url::parse(&format!("http://{addr}/otlp")).unwrap(), "test")
+            .with_header("x-api-key", "secret")
+    }
+
+    #[test]
+    fn drop_oldest_values() {
+        let mut values = vec![1, 2, 3, 4];
+        assert_eq!(drop_oldest(&mut values, 5), 0);
+        assert_eq!(drop_oldest(&mut values, 2), 2);
+        assert_eq!(values, [3, 4]);
+    }
+
+    #[test]
+    fn signal_url_appends_path() {
+        let config = OtlpConfig::new(Url::parse("http://localhost:4318").unwrap(), "test");
+        assert_eq!(config.signal_url("metrics").as_str(), "http://localhost:4318/v1/metrics");
+
+        let config = OtlpConfig::new(Url::parse("http://localhost:4318/otlp/").unwrap(), "test");
+        assert_eq!(config.signal_url("traces").as_str(), "http://localhost:4318/otlp/v1/traces");
+    }
+
+    #[tokio::test]
+    async fn push_metrics_to_collector() {
+        let (addr, mut rx) = collector_stub().await;
+
+        let recorder = OtlpRecorder::new(config(addr));
+        let handle = recorder.handle();
+
+        ::metrics::with_local_recorder(&recorder, || {
+            ::metrics::describe_counter!("requests", "Number of requests.");
+            ::metrics::counter!("requests", "method" => "get").increment(3);
+            ::metrics::gauge!("height").set(42.0);
+            ::metrics::histogram!("latency").record(0.5);
+            ::metrics::histogram!("latency").record(1.5);
+        });
+
+        handle.push().await.unwrap();
+
+        let (path, body) = rx.recv().await.unwrap();
+        assert_eq!(path, "/otlp/v1/metrics");
+
+        let resource = &body["resourceMetrics"][0];
+        assert_eq!(resource["resource"]["attributes"][0]["value"]["stringValue"], "test");
+
+        let metrics = resource["scopeMetrics"][0]["metrics"].as_array().unwrap();
+        let metric = |name: &str| metrics.iter().find(|m| m["name"] == name).unwrap().clone();
+
+        let requests = metric("requests");
+        assert_eq!(requests["description"], "Number of requests.");
+        assert_eq!(requests["sum"]["isMonotonic"], true);
+        assert_eq!(requests["sum"]["dataPoints"][0]["asInt"], "3");
+        assert_eq!(requests["sum"]["dataPoints"][0]["attributes"][0]["key"], "method");
+
+        assert_eq!(metric("height")["gauge"]["dataPoints"][0]["asDouble"], 42.0);
+
+        let latency = &metric("latency")["histogram"]["dataPoints"][0];
+        let nanos = |field: &str| latency[field].as_str().unwrap().parse::<u128>().unwrap();
+        assert!(nanos("startTimeUnixNano") <= nanos("timeUnixNano"));
+        assert_eq!(latency["count"], "2");
+        assert_eq!(latency["sum"], 2.0);
+        assert_eq!(latency["min"], 0.5);
+        assert_eq!(latency["max"], 1.5);
+
+        // Histograms are exported with delta temporality, hence drained after each export.
+        let exported: Value = serde_json::from_str(&handle.export()).unwrap();
+        let metrics = exported["resourceMetrics"][0]["scopeMetrics"][0]["metrics"].clone();
+        assert!(metrics.as_array().unwrap().iter().all(|m| m["name"] != "latency"));
+    }
+
+    #[tokio::test]
+    async fn histograms_are_kept_when_the_push_fails() {
+        let (addr, mut rx) = failing_collector_stub(1).await;
+
+        let recorder = OtlpRecorder::new(config(addr));
+        let handle = recorder.handle();
+
+        ::metrics::with_local_recorder(&recorder, || {
+            ::metrics::histogram!("latency").record(0.5);
+        });
+
+        assert!(handle.push().await.is_err());
+
+        ::metrics::with_local_recorder(&recorder, || {
+            ::metrics::histogram!("latency").record(1.5);
+        });
+
+        handle.push().await.unwrap();
+
+        let (_, body) = rx.recv().await.unwrap();
+        let metrics = body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"].clone();
+        let latency = metrics.as_array().unwrap().iter().find(|m| m["name"] == "latency").unwrap();
+
+        let point = &latency["histogram"]["dataPoints"][0];
+        assert_eq!(point["count"], "2");
+        assert_eq!(point["sum"], 2.0);
+    }
+
+    #[tokio::test]
+    async fn push_spans_to_collector() {
+        let (addr, mut rx) = collector_stub().await;
+
+        let (layer, mut pusher) = OtlpTraceLayer::new(config(addr));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let parent = tracing::info_span!("migrate", profile = "dev").entered();
+            tracing::info_span!("declare", classes = 2).in_scope(|| {});
+            drop(parent);
+        });
+
+        pusher.push_pending().await.unwrap();
+
+        let (path, body) = rx.recv().await.unwrap();
+        assert_eq!(path, "/otlp/v1/traces");
+
+        let spans = body["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(spans.len(), 2);
+
+        let declare = spans.iter().find(|s| s["name"] == "declare").unwrap();
+        let migrate = spans.iter().find(|s| s["name"] == "migrate").unwrap();
+
+        assert_eq!(declare["traceId"], migrate["traceId"]);
+        assert_eq!(declare["parentSpanId"], migrate["spanId"]);
+        assert_eq!(migrate["attributes"][0]["value"]["stringValue"], "dev");
+        assert_eq!(declare["attributes"][0]["value"]["intValue"], "2");
+    }
+
+    #[tokio::test]
+    async fn spans_are_kept_when_the_push_fails() {
+        let (addr, mut rx) = failing_collector_stub(1).await;
+
+        let (layer, mut pusher) = OtlpTraceLayer::new(config(addr));
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(layer));
+
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info_span!("declare").in_scope(|| {});
+        });
+
+        assert!(pusher.push_pending().await.is_err());
+
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info_span!("deploy").in_scope(|| {});
+        });
+
+        pusher.push_pending().await.unwrap();
+
+        let (_, body) = rx.recv().await.unwrap();
+        let spans = body["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+        let names = spans.iter().map(|s| s["name"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names, ["declare", "deploy"]);
+    }
+}
@@ -0,0 +1,211 @@
+//! OTLP traces exporter.
+//!
+//! The spans are captured by a [`tracing_subscriber::Layer`] when they are closed, and sent in
+//! batches to the collector by the [`SpanPusher`].
+
+use std::fmt;
+use std::time::SystemTime;
+
+use reqwest::Client;
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{warn, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use super::{drop_oldest, scope, send, unix_nanos, OtlpConfig};
+use crate::Error;
+
+/// OTLP `SPAN_KIND_INTERNAL`.
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// The maximum number of spans kept until the collector accepts them, the oldest being dropped.
+const MAX_PENDING_SPANS: usize = 2048;
+
+/// The OTLP context of a span, stored in the span extensions until it is closed.
+#[derive(Debug)]
+struct SpanContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    start_time: SystemTime,
+    attributes: Vec<Value>,
+}
+
+/// A [`Layer`] exporting the closed spans to an OTLP collector.
+///
+/// The spans are only sent once the [`SpanPusher`] returned by [`OtlpTraceLayer::new`] is run.
+#[derive(Debug)]
+pub struct OtlpTraceLayer {
+    sender: UnboundedSender<Value>,
+}
+
+impl OtlpTraceLayer {
+    /// Creates a new layer, and the pusher sending the spans to the collector.
+    pub fn new(config: OtlpConfig) -> (Self, SpanPusher) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let pusher = SpanPusher { receiver, pending: Vec::new(), config, client: Client::new() };
+        (Self { sender }, pusher)
+    }
+}
+
+impl<S> Layer<S> for OtlpTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+
+        let parent = span.parent().and_then(|parent| {
+            let extensions = parent.extensions();
+            extensions.get::<SpanContext>().map(|context| (context.trace_id, context.span_id))
+        });
+
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, parent_span_id)) => (trace_id, Some(parent_span_id)),
+            None => (rand::random(), None),
+        };
+
+        let mut visitor = AttributesVisitor::default();
+        attrs.record(&mut visitor);
+
+        span.extensions_mut().insert(SpanContext {
+            trace_id,
+            span_id: rand::random(),
+            parent_span_id,
+            start_time: SystemTime::now(),
+            attributes: visitor.0,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+
+        let mut extensions = span.extensions_mut();
+        if let Some(context) = extensions.get_mut::<SpanContext>() {
+            let mut visitor = AttributesVisitor::default();
+            values.record(&mut visitor);
+            context.attributes.extend(visitor.0);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(context) = span.extensions_mut().remove::<SpanContext>() else { return };
+
+        let mut otlp_span = json!({
+            "traceId": hex(&context.trace_id),
+            "spanId": hex(&context.span_id),
+            "name": span.name(),
+            "kind": SPAN_KIND_INTERNAL,
+            "startTimeUnixNano": unix_nanos(context.start_time),
+            "endTimeUnixNano": unix_nanos(SystemTime::now()),
+            "attributes": context.attributes,
+        });
+
+        if let Some(parent_span_id) = context.parent_span_id {
+            otlp_span["parentSpanId"] = hex(&parent_span_id).into();
+        }
+
+        // The pusher being dropped only means the spans are not exported anymore.
+        let _ = self.sender.send(otlp_span);
+    }
+}
+
+/// Sends the spans captured by an [`OtlpTraceLayer`] to the collector.
+#[derive(Debug)]
+pub struct SpanPusher {
+    receiver: UnboundedReceiver<Value>,
+    /// The spans received and not yet accepted by the collector.
+    pending: Vec<Value>,
+    config: OtlpConfig,
+    client: Client,
+}
+
+impl SpanPusher {
+    /// Pushes the spans closed since the last successful push to the collector, if any.
+    ///
+    /// The spans are kept if the collector doesn't accept them, and pushed again the next time.
+    pub async fn push_pending(&mut self) -> Result<(), Error> {
+        while let Ok(span) = self.receiver.try_recv() {
+            self.pending.push(span);
+        }
+
+        let dropped = drop_oldest(&mut self.pending, MAX_PENDING_SPANS);
+        if dropped > 0 {
+            warn!(target: "metrics", dropped, "Too many spans pending, dropping the oldest ones.");
+        }
+
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let body = json!({
+            "resourceSpans": [{
+                "resource": self.config.resource(),
+                "scopeSpans": [{ "scope": scope(), "spans": self.pending }],
+            }]
+        });
+
+        send(&self.client, &self.config, "traces", body.to_string()).await?;
+        self.pending.clear();
+
+        Ok(())
+    }
+
+    /// Pushes the spans to the collector at the configured interval, forever.
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.config.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(error) = self.push_pending().await {
+                warn!(target: "metrics", %error, "Failed to push spans to the OTLP collector.");
+            }
+        }
+    }
+}
+
+/// Collects the fields of a span as OTLP attributes.
+#[derive(Debug, Default)]
+struct AttributesVisitor(Vec<Value>);
+
+impl AttributesVisitor {
+    fn push(&mut self, field: &Field, value: Value) {
+        self.0.push(json!({ "key": field.name(), "value": value }));
+    }
+}
+
+impl Visit for AttributesVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, json!({ "doubleValue": value }));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, json!({ "boolValue": value }));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, json!({ "stringValue": value }));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, json!({ "stringValue": format!("{value:?}") }));
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
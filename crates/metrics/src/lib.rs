@@ -31,6 +31,14 @@ pub enum Error {
 
     #[error(transparent)]
     Server(#[from] hyper::Error),
+
+    #[cfg(feature = "otlp")]
+    #[error("failed to send OTLP request: {0}")]
+    Otlp(#[from] reqwest::Error),
+
+    #[cfg(feature = "otlp")]
+    #[error("OTLP collector responded with status {0}")]
+    CollectorStatus(u16),
 }
 
 /// A helper trait for reporting metrics.
@@ -0,0 +1,326 @@
+//! Audit of the permissions granted on the world resources.
+//!
+//! The audit flags the grants that deserve a review before a release, like accounts holding
+//! permissions or writers not declared in the profile. A policy can turn some of those findings
+//! into violations, to be used as a gate in a release process.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{BlockId, BlockTag, ContractClass, Felt, StarknetError};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::{Provider, ProviderError};
+
+/// The kind of contract holding a permission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GranteeKind {
+    /// A Dojo contract of the world, with its tag.
+    DojoContract(String),
+    /// An account, which is a contract exposing `__execute__`.
+    Account,
+    /// Any other deployed contract.
+    Contract,
+    /// No contract is deployed at the address.
+    NotDeployed,
+}
+
+/// A contract holding a permission on a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grantee {
+    pub address: Felt,
+    pub kind: GranteeKind,
+    /// Whether the permission is declared in the profile.
+    pub declared: bool,
+}
+
+impl fmt::Display for Grantee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            GranteeKind::DojoContract(tag) => write!(f, "{tag}"),
+            GranteeKind::Account => write!(f, "account {:#066x}", self.address),
+            GranteeKind::Contract => write!(f, "contract {:#066x}", self.address),
+            GranteeKind::NotDeployed => write!(f, "undeployed {:#066x}", self.address),
+        }
+    }
+}
+
+/// The permissions granted on a resource of the world, or on the world itself.
+#[derive(Debug, Clone)]
+pub struct ResourcePermissions {
+    /// The tag of the resource, the name for namespaces.
+    pub tag: String,
+    /// The number of resources covered by the permissions, when the resource is a namespace.
+    pub namespace_resources: Option<usize>,
+    pub writers: Vec<Grantee>,
+    pub owners: Vec<Grantee>,
+}
+
+/// The rules checked by the audit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditRule {
+    /// An account is writer of a resource.
+    AccountWriter,
+    /// An account is owner of a resource.
+    AccountOwner,
+    /// The only owner of a resource is an account.
+    SoleAccountOwner,
+    /// A writer is not declared in the `writers` of the profile.
+    UndeclaredWriter,
+    /// A writer is granted on a whole namespace.
+    NamespaceWriter,
+    /// A resource has more owners than allowed by the policy.
+    TooManyOwners,
+}
+
+impl fmt::Display for AuditRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AuditRule::AccountWriter => "account-writer",
+            AuditRule::AccountOwner => "account-owner",
+            AuditRule::SoleAccountOwner => "sole-account-owner",
+            AuditRule::UndeclaredWriter => "undeclared-writer",
+            AuditRule::NamespaceWriter => "namespace-writer",
+            AuditRule::TooManyOwners => "too-many-owners",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// A finding of the audit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditFinding {
+    pub rule: AuditRule,
+    pub resource: String,
+    pub message: String,
+    /// Whether the finding violates the policy.
+    pub violation: bool,
+}
+
+/// A policy checked by the audit, usually loaded from a TOML file.
+///
+/// ```toml
+/// deny_account_writers = true
+/// deny_undeclared_writers = true
+/// max_owners = 2
+/// allowed_accounts = ["0x1234"]
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditPolicy {
+    /// No account can be writer of a resource.
+    pub deny_account_writers: bool,
+    /// No account can be owner of a resource.
+    pub deny_account_owners: bool,
+    /// The only owner of a resource can't be an account.
+    pub deny_sole_account_owner: bool,
+    /// All the writers must be declared in the profile.
+    pub deny_undeclared_writers: bool,
+    /// No writer can be granted on a whole namespace.
+    pub deny_namespace_writers: bool,
+    /// The maximum number of owners of a resource.
+    pub max_owners: Option<usize>,
+    /// Accounts which are not subject to the account rules, like a multisig.
+    pub allowed_accounts: Vec<Felt>,
+}
+
+impl AuditPolicy {
+    /// Loads a policy from a TOML file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read the audit policy `{}`.", path.display()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid audit policy `{}`.", path.display()))
+    }
+
+    /// Returns whether a finding of the given rule violates the policy.
+    fn denies(&self, rule: AuditRule) -> bool {
+        match rule {
+            AuditRule::AccountWriter => self.deny_account_writers,
+            AuditRule::AccountOwner => self.deny_account_owners,
+            AuditRule::SoleAccountOwner => self.deny_sole_account_owner,
+            AuditRule::UndeclaredWriter => self.deny_undeclared_writers,
+            AuditRule::NamespaceWriter => self.deny_namespace_writers,
+            AuditRule::TooManyOwners => true,
+        }
+    }
+}
+
+/// Audits the permissions of the world resources, against the policy if any.
+pub fn audit_permissions(
+    resources: &[ResourcePermissions],
+    policy: Option<&AuditPolicy>,
+) -> Vec<AuditFinding> {
+    let default_policy = AuditPolicy::default();
+    let policy_or_default = policy.unwrap_or(&default_policy);
+
+    let is_account = |grantee: &Grantee| {
+        grantee.kind == GranteeKind::Account
+            && !policy_or_default.allowed_accounts.contains(&grantee.address)
+    };
+
+    let mut findings = vec![];
+    let mut flag = |rule: AuditRule, resource: &ResourcePermissions, message: String| {
+        findings.push(AuditFinding {
+            rule,
+            resource: resource.tag.clone(),
+            message,
+            violation: policy.is_some_and(|p| p.denies(rule)),
+        });
+    };
+
+    for resource in resources {
+        for writer in &resource.writers {
+            if is_account(writer) {
+                flag(AuditRule::AccountWriter, resource, format!("{writer} is writer."));
+            }
+
+            if !writer.declared {
+                let message = format!("{writer} is writer but is not declared in the profile.");
+                flag(AuditRule::UndeclaredWriter, resource, message);
+            }
+
+            if let Some(count) = resource.namespace_resources {
+                let message =
+                    format!("{writer} is writer of the namespace, covering {count} resources.");
+                flag(AuditRule::NamespaceWriter, resource, message);
+            }
+        }
+
+        for owner in &resource.owners {
+            if is_account(owner) {
+                flag(AuditRule::AccountOwner, resource, format!("{owner} is owner."));
+            }
+        }
+
+        if let [owner] = resource.owners.as_slice() {
+            if is_account(owner) {
+                flag(AuditRule::SoleAccountOwner, resource, format!("{owner} is the only owner."));
+            }
+        }
+
+        if let Some(max_owners) = policy.and_then(|p| p.max_owners) {
+            if resource.owners.len() > max_owners {
+                let message =
+                    format!("{} owners, at most {max_owners} allowed.", resource.owners.len());
+                flag(AuditRule::TooManyOwners, resource, message);
+            }
+        }
+    }
+
+    findings
+}
+
+/// Returns the kind of contract deployed at the given address, which is not a Dojo contract.
+pub async fn non_dojo_grantee_kind<P>(provider: &P, address: Felt) -> Result<GranteeKind>
+where
+    P: Provider + Send + Sync,
+{
+    let block_id = BlockId::Tag(BlockTag::Pending);
+
+    let class_hash = match provider.get_class_hash_at(block_id, address).await {
+        Ok(class_hash) => class_hash,
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+            return Ok(GranteeKind::NotDeployed);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let execute = get_selector_from_name("__execute__")?;
+
+    let is_account = match provider.get_class(block_id, class_hash).await? {
+        ContractClass::Sierra(class) => {
+            class.entry_points_by_type.external.iter().any(|e| e.selector == execute)
+        }
+        ContractClass::Legacy(class) => {
+            class.entry_points_by_type.external.iter().any(|e| e.selector == execute)
+        }
+    };
+
+    Ok(if is_account { GranteeKind::Account } else { GranteeKind::Contract })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grantee(address: u64, kind: GranteeKind, declared: bool) -> Grantee {
+        Grantee { address: Felt::from(address), kind, declared }
+    }
+
+    fn actions() -> Grantee {
+        grantee(0x1, GranteeKind::DojoContract("ns-actions".to_string()), true)
+    }
+
+    #[test]
+    fn test_audit_without_policy() {
+        let resources = vec![
+            ResourcePermissions {
+                tag: "ns-Position".to_string(),
+                namespace_resources: None,
+                writers: vec![actions(), grantee(0xa, GranteeKind::Account, false)],
+                owners: vec![grantee(0xa, GranteeKind::Account, true)],
+            },
+            ResourcePermissions {
+                tag: "ns".to_string(),
+                namespace_resources: Some(3),
+                writers: vec![actions()],
+                owners: vec![actions(), grantee(0xa, GranteeKind::Account, true)],
+            },
+        ];
+
+        let findings = audit_permissions(&resources, None);
+        let rules =
+            findings.iter().map(|f| (f.rule, f.resource.as_str(), f.violation)).collect::<Vec<_>>();
+
+        assert_eq!(
+            rules,
+            vec![
+                (AuditRule::AccountWriter, "ns-Position", false),
+                (AuditRule::UndeclaredWriter, "ns-Position", false),
+                (AuditRule::AccountOwner, "ns-Position", false),
+                (AuditRule::SoleAccountOwner, "ns-Position", false),
+                (AuditRule::NamespaceWriter, "ns", false),
+                (AuditRule::AccountOwner, "ns", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_audit_with_policy() {
+        let policy: AuditPolicy = toml::from_str(
+            r#"
+            deny_account_writers = true
+            max_owners = 1
+            allowed_accounts = ["0xb"]
+            "#,
+        )
+        .unwrap();
+
+        let resources = vec![ResourcePermissions {
+            tag: "ns-Position".to_string(),
+            namespace_resources: None,
+            writers: vec![
+                grantee(0xa, GranteeKind::Account, true),
+                grantee(0xb, GranteeKind::Account, true),
+            ],
+            owners: vec![actions(), grantee(0xb, GranteeKind::Account, true)],
+        }];
+
+        let findings = audit_permissions(&resources, Some(&policy));
+        let rules = findings.iter().map(|f| (f.rule, f.violation)).collect::<Vec<_>>();
+
+        assert_eq!(rules, vec![(AuditRule::AccountWriter, true), (AuditRule::TooManyOwners, true)]);
+        assert_eq!(
+            findings[0].message,
+            format!("account {:#066x} is writer.", Felt::from(0xa_u64))
+        );
+
+        assert!(toml::from_str::<AuditPolicy>("deny_everything = true").is_err());
+    }
+}
@@ -0,0 +1,426 @@
+//! Models and events read and written by the local contracts, and the writer permissions they
+//! require.
+//!
+//! The dependencies come from the aux data of the contracts, models and events generated by the
+//! Dojo plugin when the workspace is compiled, and are resolved to resource tags using the local
+//! world.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use dojo_lang::aux_data::DojoAuxData;
+use dojo_types::naming;
+use dojo_types::system::Dependency;
+use dojo_world::config::ProfileConfig;
+use dojo_world::local::{ResourceLocal, WorldLocal};
+
+/// The dependencies of a local contract, where the dependency names are resource tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractDependencies {
+    pub tag: String,
+    pub dependencies: Vec<Dependency>,
+}
+
+impl ContractDependencies {
+    /// Returns the tags of the resources written by the contract.
+    pub fn writes(&self) -> impl Iterator<Item = &str> {
+        self.dependencies.iter().filter(|d| d.write).map(|d| d.name.as_str())
+    }
+}
+
+/// A mismatch between the writers of the profile config and the dependencies of a contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriterWarning {
+    /// The contract writes a resource it has no permission on.
+    Missing { contract: String, resource: String },
+    /// The contract is granted a writer permission it never uses.
+    Unused { contract: String, resource: String },
+}
+
+impl fmt::Display for WriterWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriterWarning::Missing { contract, resource } => write!(
+                f,
+                "`{contract}` writes `{resource}` but is not a writer of it, add it to the \
+                 `[writers]` of the profile config."
+            ),
+            WriterWarning::Unused { contract, resource } => {
+                write!(f, "`{contract}` is a writer of `{resource}` but never writes it.")
+            }
+        }
+    }
+}
+
+/// A model or event of the aux data.
+#[derive(Debug)]
+struct AuxResource<'a> {
+    module_path: Vec<&'a str>,
+    struct_name: &'a str,
+    name: &'a str,
+    indexes: &'a [String],
+}
+
+impl<'a> AuxResource<'a> {
+    fn new(
+        module_path: &'a str,
+        struct_name: &'a str,
+        name: &'a str,
+        indexes: &'a [String],
+    ) -> Self {
+        Self { module_path: module_path.split("::").collect(), struct_name, name, indexes }
+    }
+}
+
+/// Returns the dependencies of each contract by contract name, from the aux data generated by the
+/// Dojo plugin during the compilation.
+///
+/// The plugin finds the dependencies by type path as written in the contract, which are mapped to
+/// the resource name of the models and events with this struct name, in the modules matching the
+/// path if it's qualified. The resource name is the one given by the `name` argument of the
+/// attribute, if any. Writing a model with `#[index]` members also writes its index models.
+pub fn from_aux_data(aux_data: &DojoAuxData) -> HashMap<String, Vec<Dependency>> {
+    let models = aux_data
+        .models
+        .iter()
+        .map(|(path, m)| AuxResource::new(path, &m.struct_name, &m.name, &m.indexes));
+    let events = aux_data
+        .events
+        .iter()
+        .map(|(path, e)| AuxResource::new(path, &e.struct_name, &e.name, &[]));
+    let resources = models.chain(events).collect::<Vec<_>>();
+
+    let mut contracts: HashMap<String, Vec<Dependency>> = HashMap::new();
+
+    for contract in &aux_data.contracts {
+        let dependencies = contracts.entry(contract.name.clone()).or_default();
+
+        for dependency in &contract.dependencies {
+            let (qualifier, struct_name) = split_type_path(&dependency.name);
+
+            let matches = resources
+                .iter()
+                .filter(|r| r.struct_name == struct_name && r.module_path.ends_with(&qualifier))
+                .collect::<Vec<_>>();
+
+            let mut resolved = matches
+                .iter()
+                .map(|r| r.name)
+                .chain(matches.is_empty().then_some(struct_name))
+                .map(|name| Dependency {
+                    name: name.to_string(),
+                    read: dependency.read,
+                    write: dependency.write,
+                })
+                .collect::<Vec<_>>();
+
+            if dependency.write {
+                resolved.extend(matches.iter().flat_map(|r| r.indexes).map(|name| Dependency {
+                    name: name.clone(),
+                    read: true,
+                    write: true,
+                }));
+            }
+
+            for dependency in resolved {
+                if !dependencies.contains(&dependency) {
+                    dependencies.push(dependency);
+                }
+            }
+        }
+    }
+
+    contracts
+}
+
+/// Splits a type path into its module qualifier and its struct name. The qualifier is relative,
+/// only the segments after the `crate`, `super` and `self` keywords are kept.
+fn split_type_path(path: &str) -> (Vec<&str>, &str) {
+    let mut segments = path.split("::").collect::<Vec<_>>();
+    let struct_name = segments.pop().unwrap_or_default();
+
+    match segments.iter().rposition(|s| matches!(*s, "crate" | "super" | "self")) {
+        Some(keyword) => (segments.split_off(keyword + 1), struct_name),
+        None => (segments, struct_name),
+    }
+}
+
+/// Resolves the dependencies of the local contracts to resource tags.
+///
+/// A dependency is resolved to the model or event of the same namespace as the contract if any,
+/// to all the models and events with the same name otherwise. Dependencies matching no local
+/// resource are ignored.
+pub fn resolve(
+    world: &WorldLocal,
+    analysis: &HashMap<String, Vec<Dependency>>,
+) -> Vec<ContractDependencies> {
+    let resources = world
+        .resources
+        .values()
+        .filter(|r| matches!(r, ResourceLocal::Model(_) | ResourceLocal::Event(_)))
+        .map(|r| (r.namespace(), r.name()))
+        .collect::<Vec<_>>();
+
+    let mut contracts = world
+        .resources
+        .values()
+        .filter_map(|r| {
+            let contract = r.as_contract()?;
+            let dependencies = analysis.get(&contract.common.name)?;
+
+            Some(ContractDependencies {
+                tag: r.tag(),
+                dependencies: resolve_dependencies(
+                    &contract.common.namespace,
+                    dependencies,
+                    &resources,
+                ),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    contracts.sort_by(|a, b| a.tag.cmp(&b.tag));
+    contracts
+}
+
+fn resolve_dependencies(
+    namespace: &str,
+    dependencies: &[Dependency],
+    resources: &[(String, String)],
+) -> Vec<Dependency> {
+    let mut resolved: BTreeMap<String, Dependency> = BTreeMap::new();
+
+    for dependency in dependencies {
+        let matches =
+            resources.iter().filter(|(_, name)| name == &dependency.name).collect::<Vec<_>>();
+
+        let tags = match matches.iter().find(|(ns, _)| ns == namespace) {
+            Some((ns, name)) => vec![naming::get_tag(ns, name)],
+            None => matches.iter().map(|(ns, name)| naming::get_tag(ns, name)).collect(),
+        };
+
+        for tag in tags {
+            let entry = resolved.entry(tag.clone()).or_insert(Dependency {
+                name: tag,
+                read: false,
+                write: false,
+            });
+
+            entry.read |= dependency.read;
+            entry.write |= dependency.write;
+        }
+    }
+
+    resolved.into_values().collect()
+}
+
+/// Checks the writers of the profile config against the resources written by the contracts.
+///
+/// A contract is allowed to write a resource if it's a writer or an owner of the resource or of
+/// its namespace. Grants to contracts that were not analyzed are not checked.
+pub fn check_writers(
+    contracts: &[ContractDependencies],
+    profile_config: &ProfileConfig,
+) -> Vec<WriterWarning> {
+    let granted = |key: &str, contract: &str| {
+        [&profile_config.writers, &profile_config.owners].iter().any(|permissions| {
+            permissions
+                .as_ref()
+                .and_then(|p| p.get(key))
+                .is_some_and(|grantees| grantees.contains(contract))
+        })
+    };
+
+    let mut warnings = vec![];
+
+    for contract in contracts {
+        for resource in contract.writes() {
+            let namespace = naming::get_namespace_from_tag(resource);
+
+            if !granted(resource, &contract.tag) && !granted(&namespace, &contract.tag) {
+                warnings.push(WriterWarning::Missing {
+                    contract: contract.tag.clone(),
+                    resource: resource.to_string(),
+                });
+            }
+        }
+    }
+
+    let analyzed = contracts.iter().map(|c| (c.tag.as_str(), c)).collect::<HashMap<_, _>>();
+    let mut unused = HashSet::new();
+
+    for (resource, grantees) in profile_config.writers.iter().flatten() {
+        for grantee in grantees {
+            let Some(contract) = analyzed.get(grantee.as_str()) else {
+                continue;
+            };
+
+            let is_used = if naming::is_valid_tag(resource) {
+                contract.writes().any(|w| w == resource)
+            } else {
+                contract.writes().any(|w| naming::get_namespace_from_tag(w) == *resource)
+            };
+
+            if !is_used {
+                unused.insert((grantee.clone(), resource.clone()));
+            }
+        }
+    }
+
+    let mut unused = unused.into_iter().collect::<Vec<_>>();
+    unused.sort();
+
+    warnings.extend(
+        unused
+            .into_iter()
+            .map(|(contract, resource)| WriterWarning::Unused { contract, resource }),
+    );
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_lang::aux_data::{ContractAuxData, EventAuxData, ModelAuxData};
+
+    use super::*;
+
+    fn dependency(name: &str, read: bool, write: bool) -> Dependency {
+        Dependency { name: name.to_string(), read, write }
+    }
+
+    #[test]
+    fn test_from_aux_data() {
+        let model = |name: &str, struct_name: &str, indexes: &[&str]| ModelAuxData {
+            name: name.to_string(),
+            struct_name: struct_name.to_string(),
+            members: vec![],
+            indexes: indexes.iter().map(|i| i.to_string()).collect(),
+        };
+
+        let aux_data = DojoAuxData {
+            models: vec![
+                ("game::models".to_string(), model("Pos", "Position", &["PosTileIndex"])),
+                ("game::legacy::models".to_string(), model("Position", "Position", &[])),
+                ("game::models".to_string(), model("Moves", "Moves", &[])),
+            ],
+            events: vec![(
+                "game::events".to_string(),
+                EventAuxData {
+                    name: "Scored".to_string(),
+                    struct_name: "PlayerScored".to_string(),
+                    members: vec![],
+                },
+            )],
+            contracts: vec![
+                ContractAuxData {
+                    name: "actions".to_string(),
+                    systems: vec![],
+                    dependencies: vec![
+                        dependency("Moves", true, false),
+                        dependency("PlayerScored", false, true),
+                        dependency("game::models::Position", true, true),
+                        dependency("Vec2", true, false),
+                    ],
+                },
+                ContractAuxData {
+                    name: "reader".to_string(),
+                    systems: vec![],
+                    dependencies: vec![dependency("Position", true, false)],
+                },
+            ],
+        };
+
+        assert_eq!(
+            from_aux_data(&aux_data),
+            HashMap::from([
+                (
+                    "actions".to_string(),
+                    vec![
+                        dependency("Moves", true, false),
+                        dependency("Scored", false, true),
+                        dependency("Pos", true, true),
+                        dependency("PosTileIndex", true, true),
+                        dependency("Vec2", true, false),
+                    ]
+                ),
+                (
+                    "reader".to_string(),
+                    vec![dependency("Pos", true, false), dependency("Position", true, false)]
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_split_type_path() {
+        assert_eq!(split_type_path("Position"), (vec![], "Position"));
+        assert_eq!(split_type_path("models::Position"), (vec!["models"], "Position"));
+        assert_eq!(
+            split_type_path("crate::game::models::Position"),
+            (vec!["game", "models"], "Position")
+        );
+        assert_eq!(split_type_path("super::super::Position"), (vec![], "Position"));
+    }
+
+    #[test]
+    fn test_resolve_dependencies_prefers_contract_namespace() {
+        let resources = vec![
+            ("ns".to_string(), "Position".to_string()),
+            ("other".to_string(), "Position".to_string()),
+            ("other".to_string(), "Moves".to_string()),
+        ];
+
+        let dependencies = vec![
+            dependency("Position", true, true),
+            dependency("Moves", false, true),
+            dependency("Unknown", false, true),
+        ];
+
+        assert_eq!(
+            resolve_dependencies("ns", &dependencies, &resources),
+            vec![dependency("ns-Position", true, true), dependency("other-Moves", false, true)]
+        );
+    }
+
+    #[test]
+    fn test_check_writers() {
+        let contracts = vec![
+            ContractDependencies {
+                tag: "ns-actions".to_string(),
+                dependencies: vec![
+                    dependency("ns-Position", true, true),
+                    dependency("ns-Moves", true, false),
+                    dependency("other-Score", false, true),
+                ],
+            },
+            ContractDependencies {
+                tag: "ns-reader".to_string(),
+                dependencies: vec![dependency("ns-Position", true, false)],
+            },
+        ];
+
+        let profile_config = ProfileConfig {
+            writers: Some(HashMap::from([
+                ("ns".to_string(), HashSet::from(["ns-actions".to_string()])),
+                ("ns-Position".to_string(), HashSet::from(["ns-reader".to_string()])),
+                ("ns-Moves".to_string(), HashSet::from(["ns-external".to_string()])),
+            ])),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check_writers(&contracts, &profile_config),
+            vec![
+                WriterWarning::Missing {
+                    contract: "ns-actions".to_string(),
+                    resource: "other-Score".to_string(),
+                },
+                WriterWarning::Unused {
+                    contract: "ns-reader".to_string(),
+                    resource: "ns-Position".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Fetching of the world events, and decoding of the model records they write.
+//!
+//! The events are fetched by ranges of blocks and pages of events, which is shared by the
+//! commands replaying the world events: `sozo events`, `sozo export`, `sozo model blame` and
+//! `sozo model migrate`.
+
+use anyhow::{bail, Result};
+use dojo_types::schema::{Member, Struct, Ty};
+use dojo_world::contracts::abigen::world::Event as WorldEvent;
+use starknet::core::types::{BlockId, BlockTag, EmittedEvent, EventFilter, Felt};
+use starknet::core::utils::get_selector_from_name;
+use starknet::macros::felt;
+use starknet::providers::Provider;
+use tracing::{trace, warn};
+
+/// The number of events fetched per page by the commands replaying the world events.
+pub(crate) const EVENTS_CHUNK_SIZE: u64 = 500;
+
+/// Fetches the events emitted by a world, by ranges of at most `max_block_range` blocks and pages
+/// of `chunk_size` events.
+#[derive(Debug)]
+pub struct WorldEventFetcher<'a, P> {
+    provider: &'a P,
+    world_address: Felt,
+    chunk_size: u64,
+    max_block_range: u64,
+    /// Katana returns an empty page with a continuation token at the end of the events.
+    is_katana: bool,
+}
+
+impl<'a, P: Provider + Send + Sync> WorldEventFetcher<'a, P> {
+    pub async fn new(
+        provider: &'a P,
+        world_address: Felt,
+        chunk_size: u64,
+        max_block_range: u64,
+    ) -> Result<Self> {
+        let chain_id = provider.chain_id().await?;
+        // Katana if it's not `SN_SEPOLIA` or `SN_MAIN`.
+        let is_katana =
+            chain_id != felt!("0x534e5f5345504f4c4941") && chain_id != felt!("0x534e5f4d41494e");
+
+        Ok(Self { provider, world_address, chunk_size, max_block_range, is_katana })
+    }
+
+    /// Returns the provider the events are fetched from.
+    pub fn provider(&self) -> &'a P {
+        self.provider
+    }
+
+    /// Returns the RPC filter of the world events matching `keys` in the given blocks.
+    fn filter(&self, keys: Option<Vec<Vec<Felt>>>, from: BlockId, to: BlockId) -> EventFilter {
+        EventFilter {
+            from_block: Some(from),
+            to_block: Some(to),
+            address: Some(self.world_address),
+            keys,
+        }
+    }
+
+    /// Fetches the world events matching `keys` in a block range.
+    pub async fn fetch_range(
+        &self,
+        keys: Option<Vec<Vec<Felt>>>,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<EmittedEvent>> {
+        let mut current_from = from_block;
+        let mut events = Vec::new();
+
+        while current_from <= to_block {
+            let current_to = std::cmp::min(current_from + self.max_block_range - 1, to_block);
+
+            let filter = self.filter(
+                keys.clone(),
+                BlockId::Number(current_from),
+                BlockId::Number(current_to),
+            );
+
+            trace!(
+                world_address = format!("{:#066x}", self.world_address),
+                self.chunk_size,
+                ?filter,
+                "Fetching remote world events for block range {}-{}.",
+                current_from,
+                current_to
+            );
+
+            events.extend(self.fetch(filter).await?);
+
+            current_from = current_to + 1;
+        }
+
+        Ok(events)
+    }
+
+    /// Fetches the world events matching `keys` in the pending block.
+    pub async fn fetch_pending(&self, keys: Option<Vec<Vec<Felt>>>) -> Result<Vec<EmittedEvent>> {
+        let pending = BlockId::Tag(BlockTag::Pending);
+        self.fetch(self.filter(keys, pending, pending)).await
+    }
+
+    /// Fetches the world events matching `keys` in a block range, along with the emitted events
+    /// they are parsed from.
+    pub async fn fetch_world_events(
+        &self,
+        keys: Option<Vec<Vec<Felt>>>,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<(WorldEvent, EmittedEvent)>> {
+        self.fetch_range(keys, from_block, to_block)
+            .await?
+            .into_iter()
+            .map(|event| match WorldEvent::try_from(&event) {
+                Ok(world_event) => Ok((world_event, event)),
+                Err(e) => bail!("Failed to parse world event: {e}"),
+            })
+            .collect()
+    }
+
+    /// Fetches all the pages of events matching the filter.
+    async fn fetch(&self, filter: EventFilter) -> Result<Vec<EmittedEvent>> {
+        let mut events = Vec::new();
+        let mut continuation_token = None;
+
+        loop {
+            let page = self
+                .provider
+                .get_events(filter.clone(), continuation_token, self.chunk_size)
+                .await?;
+
+            if self.is_katana && page.events.is_empty() {
+                break;
+            }
+
+            events.extend(page.events);
+
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+/// Deserializes the non-key members of a model record from the values of a store event.
+///
+/// The records written before an upgrade of the model don't have the members added by the
+/// upgrade, which are not returned.
+pub fn decode_record_values<'a>(
+    schema: &'a Struct,
+    values: &[Felt],
+) -> Result<Vec<(&'a Member, Ty)>> {
+    let mut values = values.to_vec();
+    let mut decoded = vec![];
+
+    for member in schema.children.iter().filter(|m| !m.key) {
+        if values.is_empty() {
+            warn!(
+                model = %schema.name,
+                member = %member.name,
+                "Record written before the member was added to the model, the member is skipped."
+            );
+            break;
+        }
+
+        let mut ty = member.ty.clone();
+        ty.deserialize(&mut values)?;
+        decoded.push((member, ty));
+    }
+
+    Ok(decoded)
+}
+
+/// Deserializes the members of a model record written by a `StoreUpdateMember` event.
+///
+/// The `#[packed]` members of a group are written together, the event being emitted with the
+/// selector of the first member of the group and the values of all the members.
+pub fn decode_member_values<'a>(
+    schema: &'a Struct,
+    member_selector: Felt,
+    values: &[Felt],
+) -> Result<Vec<(&'a Member, Ty)>> {
+    let mut group = None;
+    for members in schema.field_members() {
+        if get_selector_from_name(&members[0].name)? == member_selector {
+            group = Some(members);
+            break;
+        }
+    }
+
+    let Some(group) = group else {
+        bail!("No member with selector {:#066x} in `{}`.", member_selector, schema.name);
+    };
+
+    let mut values = values.to_vec();
+
+    group
+        .into_iter()
+        .map(|member| {
+            let mut ty = member.ty.clone();
+            ty.deserialize(&mut values)?;
+            Ok((member, ty))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+
+    use super::*;
+
+    fn stats_schema() -> Struct {
+        let member = |name: &str, key: bool, packed: bool| Member {
+            name: name.to_string(),
+            ty: Ty::Primitive(Primitive::U8(None)),
+            key,
+            packed,
+        };
+
+        Struct {
+            name: "Stats".to_string(),
+            children: vec![
+                member("player", true, false),
+                member("hp", false, true),
+                member("mana", false, true),
+                member("level", false, false),
+                member("rank", false, false),
+            ],
+        }
+    }
+
+    fn decoded(members: Vec<(&Member, Ty)>) -> Vec<(String, Ty)> {
+        members.into_iter().map(|(m, ty)| (m.name.clone(), ty)).collect()
+    }
+
+    fn u8_ty(value: u8) -> Ty {
+        Ty::Primitive(Primitive::U8(Some(value)))
+    }
+
+    #[test]
+    fn test_decode_record_values() {
+        let schema = stats_schema();
+        let values = [Felt::from(1), Felt::from(2), Felt::from(3), Felt::from(4)];
+
+        assert_eq!(
+            decoded(decode_record_values(&schema, &values).unwrap()),
+            vec![
+                ("hp".to_string(), u8_ty(1)),
+                ("mana".to_string(), u8_ty(2)),
+                ("level".to_string(), u8_ty(3)),
+                ("rank".to_string(), u8_ty(4)),
+            ]
+        );
+
+        // A record written before `rank` was added to the model.
+        assert_eq!(decode_record_values(&schema, &values[..3]).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_decode_member_values() {
+        let schema = stats_schema();
+
+        // The packed group of `hp` and `mana` is written with the selector of `hp`.
+        let hp = get_selector_from_name("hp").unwrap();
+        assert_eq!(
+            decoded(decode_member_values(&schema, hp, &[Felt::from(5), Felt::from(6)]).unwrap()),
+            vec![("hp".to_string(), u8_ty(5)), ("mana".to_string(), u8_ty(6))]
+        );
+
+        let rank = get_selector_from_name("rank").unwrap();
+        assert_eq!(
+            decoded(decode_member_values(&schema, rank, &[Felt::from(7)]).unwrap()),
+            vec![("rank".to_string(), u8_ty(7))]
+        );
+
+        // `mana` has no field of its own.
+        let mana = get_selector_from_name("mana").unwrap();
+        assert!(decode_member_values(&schema, mana, &[Felt::from(6)]).is_err());
+    }
+}
@@ -0,0 +1,1021 @@
+//! Export of the world events and of the model records for offline analysis.
+//!
+//! The events of the world are replayed to maintain the current state of the model records,
+//! which is written with one table per model, where each member of the model is a typed column.
+//! Each store event updates the row of its entity in place. The Dojo events are written with one
+//! table per event, and every world event is also kept in a raw `events` table. The export state
+//! is kept alongside the exported tables, so an export can be resumed from the last exported
+//! block.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use dojo_types::primitive::SqlType;
+use dojo_types::schema::{Struct, Ty};
+use dojo_world::contracts::abigen::world::Event as WorldEvent;
+use dojo_world::contracts::naming;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::{ConnectOptions, Connection, Row as _};
+use starknet::core::types::{EmittedEvent, Felt};
+use tracing::{trace, warn};
+
+use crate::events::{decode_member_values, decode_record_values};
+
+/// The name of the table with the raw world events.
+pub const RAW_EVENTS_TABLE: &str = "events";
+
+/// The name of the table where the SQLite sink keeps the export state.
+const SQLITE_STATE_TABLE: &str = "_sozo_export";
+
+/// The name of the file where the NDJSON sink keeps the export state.
+const NDJSON_STATE_FILE: &str = "export_state.json";
+
+/// The column identifying the rows of the model tables.
+const ENTITY_ID_COLUMN: &str = "internal_entity_id";
+
+/// A column of an exported table.
+#[derive(Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub sql_type: SqlType,
+}
+
+impl Column {
+    fn new(name: impl Into<String>, sql_type: SqlType) -> Self {
+        Self { name: name.into(), sql_type }
+    }
+}
+
+/// An exported table.
+#[derive(Debug)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<Column>,
+    /// The column identifying the rows of the tables updated in place, `None` for the tables
+    /// which are only appended to.
+    pub primary_key: Option<String>,
+}
+
+/// The values of a row of an exported table, in the order of the table columns.
+pub type TableRow = Vec<Option<String>>;
+
+/// A change of the rows of an exported table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    /// Appends a row, with a value for each column of the table.
+    Insert(TableRow),
+    /// Sets some columns of the row identified by `key`, inserting the row if it doesn't exist.
+    Upsert { key: String, columns: Vec<(String, Option<String>)> },
+    /// Deletes the row identified by `key`.
+    Delete { key: String },
+}
+
+/// The destination of an export.
+#[async_trait]
+pub trait ExportSink: Send {
+    /// Applies the changes of the exported tables in order, creating the tables or adding the
+    /// missing columns if required, and saves the state of the export along with them.
+    async fn write(&mut self, changes: &[(&Table, RowChange)], state: &ExportState) -> Result<()>;
+
+    /// Loads the state of the previous export, if any.
+    async fn load_state(&mut self) -> Result<Option<ExportState>>;
+}
+
+/// The state of an export, used to resume it.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportState {
+    /// The last exported block.
+    pub last_block: Option<u64>,
+}
+
+/// A model or a Dojo event to export.
+#[derive(Debug)]
+struct ExportedResource {
+    schema: Ty,
+    table: Table,
+}
+
+/// Exports the world events to a sink.
+pub struct WorldExporter {
+    sink: Box<dyn ExportSink>,
+    models: HashMap<Felt, ExportedResource>,
+    events: HashMap<Felt, ExportedResource>,
+    last_block: Option<u64>,
+}
+
+impl WorldExporter {
+    /// Creates an exporter for the given models and Dojo events, given by tag and schema, and
+    /// loads the state of the previous export from the sink.
+    pub async fn new(
+        mut sink: Box<dyn ExportSink>,
+        models: Vec<(String, Ty)>,
+        events: Vec<(String, Ty)>,
+    ) -> Result<Self> {
+        let models = models
+            .into_iter()
+            .map(|(tag, schema)| {
+                let selector = naming::compute_selector_from_tag(&tag);
+                let table = model_table(&tag, &schema)?;
+                Ok((selector, ExportedResource { schema, table }))
+            })
+            .collect::<Result<_>>()?;
+
+        let events = events
+            .into_iter()
+            .map(|(tag, schema)| {
+                let selector = naming::compute_selector_from_tag(&tag);
+                let table = event_table(&tag, &schema)?;
+                Ok((selector, ExportedResource { schema, table }))
+            })
+            .collect::<Result<_>>()?;
+
+        let state = sink.load_state().await?.unwrap_or_default();
+
+        Ok(Self { sink, models, events, last_block: state.last_block })
+    }
+
+    /// Returns the last block exported to the sink, if any.
+    pub fn last_block(&self) -> Option<u64> {
+        self.last_block
+    }
+
+    /// Exports the world events emitted up to `to_block`, which must all be newer than the last
+    /// exported block.
+    pub async fn export(&mut self, events: &[EmittedEvent], to_block: u64) -> Result<()> {
+        if let Some(last_block) = self.last_block {
+            if events.iter().any(|e| e.block_number.is_none_or(|n| n <= last_block)) {
+                bail!("Events up to block {last_block} have already been exported.");
+            }
+        }
+
+        let raw_table = raw_events_table();
+        let mut changes = Vec::with_capacity(events.len() * 2);
+
+        for event in events {
+            changes.push((&raw_table, RowChange::Insert(raw_event_row(event))));
+
+            let Ok(world_event) = WorldEvent::try_from(event) else {
+                trace!(?event, "Unknown world event, only exported as a raw event.");
+                continue;
+            };
+
+            if let WorldEvent::EventEmitted(e) = &world_event {
+                if let Some(exported) = self.events.get(&e.selector) {
+                    let ty = decode_event(&exported.schema, &e.keys, &e.values)?;
+
+                    let mut row = vec![
+                        event.block_number.map(|n| n.to_string()),
+                        Some(format!("{:#066x}", event.transaction_hash)),
+                        Some(format!("{:#066x}", e.system_address.0)),
+                    ];
+                    ty_values(&ty, &mut row);
+
+                    changes.push((&exported.table, RowChange::Insert(row)));
+                }
+            } else if let Some(change) = record_change(&self.models, &world_event, event)? {
+                changes.push(change);
+            }
+        }
+
+        let state = ExportState { last_block: Some(to_block) };
+        self.sink.write(&changes, &state).await?;
+        self.last_block = state.last_block;
+
+        Ok(())
+    }
+}
+
+/// Returns the change of the row of a model record written by a store event, if the model is
+/// exported.
+///
+/// Only the members written by the event are set, the members missing from the records written
+/// before an upgrade of the model being set to null.
+fn record_change<'a>(
+    models: &'a HashMap<Felt, ExportedResource>,
+    world_event: &WorldEvent,
+    event: &EmittedEvent,
+) -> Result<Option<(&'a Table, RowChange)>> {
+    let (selector, entity_id) = match world_event {
+        WorldEvent::StoreSetRecord(e) => (e.selector, e.entity_id),
+        WorldEvent::StoreUpdateRecord(e) => (e.selector, e.entity_id),
+        WorldEvent::StoreUpdateMember(e) => (e.selector, e.entity_id),
+        WorldEvent::StoreDelRecord(e) => (e.selector, e.entity_id),
+        _ => return Ok(None),
+    };
+
+    let Some(exported) = models.get(&selector) else {
+        trace!(selector = format!("{:#066x}", selector), "Unknown model.");
+        return Ok(None);
+    };
+
+    let Ty::Struct(schema) = &exported.schema else {
+        bail!("The schema of `{}` is expected to be a struct.", exported.schema.name());
+    };
+
+    let key = format!("{:#066x}", entity_id);
+    let mut columns = vec![];
+
+    match world_event {
+        WorldEvent::StoreSetRecord(e) => {
+            let mut keys = e.keys.clone();
+            for member in schema.children.iter().filter(|m| m.key) {
+                let mut ty = member.ty.clone();
+                ty.deserialize(&mut keys)?;
+                member_columns(&member.name, &ty, &mut columns);
+            }
+
+            record_values_columns(schema, &e.values, &mut columns)?;
+        }
+        WorldEvent::StoreUpdateRecord(e) => record_values_columns(schema, &e.values, &mut columns)?,
+        WorldEvent::StoreUpdateMember(e) => {
+            for (member, ty) in decode_member_values(schema, e.member_selector, &e.values)? {
+                member_columns(&member.name, &ty, &mut columns);
+            }
+        }
+        WorldEvent::StoreDelRecord(_) => {
+            return Ok(Some((&exported.table, RowChange::Delete { key })));
+        }
+        _ => return Ok(None),
+    }
+
+    columns.push(("internal_block_number".to_string(), event.block_number.map(|n| n.to_string())));
+    columns.push((
+        "internal_transaction_hash".to_string(),
+        Some(format!("{:#066x}", event.transaction_hash)),
+    ));
+
+    Ok(Some((&exported.table, RowChange::Upsert { key, columns })))
+}
+
+/// Adds the columns of the non-key members of a model record, from the values of a store event.
+fn record_values_columns(
+    schema: &Struct,
+    values: &[Felt],
+    columns: &mut Vec<(String, Option<String>)>,
+) -> Result<()> {
+    let decoded = decode_record_values(schema, values)?;
+
+    for member in schema.children.iter().filter(|m| !m.key) {
+        let decoded = decoded.iter().find(|(m, _)| m.name == member.name);
+        member_columns(&member.name, decoded.map_or(&member.ty, |(_, ty)| ty), columns);
+    }
+
+    Ok(())
+}
+
+/// Adds the columns of a member with their value.
+fn member_columns(name: &str, ty: &Ty, columns: &mut Vec<(String, Option<String>)>) {
+    let mut names = vec![];
+    ty_columns(name, ty, &mut names);
+
+    let mut values = vec![];
+    ty_values(ty, &mut values);
+
+    columns.extend(names.into_iter().map(|c| c.name).zip(values));
+}
+
+/// Returns the table of the raw world events.
+fn raw_events_table() -> Table {
+    Table {
+        name: RAW_EVENTS_TABLE.to_string(),
+        columns: vec![
+            Column::new("block_number", SqlType::Integer),
+            Column::new("transaction_hash", SqlType::Text),
+            Column::new("selector", SqlType::Text),
+            Column::new("keys", SqlType::Text),
+            Column::new("data", SqlType::Text),
+        ],
+        primary_key: None,
+    }
+}
+
+/// Returns the row of a raw world event, the keys and data being JSON arrays.
+fn raw_event_row(event: &EmittedEvent) -> TableRow {
+    let felts = |felts: &[Felt]| {
+        JsonValue::from(felts.iter().map(|f| format!("{:#x}", f)).collect::<Vec<_>>()).to_string()
+    };
+
+    vec![
+        event.block_number.map(|n| n.to_string()),
+        Some(format!("{:#066x}", event.transaction_hash)),
+        event.keys.first().map(|k| format!("{:#066x}", k)),
+        Some(felts(&event.keys)),
+        Some(felts(&event.data)),
+    ]
+}
+
+/// Returns the table of the current records of a model.
+fn model_table(tag: &str, schema: &Ty) -> Result<Table> {
+    let mut columns = vec![Column::new(ENTITY_ID_COLUMN, SqlType::Text)];
+    members_columns(tag, schema, &mut columns)?;
+    columns.push(Column::new("internal_block_number", SqlType::Integer));
+    columns.push(Column::new("internal_transaction_hash", SqlType::Text));
+
+    Ok(Table { name: tag.to_string(), columns, primary_key: Some(ENTITY_ID_COLUMN.to_string()) })
+}
+
+/// Returns the table of the emitted instances of a Dojo event.
+fn event_table(tag: &str, schema: &Ty) -> Result<Table> {
+    let mut columns = vec![
+        Column::new("internal_block_number", SqlType::Integer),
+        Column::new("internal_transaction_hash", SqlType::Text),
+        Column::new("internal_system_address", SqlType::Text),
+    ];
+    members_columns(tag, schema, &mut columns)?;
+
+    Ok(Table { name: tag.to_string(), columns, primary_key: None })
+}
+
+/// Adds the columns of the members of a model or event schema.
+fn members_columns(tag: &str, schema: &Ty, columns: &mut Vec<Column>) -> Result<()> {
+    let Ty::Struct(s) = schema else {
+        bail!("The schema of `{tag}` is expected to be a struct.");
+    };
+
+    for member in &s.children {
+        ty_columns(&member.name, &member.ty, columns);
+    }
+
+    Ok(())
+}
+
+/// Adds the columns of a type, nested struct members being flattened into `parent.member`
+/// columns, and other complex types being stored as JSON.
+fn ty_columns(name: &str, ty: &Ty, columns: &mut Vec<Column>) {
+    match ty {
+        Ty::Primitive(p) => columns.push(Column::new(name, p.to_sql_type())),
+        Ty::Struct(s) => {
+            for member in &s.children {
+                ty_columns(&format!("{name}.{}", member.name), &member.ty, columns);
+            }
+        }
+        _ => columns.push(Column::new(name, SqlType::Text)),
+    }
+}
+
+/// Adds the values of a type, in the order of the columns returned by [`ty_columns`] for its
+/// schema. Values which are not set are exported as null.
+fn ty_values(ty: &Ty, values: &mut TableRow) {
+    match ty {
+        Ty::Struct(s) => {
+            for member in &s.children {
+                ty_values(&member.ty, values);
+            }
+        }
+        Ty::Primitive(p) => values.push(ty.to_json_value().ok().map(|_| p.to_sql_value())),
+        Ty::Enum(e) => values.push(e.option().ok().and_then(|option| {
+            if option.ty == Ty::Tuple(vec![]) {
+                Some(option.name.clone())
+            } else {
+                ty.to_json_value().ok().map(|v| v.to_string())
+            }
+        })),
+        Ty::ByteArray(s) => values.push(Some(s.clone())),
+        _ => values.push(ty.to_json_value().ok().map(|v| v.to_string())),
+    }
+}
+
+/// Deserializes the members of an event.
+///
+/// The members missing from an event emitted before an upgrade of the event are exported as
+/// null, as all the members of an event which can't be deserialized with the current schema.
+fn decode_event(schema: &Ty, keys: &[Felt], values: &[Felt]) -> Result<Ty> {
+    let Ty::Struct(schema) = schema else {
+        bail!("The schema of `{}` is expected to be a struct.", schema.name());
+    };
+
+    let mut decoded = schema.clone();
+
+    match decoded.deserialize_members(keys, values) {
+        Ok(missing) if !missing.is_empty() => warn!(
+            event = %schema.name,
+            ?missing,
+            "Event emitted before the members were added to the event, the members are exported \
+             as null."
+        ),
+        Ok(_) => {}
+        Err(error) => {
+            warn!(
+                event = %schema.name,
+                %error,
+                "Event not matching the schema of the event, its members are exported as null."
+            );
+            decoded = schema.clone();
+        }
+    }
+
+    Ok(Ty::Struct(decoded))
+}
+
+/// Returns the quoted form of an SQL identifier.
+fn quote(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Exports to a SQLite database.
+///
+/// The changes of an export and its state are written in a single transaction.
+#[derive(Debug)]
+pub struct SqliteSink {
+    conn: SqliteConnection,
+}
+
+impl SqliteSink {
+    /// Opens the database at the given path, creating it if it doesn't exist.
+    pub async fn open(path: &Path) -> Result<Self> {
+        let conn =
+            SqliteConnectOptions::new().filename(path).create_if_missing(true).connect().await?;
+
+        Self::from_connection(conn).await
+    }
+
+    /// Creates the sink from a connection to the database, creating the state table if required.
+    async fn from_connection(mut conn: SqliteConnection) -> Result<Self> {
+        let create = format!(
+            "CREATE TABLE IF NOT EXISTS {} (id INTEGER PRIMARY KEY, last_block INTEGER)",
+            quote(SQLITE_STATE_TABLE)
+        );
+        sqlx::query(&create).execute(&mut conn).await?;
+
+        Ok(Self { conn })
+    }
+}
+
+/// Creates a table if it doesn't exist, adding the missing columns and the unique index of its
+/// primary key.
+async fn prepare_table(conn: &mut SqliteConnection, table: &Table) -> Result<()> {
+    let name = quote(&table.name);
+
+    let columns =
+        table.columns.iter().map(|c| format!("{} {}", quote(&c.name), c.sql_type.as_ref()));
+
+    let create =
+        format!("CREATE TABLE IF NOT EXISTS {name} ({})", columns.collect::<Vec<_>>().join(", "));
+    sqlx::query(&create).execute(&mut *conn).await?;
+
+    // The schema of the resource may have been upgraded since the table was created.
+    let existing = sqlx::query(&format!("PRAGMA table_info({name})"))
+        .fetch_all(&mut *conn)
+        .await?
+        .iter()
+        .map(|row| row.try_get::<String, _>("name"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for column in table.columns.iter().filter(|c| !existing.contains(&c.name)) {
+        let alter = format!(
+            "ALTER TABLE {name} ADD COLUMN {} {}",
+            quote(&column.name),
+            column.sql_type.as_ref()
+        );
+        sqlx::query(&alter).execute(&mut *conn).await?;
+    }
+
+    if let Some(key) = &table.primary_key {
+        let index = quote(&format!("{}_{key}", table.name));
+        let create =
+            format!("CREATE UNIQUE INDEX IF NOT EXISTS {index} ON {name} ({})", quote(key));
+        sqlx::query(&create).execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
+/// Applies a change to the rows of a table.
+async fn apply_change(
+    conn: &mut SqliteConnection,
+    table: &Table,
+    change: &RowChange,
+) -> Result<()> {
+    let name = quote(&table.name);
+
+    let primary_key = || {
+        table
+            .primary_key
+            .as_deref()
+            .map(quote)
+            .ok_or_else(|| anyhow!("The table `{}` has no primary key.", table.name))
+    };
+
+    match change {
+        RowChange::Insert(row) => {
+            let insert = format!(
+                "INSERT INTO {name} ({}) VALUES ({})",
+                table.columns.iter().map(|c| quote(&c.name)).collect::<Vec<_>>().join(", "),
+                vec!["?"; table.columns.len()].join(", ")
+            );
+
+            let mut query = sqlx::query(&insert);
+            for value in row {
+                query = query.bind(value.as_deref());
+            }
+            query.execute(&mut *conn).await?;
+        }
+        RowChange::Upsert { key, columns } => {
+            let primary_key = primary_key()?;
+
+            let names = columns.iter().map(|(c, _)| quote(c)).collect::<Vec<_>>();
+            let on_conflict = if names.is_empty() {
+                "DO NOTHING".to_string()
+            } else {
+                let updates = names.iter().map(|c| format!("{c} = excluded.{c}"));
+                format!("DO UPDATE SET {}", updates.collect::<Vec<_>>().join(", "))
+            };
+
+            let upsert = format!(
+                "INSERT INTO {name} ({primary_key}{}) VALUES (?{}) ON CONFLICT({primary_key}) \
+                 {on_conflict}",
+                names.iter().map(|c| format!(", {c}")).collect::<String>(),
+                ", ?".repeat(names.len()),
+            );
+
+            let mut query = sqlx::query(&upsert).bind(key.as_str());
+            for (_, value) in columns {
+                query = query.bind(value.as_deref());
+            }
+            query.execute(&mut *conn).await?;
+        }
+        RowChange::Delete { key } => {
+            let delete = format!("DELETE FROM {name} WHERE {} = ?", primary_key()?);
+            sqlx::query(&delete).bind(key.as_str()).execute(&mut *conn).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl ExportSink for SqliteSink {
+    async fn write(&mut self, changes: &[(&Table, RowChange)], state: &ExportState) -> Result<()> {
+        let mut tx = self.conn.begin().await?;
+        let mut prepared = HashSet::new();
+
+        for (table, change) in changes {
+            if prepared.insert(table.name.as_str()) {
+                prepare_table(&mut *tx, table).await?;
+            }
+
+            apply_change(&mut *tx, table, change).await?;
+        }
+
+        let upsert = format!(
+            "INSERT OR REPLACE INTO {} (id, last_block) VALUES (0, ?)",
+            quote(SQLITE_STATE_TABLE)
+        );
+        let last_block = state.last_block.map(i64::try_from).transpose()?;
+        sqlx::query(&upsert).bind(last_block).execute(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn load_state(&mut self) -> Result<Option<ExportState>> {
+        let select = format!("SELECT last_block FROM {} WHERE id = 0", quote(SQLITE_STATE_TABLE));
+        let Some(row) = sqlx::query(&select).fetch_optional(&mut self.conn).await? else {
+            return Ok(None);
+        };
+
+        let last_block = row.try_get::<Option<i64>, _>("last_block")?;
+        Ok(Some(ExportState { last_block: last_block.map(u64::try_from).transpose()? }))
+    }
+}
+
+/// Exports to a directory of newline-delimited JSON files, one file per table.
+///
+/// The rows of the tables updated in place are rewritten into a new file replacing the previous
+/// one, and the state is written last. An interrupted export may leave its raw and Dojo events
+/// appended without its state, and they are appended again when the export is resumed.
+#[derive(Debug)]
+pub struct NdjsonSink {
+    dir: PathBuf,
+}
+
+impl NdjsonSink {
+    /// Opens the directory at the given path, creating it if it doesn't exist.
+    pub fn open(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    fn table_path(&self, table: &Table) -> PathBuf {
+        self.dir.join(format!("{}.ndjson", table.name))
+    }
+
+    /// Appends the inserted rows to the file of a table.
+    fn append(&self, table: &Table, changes: &[&RowChange]) -> Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(self.table_path(table))?;
+        let mut writer = BufWriter::new(file);
+
+        for change in changes {
+            let RowChange::Insert(row) = change else {
+                bail!("The table `{}` can only be appended to.", table.name);
+            };
+
+            let object = table
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| (column.name.clone(), ndjson_value(column, value)))
+                .collect::<serde_json::Map<_, _>>();
+
+            writeln!(writer, "{}", JsonValue::Object(object))?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Applies the changes to the rows of a table identified by `primary_key`, and rewrites its
+    /// file.
+    fn update(&self, table: &Table, primary_key: &str, changes: &[&RowChange]) -> Result<()> {
+        let path = self.table_path(table);
+        let mut rows = BTreeMap::new();
+
+        if path.exists() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                let object: serde_json::Map<String, JsonValue> = serde_json::from_str(&line?)?;
+                let key = object.get(primary_key).and_then(|k| k.as_str()).map(str::to_string);
+                let key = key.ok_or_else(|| anyhow!("Row without key in `{}`.", path.display()))?;
+
+                rows.insert(key, object);
+            }
+        }
+
+        for change in changes {
+            match change {
+                RowChange::Upsert { key, columns } => {
+                    let object = rows.entry(key.clone()).or_insert_with(|| {
+                        let mut object = table
+                            .columns
+                            .iter()
+                            .map(|c| (c.name.clone(), JsonValue::Null))
+                            .collect::<serde_json::Map<_, _>>();
+                        object.insert(primary_key.to_string(), JsonValue::from(key.clone()));
+                        object
+                    });
+
+                    for (name, value) in columns {
+                        let value = match table.columns.iter().find(|c| &c.name == name) {
+                            Some(column) => ndjson_value(column, value),
+                            None => value.clone().map_or(JsonValue::Null, JsonValue::from),
+                        };
+                        object.insert(name.clone(), value);
+                    }
+                }
+                RowChange::Delete { key } => {
+                    rows.remove(key);
+                }
+                RowChange::Insert(_) => {
+                    bail!("Rows are inserted in `{}` by their key.", table.name);
+                }
+            }
+        }
+
+        let objects = rows.into_values().map(JsonValue::Object);
+        write_replacing(&path, objects.map(|o| o.to_string()))
+    }
+}
+
+/// Writes lines to a new file replacing the file at `path` once they are all written.
+fn write_replacing(path: &Path, lines: impl IntoIterator<Item = String>) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+
+    for line in lines {
+        writeln!(writer, "{line}")?;
+    }
+
+    writer.flush()?;
+    drop(writer);
+
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Returns the JSON value of a column, integers being written as numbers.
+fn ndjson_value(column: &Column, value: &Option<String>) -> JsonValue {
+    match value {
+        None => JsonValue::Null,
+        Some(v) if column.sql_type == SqlType::Integer => {
+            v.parse::<i64>().map(JsonValue::from).unwrap_or_else(|_| JsonValue::from(v.clone()))
+        }
+        Some(v) => JsonValue::from(v.clone()),
+    }
+}
+
+#[async_trait]
+impl ExportSink for NdjsonSink {
+    async fn write(&mut self, changes: &[(&Table, RowChange)], state: &ExportState) -> Result<()> {
+        // The changes grouped by table, in order.
+        let mut tables: Vec<(&Table, Vec<&RowChange>)> = vec![];
+        for (table, change) in changes {
+            match tables.iter_mut().find(|(t, _)| t.name == table.name) {
+                Some((_, table_changes)) => table_changes.push(change),
+                None => tables.push((*table, vec![change])),
+            }
+        }
+
+        for (table, changes) in &tables {
+            match &table.primary_key {
+                Some(primary_key) => self.update(table, primary_key, changes)?,
+                None => self.append(table, changes)?,
+            }
+        }
+
+        write_replacing(&self.dir.join(NDJSON_STATE_FILE), [serde_json::to_string(state)?])
+    }
+
+    async fn load_state(&mut self) -> Result<Option<ExportState>> {
+        let path = self.dir.join(NDJSON_STATE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let state = fs::read_to_string(&path)?;
+        serde_json::from_str(&state)
+            .map(Some)
+            .map_err(|e| anyhow!("Invalid export state `{}`: {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Enum, EnumOption, Member};
+    use dojo_world::contracts::abigen::world::{
+        StoreDelRecord, StoreSetRecord, StoreUpdateMember, StoreUpdateRecord,
+    };
+    use sqlx::Row;
+    use starknet::core::utils::get_selector_from_name;
+
+    use super::*;
+
+    fn position_schema() -> Ty {
+        let member = |name: &str, ty: Ty, key: bool| Member {
+            name: name.to_string(),
+            ty,
+            key,
+            packed: false,
+        };
+
+        Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(None)), true),
+                member(
+                    "vec",
+                    Ty::Struct(Struct {
+                        name: "Vec2".to_string(),
+                        children: vec![
+                            member("x", Ty::Primitive(Primitive::U32(None)), false),
+                            member("y", Ty::Primitive(Primitive::U32(None)), false),
+                        ],
+                    }),
+                    false,
+                ),
+                member(
+                    "direction",
+                    Ty::Enum(Enum {
+                        name: "Direction".to_string(),
+                        option: None,
+                        options: vec![
+                            EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Right".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                    false,
+                ),
+                member("moves", Ty::Array(vec![Ty::Primitive(Primitive::U8(None))]), false),
+            ],
+        })
+    }
+
+    #[test]
+    fn test_model_table_columns() {
+        let table = model_table("ns-Position", &position_schema()).unwrap();
+
+        assert_eq!(table.name, "ns-Position");
+        assert_eq!(
+            table.columns,
+            vec![
+                Column::new("internal_entity_id", SqlType::Text),
+                Column::new("player", SqlType::Text),
+                Column::new("vec.x", SqlType::Integer),
+                Column::new("vec.y", SqlType::Integer),
+                Column::new("direction", SqlType::Text),
+                Column::new("moves", SqlType::Text),
+                Column::new("internal_block_number", SqlType::Integer),
+                Column::new("internal_transaction_hash", SqlType::Text),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decoded_record_values() {
+        let ty = decode_event(
+            &position_schema(),
+            &[Felt::from(0x42)],
+            &[Felt::from(1), Felt::from(2), Felt::from(1), Felt::from(2), Felt::from(7), 8.into()],
+        )
+        .unwrap();
+
+        let mut values = vec![];
+        ty_values(&ty, &mut values);
+
+        assert_eq!(
+            values,
+            vec![
+                Some(format!("0x{:064x}", 0x42)),
+                Some("1".to_string()),
+                Some("2".to_string()),
+                Some("Right".to_string()),
+                Some("[7,8]".to_string()),
+            ]
+        );
+
+        // Members missing from an event emitted before an upgrade are not set.
+        let ty = decode_event(&position_schema(), &[Felt::from(0x42)], &[]).unwrap();
+
+        let mut values = vec![];
+        ty_values(&ty, &mut values);
+
+        assert_eq!(values, vec![Some(format!("0x{:064x}", 0x42)), None, None, None, None]);
+
+        // An event not matching its schema is exported with null members.
+        let ty = decode_event(&position_schema(), &[Felt::from(0x42)], &[Felt::MAX]).unwrap();
+
+        let mut values = vec![];
+        ty_values(&ty, &mut values);
+
+        assert_eq!(values, vec![None, None, None, None, None]);
+    }
+
+    fn emitted(block_number: u64) -> EmittedEvent {
+        EmittedEvent {
+            from_address: Felt::ZERO,
+            keys: vec![],
+            data: vec![],
+            block_hash: None,
+            block_number: Some(block_number),
+            transaction_hash: Felt::from(block_number),
+        }
+    }
+
+    fn upsert(key: Felt, columns: &[(&str, Option<&str>)], block_number: u64) -> RowChange {
+        let mut columns = columns
+            .iter()
+            .map(|(c, v)| (c.to_string(), v.map(str::to_string)))
+            .collect::<Vec<_>>();
+        columns.push(("internal_block_number".to_string(), Some(block_number.to_string())));
+        columns.push((
+            "internal_transaction_hash".to_string(),
+            Some(format!("{:#066x}", Felt::from(block_number))),
+        ));
+
+        RowChange::Upsert { key: format!("{:#066x}", key), columns }
+    }
+
+    #[test]
+    fn test_record_change() {
+        let selector = naming::compute_selector_from_tag("ns-Position");
+        let table = model_table("ns-Position", &position_schema()).unwrap();
+        let models =
+            HashMap::from([(selector, ExportedResource { schema: position_schema(), table })]);
+
+        let entity_id = Felt::from(0xe);
+        let change = |world_event: WorldEvent, block_number: u64| {
+            record_change(&models, &world_event, &emitted(block_number))
+                .unwrap()
+                .map(|(table, change)| (table.name.clone(), change))
+        };
+
+        let set_record = WorldEvent::StoreSetRecord(StoreSetRecord {
+            selector,
+            entity_id,
+            keys: vec![Felt::from(0x42)],
+            values: vec![Felt::ONE, Felt::TWO, Felt::ONE, Felt::TWO, Felt::from(7), 8.into()],
+        });
+        let player = format!("0x{:064x}", 0x42);
+        assert_eq!(
+            change(set_record, 1),
+            Some((
+                "ns-Position".to_string(),
+                upsert(
+                    entity_id,
+                    &[
+                        ("player", Some(player.as_str())),
+                        ("vec.x", Some("1")),
+                        ("vec.y", Some("2")),
+                        ("direction", Some("Right")),
+                        ("moves", Some("[7,8]")),
+                    ],
+                    1
+                )
+            ))
+        );
+
+        // The record is written by a contract of the model before `direction` and `moves` were
+        // added.
+        let update_record = WorldEvent::StoreUpdateRecord(StoreUpdateRecord {
+            selector,
+            entity_id,
+            values: vec![Felt::THREE, Felt::from(4)],
+        });
+        assert_eq!(
+            change(update_record, 2).unwrap().1,
+            upsert(
+                entity_id,
+                &[("vec.x", Some("3")), ("vec.y", Some("4")), ("direction", None), ("moves", None)],
+                2
+            )
+        );
+
+        let update_member = WorldEvent::StoreUpdateMember(StoreUpdateMember {
+            selector,
+            entity_id,
+            member_selector: get_selector_from_name("direction").unwrap(),
+            values: vec![Felt::ZERO],
+        });
+        assert_eq!(
+            change(update_member, 3).unwrap().1,
+            upsert(entity_id, &[("direction", Some("Left"))], 3)
+        );
+
+        let del_record = WorldEvent::StoreDelRecord(StoreDelRecord { selector, entity_id });
+        assert_eq!(
+            change(del_record, 4).unwrap().1,
+            RowChange::Delete { key: format!("{:#066x}", entity_id) }
+        );
+
+        let unknown = WorldEvent::StoreDelRecord(StoreDelRecord { selector: Felt::ONE, entity_id });
+        assert_eq!(change(unknown, 5), None);
+    }
+
+    fn sink_changes(table: &Table) -> Vec<(&Table, RowChange)> {
+        let columns = |x: &str| vec![("vec.x".to_string(), Some(x.to_string()))];
+
+        vec![
+            (table, RowChange::Upsert { key: "0x1".to_string(), columns: columns("1") }),
+            (table, RowChange::Upsert { key: "0x2".to_string(), columns: columns("2") }),
+            (table, RowChange::Upsert { key: "0x1".to_string(), columns: columns("3") }),
+            (table, RowChange::Delete { key: "0x2".to_string() }),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_sink() {
+        let conn = SqliteConnectOptions::new().in_memory(true).connect().await.unwrap();
+        let mut sink = SqliteSink::from_connection(conn).await.unwrap();
+        assert_eq!(sink.load_state().await.unwrap(), None);
+
+        let table = model_table("ns-Position", &position_schema()).unwrap();
+        let state = ExportState { last_block: Some(10) };
+        sink.write(&sink_changes(&table), &state).await.unwrap();
+
+        let rows = sqlx::query("SELECT internal_entity_id, \"vec.x\" FROM \"ns-Position\"")
+            .fetch_all(&mut sink.conn)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| (row.get::<String, _>(0), row.get::<Option<i64>, _>(1)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(rows, vec![("0x1".to_string(), Some(3))]);
+        assert_eq!(sink.load_state().await.unwrap(), Some(state));
+
+        // A failing change rolls back the whole write, along with the state.
+        let changes = [(&raw_events_table(), RowChange::Delete { key: "0x1".to_string() })];
+        let state = ExportState { last_block: Some(20) };
+        assert!(sink.write(&changes, &state).await.is_err());
+        assert_eq!(sink.load_state().await.unwrap(), Some(ExportState { last_block: Some(10) }));
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_sink() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let mut sink = NdjsonSink::open(dir.path()).unwrap();
+
+        let table = model_table("ns-Position", &position_schema()).unwrap();
+        let state = ExportState { last_block: Some(10) };
+        sink.write(&sink_changes(&table), &state).await.unwrap();
+        sink.write(&[], &ExportState { last_block: Some(11) }).await.unwrap();
+
+        let content = fs::read_to_string(dir.path().join("ns-Position.ndjson")).unwrap();
+        let rows = content
+            .lines()
+            .map(|line| serde_json::from_str::<JsonValue>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["internal_entity_id"], "0x1");
+        assert_eq!(rows[0]["vec.x"], 3);
+        assert_eq!(rows[0]["direction"], JsonValue::Null);
+        assert_eq!(sink.load_state().await.unwrap(), Some(ExportState { last_block: Some(11) }));
+    }
+}
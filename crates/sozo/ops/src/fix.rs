@@ -0,0 +1,99 @@
+//! Automatic fixes of the Cairo sources, currently the rewrite of the removed `get!`, `set!`,
+//! `delete!`, `emit!`, `spawn_test_world!` and `get_models_test_class_hashes!` macros, see
+//! [`dojo_lang::inline_macros::legacy`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use dojo_lang::inline_macros::legacy::{apply_rewrites, legacy_macro_rewrites_from_source};
+
+/// A Cairo file with legacy macros to rewrite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedFile {
+    pub path: PathBuf,
+    /// The number of rewritten macros.
+    pub rewrites: usize,
+}
+
+/// Rewrites the legacy macros of the Cairo files of the given directories.
+///
+/// When `dry_run` is set, the files are left untouched and only the files that would be
+/// rewritten are returned.
+pub fn fix_sources<P: AsRef<Path>>(dirs: &[P], dry_run: bool) -> Result<Vec<FixedFile>> {
+    let mut files = vec![];
+    for dir in dirs {
+        cairo_files(dir.as_ref(), &mut files)?;
+    }
+
+    // The directories may be nested, a file is only rewritten once.
+    files.sort();
+    files.dedup();
+
+    let mut fixed = vec![];
+
+    for path in files {
+        let content = fs::read_to_string(&path)?;
+        let rewrites = legacy_macro_rewrites_from_source(&content);
+
+        if rewrites.is_empty() {
+            continue;
+        }
+
+        if !dry_run {
+            fs::write(&path, apply_rewrites(&content, &rewrites))?;
+        }
+
+        fixed.push(FixedFile { path, rewrites: rewrites.len() });
+    }
+
+    Ok(fixed)
+}
+
+fn cairo_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            cairo_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "cairo") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix_sources() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let src = dir.path().join("systems");
+        fs::create_dir_all(&src).unwrap();
+
+        let legacy = src.join("actions.cairo");
+        fs::write(&legacy, "fn f(world: WorldStorage) {\n    set!(world, (position));\n}\n")
+            .unwrap();
+
+        let up_to_date = dir.path().join("lib.cairo");
+        fs::write(&up_to_date, "mod systems;\n").unwrap();
+
+        let fixed = fix_sources(&[dir.path()], true).unwrap();
+        assert_eq!(fixed, vec![FixedFile { path: legacy.clone(), rewrites: 1 }]);
+        assert!(fs::read_to_string(&legacy).unwrap().contains("set!"));
+
+        fix_sources(&[dir.path()], false).unwrap();
+        assert_eq!(
+            fs::read_to_string(&legacy).unwrap(),
+            "fn f(world: WorldStorage) {\n    world.write_model(@position);\n}\n"
+        );
+        assert!(fix_sources(&[dir.path()], true).unwrap().is_empty());
+    }
+}
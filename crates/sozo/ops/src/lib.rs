@@ -1,9 +1,17 @@
 // #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+pub mod auth_audit;
+pub mod dependencies;
+pub mod events;
+pub mod export;
+pub mod fix;
 pub mod migrate;
 pub mod migration_ui;
 pub mod model;
+pub mod model_blame;
+pub mod model_migration;
 pub mod resource_descriptor;
+pub mod scenario;
 
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,306 @@
+//! Fees paid by the transactions sent during a migration.
+//!
+//! Every transaction sent by the migration is recorded with the step it belongs to,
+//! and the actual fee and gas consumption are extracted from its receipt.
+
+use std::fmt;
+
+use dojo_utils::TransactionResult;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{Felt, PriceUnit, TransactionReceiptWithBlockInfo};
+use starknet::providers::{Provider, ProviderError};
+
+/// The number of decimals of STRK and ETH (FRI and WEI being the smallest units).
+const FEE_DECIMALS: u32 = 18;
+
+/// A step of the migration sending transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStep {
+    World,
+    Declare,
+    Resources,
+    Permissions,
+    Initialization,
+    ExternalContracts,
+    Metadata,
+    Seed,
+}
+
+impl fmt::Display for MigrationStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationStep::World => write!(f, "world"),
+            MigrationStep::Declare => write!(f, "declare"),
+            MigrationStep::Resources => write!(f, "resources"),
+            MigrationStep::Permissions => write!(f, "permissions"),
+            MigrationStep::Initialization => write!(f, "initialization"),
+            MigrationStep::ExternalContracts => write!(f, "external contracts"),
+            MigrationStep::Metadata => write!(f, "metadata"),
+            MigrationStep::Seed => write!(f, "seed"),
+        }
+    }
+}
+
+/// The fee paid by a single transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionFee {
+    pub step: MigrationStep,
+    pub transaction_hash: Felt,
+    /// The actual fee paid, in the smallest unit of `unit`.
+    pub actual_fee: Felt,
+    pub unit: PriceUnit,
+    pub l1_gas: u64,
+    pub l1_data_gas: u64,
+    pub l2_gas: u64,
+}
+
+impl TransactionFee {
+    /// Extracts the fee from the receipt of a transaction.
+    pub fn from_receipt(step: MigrationStep, receipt: &TransactionReceiptWithBlockInfo) -> Self {
+        let fee = receipt.receipt.actual_fee();
+        let resources = receipt.receipt.execution_resources();
+
+        Self {
+            step,
+            transaction_hash: *receipt.receipt.transaction_hash(),
+            actual_fee: fee.amount,
+            unit: fee.unit,
+            l1_gas: resources.l1_gas,
+            l1_data_gas: resources.l1_data_gas,
+            l2_gas: resources.l2_gas,
+        }
+    }
+}
+
+/// The gas and fees aggregated over several transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeTotals {
+    pub transactions: usize,
+    /// The fees paid in STRK, in FRI.
+    pub fee_fri: u128,
+    /// The fees paid in ETH, in WEI. Only used by legacy transactions.
+    pub fee_wei: u128,
+    pub l1_gas: u64,
+    pub l1_data_gas: u64,
+    pub l2_gas: u64,
+}
+
+impl FeeTotals {
+    fn add(&mut self, fee: &TransactionFee) {
+        let amount: u128 = fee.actual_fee.try_into().unwrap_or(u128::MAX);
+
+        match fee.unit {
+            PriceUnit::Fri => self.fee_fri = self.fee_fri.saturating_add(amount),
+            PriceUnit::Wei => self.fee_wei = self.fee_wei.saturating_add(amount),
+        }
+
+        self.transactions += 1;
+        self.l1_gas += fee.l1_gas;
+        self.l1_data_gas += fee.l1_data_gas;
+        self.l2_gas += fee.l2_gas;
+    }
+
+    /// Returns the fees paid in STRK, formatted with all the decimals.
+    pub fn fee_strk(&self) -> String {
+        format_fee(self.fee_fri)
+    }
+}
+
+/// The fee report of a migration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeReport {
+    pub transactions: Vec<TransactionFee>,
+}
+
+impl FeeReport {
+    /// Records the fee of a transaction sent during the given step.
+    ///
+    /// If the receipt is not part of the transaction result, it is fetched from the provider.
+    /// A transaction that was not sent is ignored.
+    pub async fn record<P>(
+        &mut self,
+        step: MigrationStep,
+        result: &TransactionResult,
+        provider: &P,
+    ) -> Result<(), ProviderError>
+    where
+        P: Provider,
+    {
+        let fee = match result {
+            TransactionResult::Noop => return Ok(()),
+            TransactionResult::HashReceipt(_, receipt) => {
+                TransactionFee::from_receipt(step, receipt)
+            }
+            TransactionResult::Hash(hash) => {
+                let receipt = provider.get_transaction_receipt(hash).await?;
+                TransactionFee::from_receipt(step, &receipt)
+            }
+        };
+
+        self.transactions.push(fee);
+        Ok(())
+    }
+
+    /// Records the fees of several transactions sent during the given step.
+    pub async fn record_all<P>(
+        &mut self,
+        step: MigrationStep,
+        results: &[TransactionResult],
+        provider: &P,
+    ) -> Result<(), ProviderError>
+    where
+        P: Provider,
+    {
+        for result in results {
+            self.record(step, result, provider).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Merges an other report into this one.
+    pub fn extend(&mut self, other: FeeReport) {
+        self.transactions.extend(other.transactions);
+    }
+
+    /// Returns the totals per step, in the order of the migration.
+    pub fn steps(&self) -> Vec<(MigrationStep, FeeTotals)> {
+        let mut steps: Vec<(MigrationStep, FeeTotals)> = vec![];
+
+        for fee in &self.transactions {
+            match steps.iter_mut().find(|(s, _)| *s == fee.step) {
+                Some((_, totals)) => totals.add(fee),
+                None => {
+                    let mut totals = FeeTotals::default();
+                    totals.add(fee);
+                    steps.push((fee.step, totals));
+                }
+            }
+        }
+
+        steps
+    }
+
+    /// Returns the totals over all the transactions.
+    pub fn totals(&self) -> FeeTotals {
+        let mut totals = FeeTotals::default();
+
+        for fee in &self.transactions {
+            totals.add(fee);
+        }
+
+        totals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
+
+/// The JSON representation of the report, including the computed totals.
+#[derive(Debug, Serialize)]
+pub struct FeeReportJson<'a> {
+    pub transactions: &'a [TransactionFee],
+    pub steps: Vec<FeeReportStepJson>,
+    pub totals: FeeReportTotalsJson,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeReportStepJson {
+    pub step: MigrationStep,
+    #[serde(flatten)]
+    pub totals: FeeReportTotalsJson,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeReportTotalsJson {
+    #[serde(flatten)]
+    pub totals: FeeTotals,
+    pub fee_strk: String,
+}
+
+impl From<FeeTotals> for FeeReportTotalsJson {
+    fn from(totals: FeeTotals) -> Self {
+        Self { fee_strk: totals.fee_strk(), totals }
+    }
+}
+
+impl<'a> From<&'a FeeReport> for FeeReportJson<'a> {
+    fn from(report: &'a FeeReport) -> Self {
+        Self {
+            transactions: &report.transactions,
+            steps: report
+                .steps()
+                .into_iter()
+                .map(|(step, totals)| FeeReportStepJson { step, totals: totals.into() })
+                .collect(),
+            totals: report.totals().into(),
+        }
+    }
+}
+
+/// Formats an amount of FRI (resp. WEI) into STRK (resp. ETH).
+pub fn format_fee(fri: u128) -> String {
+    let unit = 10u128.pow(FEE_DECIMALS);
+    let decimals = format!("{:0width$}", fri % unit, width = FEE_DECIMALS as usize);
+    let decimals = decimals.trim_end_matches('0');
+
+    if decimals.is_empty() {
+        format!("{}", fri / unit)
+    } else {
+        format!("{}.{}", fri / unit, decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee(step: MigrationStep, amount: u128, unit: PriceUnit) -> TransactionFee {
+        TransactionFee {
+            step,
+            transaction_hash: Felt::ONE,
+            actual_fee: Felt::from(amount),
+            unit,
+            l1_gas: 1,
+            l1_data_gas: 2,
+            l2_gas: 3,
+        }
+    }
+
+    #[test]
+    fn test_format_fee() {
+        assert_eq!(format_fee(0), "0");
+        assert_eq!(format_fee(1_000_000_000_000_000_000), "1");
+        assert_eq!(format_fee(1_500_000_000_000_000_000), "1.5");
+        assert_eq!(format_fee(1), "0.000000000000000001");
+    }
+
+    #[test]
+    fn test_totals_per_step() {
+        let report = FeeReport {
+            transactions: vec![
+                fee(MigrationStep::Declare, 10, PriceUnit::Fri),
+                fee(MigrationStep::Resources, 20, PriceUnit::Fri),
+                fee(MigrationStep::Declare, 30, PriceUnit::Fri),
+                fee(MigrationStep::Permissions, 5, PriceUnit::Wei),
+            ],
+        };
+
+        let steps = report.steps();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].0, MigrationStep::Declare);
+        assert_eq!(steps[0].1.fee_fri, 40);
+        assert_eq!(steps[0].1.transactions, 2);
+        assert_eq!(steps[0].1.l2_gas, 6);
+        assert_eq!(steps[2].1.fee_wei, 5);
+
+        let totals = report.totals();
+        assert_eq!(totals.transactions, 4);
+        assert_eq!(totals.fee_fri, 60);
+        assert_eq!(totals.fee_wei, 5);
+        assert_eq!(totals.l1_gas, 4);
+        assert_eq!(totals.l1_data_gas, 8);
+    }
+}
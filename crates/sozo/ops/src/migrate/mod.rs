@@ -18,16 +18,20 @@
 //! 4. All contracts that are not initialized are initialized, since permissions are applied,
 //!    initialization of contracts can mutate resources.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 use anyhow::anyhow;
 use cainome::cairo_serde::{ByteArray, ClassHash, ContractAddress};
 use dojo_utils::{Declarer, Deployer, Invoker, LabeledClass, TransactionResult, TxnConfig};
 use dojo_world::config::calldata_decoder::decode_calldata;
-use dojo_world::config::{metadata_config, ProfileConfig, ResourceConfig, WorldMetadata};
+use dojo_world::config::{
+    metadata_config, ProfileConfig, ResourceConfig, SeedAction, WorldMetadata,
+};
 use dojo_world::constants::WORLD;
-use dojo_world::contracts::abigen::world::ResourceMetadata;
-use dojo_world::contracts::WorldContract;
+use dojo_world::contracts::abigen::world::{ModelIndex, ResourceMetadata};
+use dojo_world::contracts::model::ModelReader;
+use dojo_world::contracts::{ContractInfo, WorldContract, WorldContractReader};
 use dojo_world::diff::{
     ExternalContractClassDiff, ExternalContractDiff, Manifest, ResourceDiff, WorldDiff, WorldStatus,
 };
@@ -36,8 +40,10 @@ use dojo_world::metadata::MetadataStorage;
 use dojo_world::remote::ResourceRemote;
 use dojo_world::services::UploadService;
 use dojo_world::{utils, ResourceType};
+use metrics::histogram;
 use starknet::accounts::{ConnectedAccount, SingleOwnerAccount};
 use starknet::core::types::Call;
+use starknet::core::utils::get_selector_from_name;
 use starknet::providers::{AnyProvider, Provider};
 use starknet::signers::LocalWallet;
 use starknet_crypto::Felt;
@@ -46,7 +52,12 @@ use tracing::trace;
 use crate::migration_ui::MigrationUi;
 
 pub mod error;
+pub mod fee_report;
 pub use error::MigrationError;
+pub use fee_report::{FeeReport, MigrationStep};
+
+/// The name of the histogram recording the duration of each stage of the migration.
+pub const MIGRATION_STAGE_DURATION: &str = "migration_stage_duration_seconds";
 
 #[derive(Debug)]
 pub struct Migration<A>
@@ -67,6 +78,8 @@ where
 pub struct MigrationResult {
     pub has_changes: bool,
     pub manifest: Manifest,
+    /// The fees paid by the transactions sent during the migration.
+    pub fee_report: FeeReport,
 }
 
 impl<A> Migration<A>
@@ -94,16 +107,33 @@ where
         &self,
         ui: &mut MigrationUi,
     ) -> Result<MigrationResult, MigrationError<A::SignError>> {
-        let world_has_changed = if !self.guest { self.ensure_world(ui).await? } else { false };
+        let mut fee_report = FeeReport::default();
+
+        let start = Instant::now();
+        let world_has_changed =
+            if !self.guest { self.ensure_world(ui, &mut fee_report).await? } else { false };
+        record_stage_duration(MigrationStep::World, start);
 
-        let resources_have_changed =
-            if !self.diff.is_synced() { self.sync_resources(ui).await? } else { false };
+        let start = Instant::now();
+        let resources_have_changed = if !self.diff.is_synced() {
+            self.sync_resources(ui, &mut fee_report).await?
+        } else {
+            false
+        };
+        record_stage_duration(MigrationStep::Resources, start);
 
-        let permissions_have_changed = self.sync_permissions(ui).await?;
+        let start = Instant::now();
+        let permissions_have_changed = self.sync_permissions(ui, &mut fee_report).await?;
+        record_stage_duration(MigrationStep::Permissions, start);
 
-        let contracts_have_changed = self.initialize_contracts(ui).await?;
+        let start = Instant::now();
+        let contracts_have_changed = self.initialize_contracts(ui, &mut fee_report).await?;
+        record_stage_duration(MigrationStep::Initialization, start);
 
-        let external_contracts_have_changed = self.sync_external_contracts(ui).await?;
+        let start = Instant::now();
+        let external_contracts_have_changed =
+            self.sync_external_contracts(ui, &mut fee_report).await?;
+        record_stage_duration(MigrationStep::ExternalContracts, start);
 
         Ok(MigrationResult {
             has_changes: world_has_changed
@@ -112,6 +142,7 @@ where
                 || external_contracts_have_changed
                 || contracts_have_changed,
             manifest: Manifest::new(&self.diff),
+            fee_report,
         })
     }
 
@@ -120,11 +151,13 @@ where
     /// # Arguments
     ///
     /// # Returns
+    ///
+    /// The fees paid to update the metadata onchain.
     pub async fn upload_metadata(
         &self,
         ui: &mut MigrationUi,
         service: &mut impl UploadService,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<FeeReport> {
         ui.update_text("Uploading metadata...");
 
         let mut invoker = Invoker::new(&self.world.account, self.txn_config);
@@ -169,18 +202,125 @@ where
             invoker.extend_calls(calls);
         }
 
-        if self.do_multicall() {
+        let results = if self.do_multicall() {
             ui.update_text_boxed(format!("Uploading {} metadata...", invoker.calls.len()));
-            invoker.multicall().await.map_err(|e| anyhow!(e.to_string()))?;
+            vec![invoker.multicall().await.map_err(|e| anyhow!(e.to_string()))?]
         } else {
             ui.update_text_boxed(format!(
                 "Uploading {} metadata (sequentially)...",
                 invoker.calls.len()
             ));
-            invoker.invoke_all_sequentially().await.map_err(|e| anyhow!(e.to_string()))?;
+            invoker.invoke_all_sequentially().await.map_err(|e| anyhow!(e.to_string()))?
+        };
+
+        let mut fee_report = FeeReport::default();
+        fee_report
+            .record_all(MigrationStep::Metadata, &results, self.world.account.provider())
+            .await?;
+
+        Ok(fee_report)
+    }
+
+    /// Applies the `[[seed]]` blocks of the [`ProfileConfig`], in order.
+    ///
+    /// A seed with a `model` and `keys` is skipped if the record already exists, or if a previous
+    /// seed with the same record is applied in the same run, which makes seeding idempotent.
+    ///
+    /// The records of models with `#[index]` members can't be written by a seed, since the
+    /// indexes are only maintained by the systems writing the records.
+    ///
+    /// # Returns
+    ///
+    /// The number of seeds applied and the fees paid to apply them.
+    pub async fn seed(&self, ui: &mut MigrationUi) -> anyhow::Result<(usize, FeeReport)> {
+        let mut fee_report = FeeReport::default();
+
+        let Some(seeds) = &self.profile_config.seed else {
+            return Ok((0, fee_report));
+        };
+
+        ui.update_text("Seeding the world...");
+
+        let world_reader =
+            WorldContractReader::new(self.world.address, self.world.account.provider());
+        let contracts: HashMap<String, ContractInfo> = (&self.diff).into();
+
+        let mut invoker = Invoker::new(&self.world.account, self.txn_config);
+        // The guards of the seeds applied in this run, whose records are not written yet.
+        let mut applied_guards = HashSet::new();
+
+        for (i, seed) in seeds.iter().enumerate() {
+            // Seeds are numbered from 1, like in the profile config validation.
+            let n = i + 1;
+
+            if let Some((model, keys)) = seed.guard() {
+                let model = world_reader.model_reader_with_tag(model).await?;
+                let keys = decode_calldata(keys)?;
+
+                if model.entity_storage(&keys).await?.iter().any(|v| *v != Felt::ZERO) {
+                    trace!(seed = n, "Seed already applied, skipping.");
+                    continue;
+                }
+
+                if !applied_guards.insert((model.selector(), keys)) {
+                    trace!(seed = n, "Seed already applied in this run, skipping.");
+                    continue;
+                }
+            }
+
+            let call = match seed.action()? {
+                SeedAction::Call { contract, entrypoint, calldata } => {
+                    let Some(contract) = contracts.get(contract) else {
+                        anyhow::bail!("The contract `{contract}` of seed #{n} is not found.");
+                    };
+
+                    Call {
+                        to: contract.address,
+                        selector: get_selector_from_name(entrypoint)?,
+                        calldata: decode_calldata(calldata)?,
+                    }
+                }
+                SeedAction::SetRecord { model: tag, keys, values } => {
+                    let model = world_reader.model_reader_with_tag(tag).await?;
+
+                    if !model.index_models().await?.is_empty() {
+                        anyhow::bail!(
+                            "The model `{tag}` of seed #{n} has indexes, which are not updated \
+                             when the record is written by a seed. Write the record with a system \
+                             call instead."
+                        );
+                    }
+
+                    self.world.set_entity_getcall(
+                        &model.selector(),
+                        &ModelIndex::Keys(decode_calldata(keys)?),
+                        &decode_calldata(values)?,
+                        &model.world_layout().await?,
+                    )
+                }
+            };
+
+            trace!(seed = n, ?call, "Applying seed.");
+            invoker.add_call(call);
         }
 
-        Ok(())
+        let applied = invoker.calls.len();
+
+        if applied == 0 {
+            return Ok((0, fee_report));
+        }
+
+        let results = if self.do_multicall() {
+            ui.update_text_boxed(format!("Applying {applied} seeds..."));
+            vec![invoker.multicall().await.map_err(|e| anyhow!(e.to_string()))?]
+        } else {
+            ui.update_text_boxed(format!("Applying {applied} seeds (sequentially)..."));
+            invoker.invoke_all_sequentially().await.map_err(|e| anyhow!(e.to_string()))?
+        };
+
+        fee_report.record_all(MigrationStep::Seed, &results, self.world.account.provider()).await?;
+
+        Ok((applied, fee_report))
     }
 
     async fn upload_metadata_from_resource_config(
@@ -231,6 +371,7 @@ where
     async fn initialize_contracts(
         &self,
         ui: &mut MigrationUi,
+        fee_report: &mut FeeReport,
     ) -> Result<bool, MigrationError<A::SignError>> {
         ui.update_text("Initializing contracts...");
 
@@ -313,18 +454,20 @@ where
         let has_changed = !invoker.calls.is_empty();
 
         if !invoker.calls.is_empty() {
-            if self.do_multicall() {
+            let results = if self.do_multicall() {
                 let ui_text = format!("Initializing {} contracts...", invoker.calls.len());
                 ui.update_text_boxed(ui_text);
 
-                invoker.multicall().await?;
+                vec![invoker.multicall().await?]
             } else {
                 let ui_text =
                     format!("Initializing {} contracts (sequentially)...", invoker.calls.len());
                 ui.update_text_boxed(ui_text);
 
-                invoker.invoke_all_sequentially().await?;
-            }
+                invoker.invoke_all_sequentially().await?
+            };
+
+            self.record_fees(fee_report, MigrationStep::Initialization, &results).await?;
         }
 
         Ok(has_changed)
@@ -346,6 +489,7 @@ where
     async fn sync_permissions(
         &self,
         ui: &mut MigrationUi,
+        fee_report: &mut FeeReport,
     ) -> Result<bool, MigrationError<A::SignError>> {
         ui.update_text("Syncing permissions...");
 
@@ -387,17 +531,19 @@ where
 
         let has_changed = !invoker.calls.is_empty();
 
-        if self.do_multicall() {
+        let results = if self.do_multicall() {
             let ui_text = format!("Syncing {} permissions...", invoker.calls.len());
             ui.update_text_boxed(ui_text);
 
-            invoker.multicall().await?;
+            vec![invoker.multicall().await?]
         } else {
             let ui_text = format!("Syncing {} permissions (sequentially)...", invoker.calls.len());
             ui.update_text_boxed(ui_text);
 
-            invoker.invoke_all_sequentially().await?;
-        }
+            invoker.invoke_all_sequentially().await?
+        };
+
+        self.record_fees(fee_report, MigrationStep::Permissions, &results).await?;
 
         Ok(has_changed)
     }
@@ -407,6 +553,7 @@ where
         &self,
         ui: &mut MigrationUi,
         classes: HashMap<Felt, LabeledClass>,
+        fee_report: &mut FeeReport,
     ) -> Result<(), MigrationError<A::SignError>> {
         // Declaration can be slow, and can be speed up by using multiple accounts.
         // Since migrator account from `self.world.account` is under the [`ConnectedAccount`] trait,
@@ -422,7 +569,8 @@ where
             let ui_text = format!("Declaring {} classes...", n_classes);
             ui.update_text_boxed(ui_text);
 
-            declarer.declare_all().await?;
+            let results = declarer.declare_all().await?;
+            self.record_fees(fee_report, MigrationStep::Declare, &results).await?;
         } else {
             trace!("Declaring classes with {} accounts.", accounts.len());
             let mut declarers = vec![];
@@ -443,17 +591,22 @@ where
                 futures::future::join_all(declarers.into_iter().map(|d| d.declare_all())).await;
 
             for declarer_results in declarers_futures {
-                if let Err(e) = declarer_results {
-                    // The issue is that `e` is bound to concrete type `SingleOwnerAccount`.
-                    // Thus, we can't return `e` directly.
-                    // Might have a better solution by addind a new variant?
-                    if e.to_string().contains("Class already declared") {
-                        // If the class is already declared, it might be because it was already
-                        // declared in a previous run or an other declarer.
-                        continue;
+                match declarer_results {
+                    Ok(results) => {
+                        self.record_fees(fee_report, MigrationStep::Declare, &results).await?;
+                    }
+                    Err(e) => {
+                        // The issue is that `e` is bound to concrete type `SingleOwnerAccount`.
+                        // Thus, we can't return `e` directly.
+                        // Might have a better solution by addind a new variant?
+                        if e.to_string().contains("Class already declared") {
+                            // If the class is already declared, it might be because it was
+                            // already declared in a previous run or an other declarer.
+                            continue;
+                        }
+
+                        return Err(MigrationError::DeclareClassError(e.to_string()));
                     }
-
-                    return Err(MigrationError::DeclareClassError(e.to_string()));
                 }
             }
         }
@@ -467,6 +620,7 @@ where
     async fn sync_resources(
         &self,
         ui: &mut MigrationUi,
+        fee_report: &mut FeeReport,
     ) -> Result<bool, MigrationError<A::SignError>> {
         ui.update_text("Syncing resources...");
 
@@ -536,19 +690,21 @@ where
         let has_calls = !invoker.calls.is_empty();
         let has_changed = has_classes || has_calls;
 
-        self.declare_classes(ui, classes).await?;
+        self.declare_classes(ui, classes, fee_report).await?;
 
-        if self.do_multicall() {
+        let results = if self.do_multicall() {
             let ui_text = format!("Registering {} resources...", n_resources);
             ui.update_text_boxed(ui_text);
 
-            invoker.multicall().await?;
+            vec![invoker.multicall().await?]
         } else {
             let ui_text = format!("Registering {} resources (sequentially)...", n_resources);
             ui.update_text_boxed(ui_text);
 
-            invoker.invoke_all_sequentially().await?;
-        }
+            invoker.invoke_all_sequentially().await?
+        };
+
+        self.record_fees(fee_report, MigrationStep::Resources, &results).await?;
 
         Ok(has_changed)
     }
@@ -560,6 +716,7 @@ where
     async fn sync_external_contracts(
         &self,
         ui: &mut MigrationUi,
+        fee_report: &mut FeeReport,
     ) -> Result<bool, MigrationError<A::SignError>> {
         let ui_text =
             format!("Syncing {} external contracts...", self.diff.external_contracts.len());
@@ -578,7 +735,7 @@ where
         let ui_text = format!("Declaring {} external contract classes...", classes.len());
         ui.update_text_boxed(ui_text);
 
-        self.declare_classes(ui, classes).await?;
+        self.declare_classes(ui, classes, fee_report).await?;
 
         // then deploying new external contracts
         let deployer = Deployer::new(&self.world.account, self.txn_config);
@@ -601,18 +758,20 @@ where
 
         let has_changed = !invoker.calls.is_empty();
 
-        if self.do_multicall() {
+        let results = if self.do_multicall() {
             let ui_text = format!("Deploying {} external contracts...", invoker.calls.len());
             ui.update_text_boxed(ui_text);
 
-            invoker.multicall().await?;
+            vec![invoker.multicall().await?]
         } else {
             let ui_text =
                 format!("Deploying {} external contracts (sequentially)...", invoker.calls.len());
             ui.update_text_boxed(ui_text);
 
-            invoker.invoke_all_sequentially().await?;
-        }
+            invoker.invoke_all_sequentially().await?
+        };
+
+        self.record_fees(fee_report, MigrationStep::ExternalContracts, &results).await?;
 
         Ok(has_changed)
     }
@@ -923,6 +1082,7 @@ where
     async fn ensure_world(
         &self,
         ui: &mut MigrationUi,
+        fee_report: &mut FeeReport,
     ) -> Result<bool, MigrationError<A::SignError>> {
         match &self.diff.world_info.status {
             WorldStatus::Synced => return Ok(false),
@@ -936,7 +1096,9 @@ where
                     class: self.diff.world_info.class.clone().flatten()?,
                 };
 
-                Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                let res =
+                    Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                self.record_fees(fee_report, MigrationStep::World, &[res]).await?;
 
                 // We want to wait for the receipt to be able to print the
                 // world block number.
@@ -955,6 +1117,9 @@ where
                     )
                     .await?;
 
+                self.record_fees(fee_report, MigrationStep::World, std::slice::from_ref(&res))
+                    .await?;
+
                 match res {
                     TransactionResult::HashReceipt(hash, receipt) => {
                         let block_msg = if let Some(n) = receipt.block.block_number() {
@@ -993,7 +1158,9 @@ where
                     class: self.diff.world_info.class.clone().flatten()?,
                 };
 
-                Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                let res =
+                    Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                self.record_fees(fee_report, MigrationStep::World, &[res]).await?;
 
                 let mut invoker = Invoker::new(&self.world.account, self.txn_config);
 
@@ -1001,13 +1168,27 @@ where
                     self.world.upgrade_getcall(&ClassHash(self.diff.world_info.class_hash)),
                 );
 
-                invoker.multicall().await?;
+                let res = invoker.multicall().await?;
+                self.record_fees(fee_report, MigrationStep::World, &[res]).await?;
             }
         };
 
         Ok(true)
     }
 
+    /// Records the fees paid by the given transactions into the fee report.
+    async fn record_fees(
+        &self,
+        fee_report: &mut FeeReport,
+        step: MigrationStep,
+        results: &[TransactionResult],
+    ) -> Result<(), MigrationError<A::SignError>> {
+        fee_report
+            .record_all(step, results, self.world.account.provider())
+            .await
+            .map_err(MigrationError::Provider)
+    }
+
     /// Returns the accounts to use for the migration.
     ///
     /// This is useful to use multiple accounts since the declare transaction is nonce-based,
@@ -1024,3 +1205,11 @@ where
             .unwrap_or_default()
     }
 }
+
+/// Records the duration of a migration stage, which is a no-op if no metrics recorder is
+/// installed.
+fn record_stage_duration(stage: MigrationStep, start: Instant) {
+    histogram!(MIGRATION_STAGE_DURATION, "stage" => stage.to_string())
+        .record(start.elapsed().as_secs_f64());
+}
+
@@ -4,6 +4,7 @@ use dojo_types::primitive::Primitive;
 use dojo_types::schema::{Enum, Member, Struct, Ty};
 use dojo_world::contracts::abigen::model::{FieldLayout, Layout};
 use dojo_world::contracts::model::ModelReader;
+use dojo_world::contracts::storage::{self, StorageReader, StorageSlot};
 use dojo_world::contracts::world::WorldContractReader;
 use num_traits::ToPrimitive;
 use starknet::core::types::{BlockId, BlockTag, Felt};
@@ -120,6 +121,51 @@ where
     Ok((format_deep_record(&schema, &keys, &values), schema, values))
 }
 
+/// Lists the storage slots occupied by a model record, and optionally their raw values.
+pub async fn model_storage_slots<P>(
+    tag: String,
+    keys: Vec<Felt>,
+    world_address: Felt,
+    provider: P,
+    block_id: BlockId,
+    with_values: bool,
+) -> Result<Vec<(StorageSlot, Option<Felt>)>>
+where
+    P: Provider + Send + Sync,
+{
+    if keys.is_empty() {
+        anyhow::bail!("Models always have at least one key. Please provide it (or them).");
+    }
+
+    let mut world_reader = WorldContractReader::new(world_address, &provider);
+    world_reader.set_block(block_id);
+
+    let model = world_reader.model_reader_with_tag(&tag).await?;
+    let schema = model.schema().await?;
+    let layout = model.world_layout().await?;
+
+    let storage = StorageReader::new(world_address, &provider).with_block(block_id);
+    let slots = storage.entity_slots(model.selector(), &keys, &layout, &schema).await?;
+
+    let values = if with_values {
+        let addresses = slots.iter().map(|s| s.address).collect();
+        storage.storage_at(addresses).await?.into_iter().map(Some).collect()
+    } else {
+        vec![None; slots.len()]
+    };
+
+    println!("{} entity id: {:#x}\n", tag, storage::entity_id(&keys));
+
+    for (slot, value) in slots.iter().zip(values.iter()) {
+        match value {
+            Some(value) => println!("{:#066x}  {:#066x}  {}", slot.address, value, slot.path),
+            None => println!("{:#066x}  {}", slot.address, slot.path),
+        }
+    }
+
+    Ok(slots.into_iter().zip(values).collect())
+}
+
 #[derive(Clone, Debug)]
 struct LayoutInfo {
     layout_type: LayoutInfoType,
@@ -202,6 +248,7 @@ fn get_name_from_schema(schema: &Ty) -> String {
             format!("({})", t.iter().map(get_name_from_schema).collect::<Vec<_>>().join(", "))
         }
         Ty::Array(a) => format!("Array<{}>", get_name_from_schema(&a[0])),
+        Ty::FixedArray(a, size) => format!("[{}; {size}]", get_name_from_schema(&a[0])),
         _ => "".to_string(),
     }
 }
@@ -214,6 +261,9 @@ fn get_printable_layout_list_from_struct(
     if let Ty::Struct(ss) = schema {
         let name = get_name_from_schema(schema);
 
+        // `#[packed]` members share a single field, named after all the members of the group.
+        let field_members = ss.field_members();
+
         // process main struct
         if !is_layout_in_list(layout_list, &name) {
             layout_list.push(LayoutInfo {
@@ -221,21 +271,24 @@ fn get_printable_layout_list_from_struct(
                 name,
                 fields: field_layouts
                     .iter()
-                    .zip(ss.children.iter().filter(|x| !x.key))
-                    .map(|(l, m)| FieldLayoutInfo {
-                        selector: format!("{:#x}", l.selector),
-                        name: m.name.clone(),
-                        layout: format_field_layout(&l.layout, &m.ty),
+                    .zip(field_members.iter())
+                    .filter_map(|(l, group)| {
+                        let names = group.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+                        Some(FieldLayoutInfo {
+                            selector: format!("{:#x}", l.selector),
+                            name: names.join(", "),
+                            layout: format_field_layout(&l.layout, &group.first()?.ty),
+                        })
                     })
                     .collect::<Vec<_>>(),
             });
         }
 
         // process members
-        for (member_layout, member) in
-            field_layouts.iter().zip(ss.children.iter().filter(|x| !x.key))
-        {
-            get_printable_layout_list(&member_layout.layout, &member.ty, layout_list);
+        for (member_layout, group) in field_layouts.iter().zip(field_members.iter()) {
+            if let [member] = group.as_slice() {
+                get_printable_layout_list(&member_layout.layout, &member.ty, layout_list);
+            }
         }
     };
 }
@@ -277,7 +330,14 @@ fn get_printable_layout_list_from_tuple(
     schema: &Ty,
     layout_list: &mut Vec<LayoutInfo>,
 ) {
-    if let Ty::Tuple(st) = schema {
+    // the layout of a fixed-size array is a tuple of N times the item layout.
+    let st = match schema {
+        Ty::Tuple(st) => Some(st.clone()),
+        Ty::FixedArray(items, size) => Some(vec![items[0].clone(); *size as usize]),
+        _ => None,
+    };
+
+    if let Some(st) = st {
         let name = get_name_from_schema(schema);
 
         // process tuple
@@ -443,6 +503,27 @@ fn format_array(item: &Ty, values: &mut Vec<Felt>, level: usize, start_indent: b
     )
 }
 
+fn format_fixed_array(
+    item: &Ty,
+    size: u32,
+    values: &mut Vec<Felt>,
+    level: usize,
+    start_indent: bool,
+) -> String {
+    let mut items = vec![];
+
+    for _ in 0..size {
+        items.push(format_record_value(item, values, level + 1, true));
+    }
+
+    format!(
+        "{}[\n{}\n{}]",
+        _start_indent(level, start_indent),
+        items.join(",\n"),
+        INDENT.repeat(level)
+    )
+}
+
 fn format_tuple(items: &[Ty], values: &mut Vec<Felt>, level: usize, start_indent: bool) -> String {
     if items.is_empty() {
         return "".to_string();
@@ -509,6 +590,7 @@ fn format_record_value(
         Ty::Enum(e) => format_enum(e, values, level, start_indent),
         Ty::Array(a) => format_array(&a[0], values, level, start_indent),
         Ty::Tuple(t) => format_tuple(t, values, level, start_indent),
+        Ty::FixedArray(a, size) => format_fixed_array(&a[0], *size, values, level, start_indent),
     }
 }
 
@@ -534,6 +616,7 @@ fn get_ty_repr(ty: &Ty) -> String {
             }
         }
         Ty::Array(items) => format!("Array<{}>", get_ty_repr(&items[0])),
+        Ty::FixedArray(items, size) => format!("[{}; {size}]", get_ty_repr(&items[0])),
         Ty::ByteArray(_) => "ByteArray".to_string(),
     }
 }
@@ -580,7 +663,7 @@ fn get_printable_ty_list(root_ty: &Ty, ty_list: &mut Vec<Ty>) {
                 }
             }
         }
-        Ty::Array(items_ty) => {
+        Ty::Array(items_ty) | Ty::FixedArray(items_ty, _) => {
             if !is_ty_already_in_list(ty_list, &items_ty[0]) {
                 get_printable_ty_list(&items_ty[0], ty_list)
             }
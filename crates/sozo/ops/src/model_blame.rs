@@ -0,0 +1,455 @@
+//! Lineage of a model record.
+//!
+//! The writes of a record are found by replaying the store events of its entity. Each write is
+//! attributed to its transaction, to the account which sent it and to the Dojo systems it
+//! called, decoded from the calldata of the transaction using the systems of the contracts.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use dojo_types::schema::{Member, Ty};
+use dojo_world::contracts::abigen::world::{self, Event as WorldEvent};
+use dojo_world::contracts::model::ModelReader;
+use dojo_world::contracts::{storage, ContractInfo, WorldContractReader};
+use serde_json::Value as JsonValue;
+use starknet::core::types::{BlockId, BlockTag, EmittedEvent, Felt, InvokeTransaction, Transaction};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::Provider;
+use tracing::trace;
+
+use crate::events::{
+    decode_member_values, decode_record_values, WorldEventFetcher, EVENTS_CHUNK_SIZE,
+};
+
+/// The kind of write of a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteKind {
+    Set,
+    Update,
+    UpdateMember,
+    Delete,
+}
+
+/// A write of a record, from a store event of the world.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordWrite {
+    pub block_number: Option<u64>,
+    pub transaction_hash: Felt,
+    pub kind: WriteKind,
+    /// The members written with their new value, `None` when the record is deleted.
+    pub members: Vec<(String, Option<String>)>,
+}
+
+/// The origin of a transaction writing a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionOrigin {
+    /// The account which sent the transaction, if it's an invoke transaction.
+    pub sender: Option<Felt>,
+    /// The systems called by the transaction, as `contract::system`.
+    pub calls: Vec<String>,
+}
+
+/// The lineage of a model record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordBlame {
+    /// The non-key members of the model.
+    pub members: Vec<String>,
+    /// The writes of the record, from the oldest to the latest.
+    pub writes: Vec<RecordWrite>,
+    /// The origin of the transactions writing the record, by transaction hash.
+    pub origins: HashMap<Felt, TransactionOrigin>,
+}
+
+impl RecordBlame {
+    /// Returns the last write of each member, with the value it has written.
+    pub fn last_writes(&self) -> Vec<(&str, Option<(&RecordWrite, &Option<String>)>)> {
+        self.members
+            .iter()
+            .map(|member| {
+                let last = self.writes.iter().rev().find_map(|write| {
+                    write.members.iter().find(|(m, _)| m == member).map(|(_, v)| (write, v))
+                });
+
+                (member.as_str(), last)
+            })
+            .collect()
+    }
+}
+
+/// Returns the lineage of the record of the model `tag` with the given keys.
+///
+/// The `contracts` are used to decode the systems called by the transactions.
+pub async fn model_blame<P>(
+    tag: &str,
+    keys: &[Felt],
+    world_address: Felt,
+    provider: P,
+    contracts: &HashMap<String, ContractInfo>,
+    from_block: u64,
+    max_block_range: u64,
+) -> Result<RecordBlame>
+where
+    P: Provider + Send + Sync,
+{
+    if keys.is_empty() {
+        bail!("Models always have at least one key. Please provide it (or them).");
+    }
+
+    let mut world_reader = WorldContractReader::new(world_address, &provider);
+    world_reader.set_block(BlockId::Tag(BlockTag::Pending));
+
+    let model = world_reader.model_reader_with_tag(tag).await?;
+    let schema = model.schema().await?;
+
+    let event_keys = vec![
+        vec![
+            world::StoreSetRecord::event_selector(),
+            world::StoreUpdateRecord::event_selector(),
+            world::StoreUpdateMember::event_selector(),
+            world::StoreDelRecord::event_selector(),
+        ],
+        vec![model.selector()],
+        vec![storage::entity_id(keys)],
+    ];
+
+    let fetcher =
+        WorldEventFetcher::new(&provider, world_address, EVENTS_CHUNK_SIZE, max_block_range)
+            .await?;
+    let to_block = provider.block_number().await?;
+    let events = fetcher.fetch_world_events(Some(event_keys), from_block, to_block).await?;
+
+    let writes = record_writes(&schema, &events)?;
+
+    let mut origins = HashMap::new();
+    for write in &writes {
+        if origins.contains_key(&write.transaction_hash) {
+            continue;
+        }
+
+        let transaction = provider.get_transaction_by_hash(write.transaction_hash).await?;
+        origins.insert(write.transaction_hash, transaction_origin(&transaction, contracts));
+    }
+
+    trace!(tag, writes_count = writes.len(), "Fetched record writes.");
+
+    Ok(RecordBlame { members: non_key_members(&schema)?, writes, origins })
+}
+
+/// Returns the writes of a record from its store events.
+///
+/// The `#[packed]` members of a group are written together by a member update, and the members
+/// added by an upgrade of the model are not written by the records written before it.
+pub fn record_writes(
+    schema: &Ty,
+    events: &[(WorldEvent, EmittedEvent)],
+) -> Result<Vec<RecordWrite>> {
+    let Ty::Struct(s) = schema else {
+        bail!("The schema of `{}` is expected to be a struct.", schema.name());
+    };
+
+    let mut writes = vec![];
+
+    for (world_event, event) in events {
+        let (kind, members) = match world_event {
+            WorldEvent::StoreSetRecord(e) => {
+                (WriteKind::Set, written_members(decode_record_values(s, &e.values)?))
+            }
+            WorldEvent::StoreUpdateRecord(e) => {
+                (WriteKind::Update, written_members(decode_record_values(s, &e.values)?))
+            }
+            WorldEvent::StoreUpdateMember(e) => (
+                WriteKind::UpdateMember,
+                written_members(decode_member_values(s, e.member_selector, &e.values)?),
+            ),
+            WorldEvent::StoreDelRecord(_) => (
+                WriteKind::Delete,
+                s.children.iter().filter(|m| !m.key).map(|m| (m.name.clone(), None)).collect(),
+            ),
+            _ => continue,
+        };
+
+        writes.push(RecordWrite {
+            block_number: event.block_number,
+            transaction_hash: event.transaction_hash,
+            kind,
+            members,
+        });
+    }
+
+    Ok(writes)
+}
+
+/// Returns the names of the non-key members of a model schema.
+fn non_key_members(schema: &Ty) -> Result<Vec<String>> {
+    let Ty::Struct(s) = schema else {
+        bail!("The schema of `{}` is expected to be a struct.", schema.name());
+    };
+
+    Ok(s.children.iter().filter(|m| !m.key).map(|m| m.name.clone()).collect())
+}
+
+/// Returns the decoded members written with their displayed value.
+fn written_members(decoded: Vec<(&Member, Ty)>) -> Vec<(String, Option<String>)> {
+    decoded.into_iter().map(|(m, ty)| (m.name.clone(), Some(member_value(&ty)))).collect()
+}
+
+/// Returns the displayed value of a member.
+fn member_value(ty: &Ty) -> String {
+    match ty.to_json_value() {
+        Ok(JsonValue::String(s)) => s,
+        Ok(value) => value.to_string(),
+        Err(_) => "?".to_string(),
+    }
+}
+
+/// Returns the sender and the systems called by a transaction.
+fn transaction_origin(
+    transaction: &Transaction,
+    contracts: &HashMap<String, ContractInfo>,
+) -> TransactionOrigin {
+    let (sender, calls) = match transaction {
+        Transaction::Invoke(InvokeTransaction::V0(tx)) => {
+            (None, vec![(tx.contract_address, tx.entry_point_selector)])
+        }
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => {
+            (Some(tx.sender_address), decode_calls(&tx.calldata))
+        }
+        Transaction::Invoke(InvokeTransaction::V3(tx)) => {
+            (Some(tx.sender_address), decode_calls(&tx.calldata))
+        }
+        _ => (None, vec![]),
+    };
+
+    let calls = calls.into_iter().map(|(to, selector)| call_label(contracts, to, selector));
+
+    TransactionOrigin { sender, calls: calls.collect() }
+}
+
+/// Decodes the contract addresses and the selectors of the calls of an account `__execute__`
+/// calldata, which is a serialized `Array<Call>`.
+///
+/// Returns no call if the calldata doesn't match this format.
+pub fn decode_calls(calldata: &[Felt]) -> Vec<(Felt, Felt)> {
+    let mut calls = vec![];
+
+    let Some((count, mut rest)) = calldata.split_first() else {
+        return calls;
+    };
+
+    let Ok(count) = u64::try_from(*count) else {
+        return calls;
+    };
+
+    for _ in 0..count {
+        let [to, selector, len, tail @ ..] = rest else {
+            return vec![];
+        };
+
+        let Some(len) = usize::try_from(*len).ok().filter(|len| *len <= tail.len()) else {
+            return vec![];
+        };
+
+        calls.push((*to, *selector));
+        rest = &tail[len..];
+    }
+
+    calls
+}
+
+/// Returns the label of a call, `contract::system` when the contract and the system are known.
+pub fn call_label(contracts: &HashMap<String, ContractInfo>, to: Felt, selector: Felt) -> String {
+    let Some(contract) = contracts.values().find(|c| c.address == to) else {
+        return format!("{:#066x}::{:#x}", to, selector);
+    };
+
+    let system = contract
+        .entrypoints
+        .iter()
+        .find(|e| get_selector_from_name(e).is_ok_and(|s| s == selector))
+        .cloned()
+        .unwrap_or_else(|| format!("{:#x}", selector));
+
+    format!("{}::{}", contract.tag_or_name, system)
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::Struct;
+    use dojo_world::contracts::abigen::world::{StoreDelRecord, StoreSetRecord, StoreUpdateMember};
+
+    use super::*;
+
+    fn position_schema() -> Ty {
+        let member = |name: &str, key: bool| Member {
+            name: name.to_string(),
+            ty: Ty::Primitive(Primitive::U32(None)),
+            key,
+            packed: false,
+        };
+
+        Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![member("player", true), member("x", false), member("y", false)],
+        })
+    }
+
+    fn emitted(block_number: u64, transaction_hash: u64) -> EmittedEvent {
+        EmittedEvent {
+            from_address: Felt::ZERO,
+            keys: vec![],
+            data: vec![],
+            block_hash: None,
+            block_number: Some(block_number),
+            transaction_hash: Felt::from(transaction_hash),
+        }
+    }
+
+    #[test]
+    fn test_record_writes() {
+        let events = vec![
+            (
+                WorldEvent::StoreSetRecord(StoreSetRecord {
+                    selector: Felt::ONE,
+                    entity_id: Felt::TWO,
+                    keys: vec![Felt::ONE],
+                    values: vec![Felt::from(10), Felt::from(20)],
+                }),
+                emitted(1, 0xa),
+            ),
+            (
+                WorldEvent::StoreUpdateMember(StoreUpdateMember {
+                    selector: Felt::ONE,
+                    entity_id: Felt::TWO,
+                    member_selector: get_selector_from_name("y").unwrap(),
+                    values: vec![Felt::from(0)],
+                }),
+                emitted(2, 0xb),
+            ),
+            (
+                WorldEvent::StoreDelRecord(StoreDelRecord {
+                    selector: Felt::ONE,
+                    entity_id: Felt::TWO,
+                }),
+                emitted(3, 0xc),
+            ),
+        ];
+
+        let writes = record_writes(&position_schema(), &events).unwrap();
+
+        assert_eq!(
+            writes.iter().map(|w| (w.kind, w.members.clone())).collect::<Vec<_>>(),
+            vec![
+                (
+                    WriteKind::Set,
+                    vec![
+                        ("x".to_string(), Some("10".to_string())),
+                        ("y".to_string(), Some("20".to_string())),
+                    ]
+                ),
+                (WriteKind::UpdateMember, vec![("y".to_string(), Some("0".to_string()))]),
+                (WriteKind::Delete, vec![("x".to_string(), None), ("y".to_string(), None)]),
+            ]
+        );
+
+        let blame = RecordBlame {
+            members: vec!["x".to_string(), "y".to_string()],
+            writes: writes[..2].to_vec(),
+            origins: HashMap::new(),
+        };
+
+        let last_writes = blame
+            .last_writes()
+            .into_iter()
+            .map(|(member, last)| (member, last.map(|(w, _)| w.transaction_hash)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(last_writes, vec![("x", Some(Felt::from(0xa))), ("y", Some(Felt::from(0xb)))]);
+    }
+
+    #[test]
+    fn test_record_writes_packed_and_upgraded() {
+        let member = |name: &str, key: bool, packed: bool| Member {
+            name: name.to_string(),
+            ty: Ty::Primitive(Primitive::U8(None)),
+            key,
+            packed,
+        };
+        let schema = Ty::Struct(Struct {
+            name: "Stats".to_string(),
+            children: vec![
+                member("player", true, false),
+                member("hp", false, true),
+                member("mana", false, true),
+                member("rank", false, false),
+            ],
+        });
+
+        let events = vec![
+            // Written before `rank` was added to the model.
+            (
+                WorldEvent::StoreSetRecord(StoreSetRecord {
+                    selector: Felt::ONE,
+                    entity_id: Felt::TWO,
+                    keys: vec![Felt::ONE],
+                    values: vec![Felt::from(10), Felt::from(20)],
+                }),
+                emitted(1, 0xa),
+            ),
+            (
+                WorldEvent::StoreUpdateMember(StoreUpdateMember {
+                    selector: Felt::ONE,
+                    entity_id: Felt::TWO,
+                    member_selector: get_selector_from_name("hp").unwrap(),
+                    values: vec![Felt::from(5), Felt::from(6)],
+                }),
+                emitted(2, 0xb),
+            ),
+        ];
+
+        let writes = record_writes(&schema, &events).unwrap();
+
+        assert_eq!(
+            writes.iter().map(|w| w.members.clone()).collect::<Vec<_>>(),
+            vec![
+                vec![
+                    ("hp".to_string(), Some("10".to_string())),
+                    ("mana".to_string(), Some("20".to_string())),
+                ],
+                vec![
+                    ("hp".to_string(), Some("5".to_string())),
+                    ("mana".to_string(), Some("6".to_string())),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_calls() {
+        let calldata = [2_u64, 0x10, 0x20, 2, 7, 8, 0x11, 0x21, 0]
+            .into_iter()
+            .map(Felt::from)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            decode_calls(&calldata),
+            vec![(Felt::from(0x10), Felt::from(0x20)), (Felt::from(0x11), Felt::from(0x21))]
+        );
+
+        // The length of the call exceeds the calldata.
+        assert!(decode_calls(&[Felt::ONE, Felt::ONE, Felt::ONE, Felt::THREE]).is_empty());
+
+        let contracts = HashMap::from([(
+            "ns-actions".to_string(),
+            ContractInfo {
+                tag_or_name: "ns-actions".to_string(),
+                address: Felt::from(0x10),
+                entrypoints: vec!["spawn".to_string()],
+            },
+        )]);
+
+        let spawn = get_selector_from_name("spawn").unwrap();
+        assert_eq!(call_label(&contracts, Felt::from(0x10), spawn), "ns-actions::spawn");
+        assert_eq!(call_label(&contracts, Felt::from(0x10), Felt::ONE), "ns-actions::0x1");
+    }
+}
@@ -0,0 +1,497 @@
+//! Migrates the records of a versioned model after its upgrade.
+//!
+//! A model declared with `#[dojo::model(version: N)]` exposes a `migrate_values` entrypoint,
+//! which transforms the values written by a previous version of the model into the values of
+//! the current version, using the `ModelMigration` implementation of the model author.
+//!
+//! The records to migrate are the records written before the last upgrade of the model, which
+//! are found by replaying the world events of the model. Their values are read with the layout of
+//! the model contract they were written with, transformed by the current model contract from the
+//! version of this contract, and written back in batched transactions.
+//!
+//! The progress is saved after each batch, to resume an interrupted migration.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use cainome::cairo_serde::CairoSerde;
+use dojo_utils::{execution_status_from_receipt, Invoker, TransactionResult, TxnConfig};
+use dojo_world::contracts::abigen::world::{self, Event as WorldEvent, Layout, ModelIndex};
+use dojo_world::contracts::model::{is_entrypoint_not_found, ModelContractReader, ModelReader};
+use dojo_world::contracts::{WorldContract, WorldContractReader};
+use serde::{Deserialize, Serialize};
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::{BlockId, BlockTag, ExecutionResult, Felt, FunctionCall};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::Provider;
+use tracing::trace;
+
+use crate::events::{WorldEventFetcher, EVENTS_CHUNK_SIZE};
+
+/// The version of the models declared before the introduction of model versions.
+const UNVERSIONED_MODEL_VERSION: u32 = 1;
+
+/// The progress of a model migration, saved after each batch of migrated records.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MigrationProgress {
+    /// The tag of the migrated model.
+    pub tag: String,
+    /// The address of the model contract the records are migrated to.
+    pub model_address: Felt,
+    /// The version of the model the records are migrated to.
+    pub to_version: u32,
+    /// The id of the last migrated entity, records being migrated by increasing entity id.
+    pub last_entity_id: Option<Felt>,
+    /// The number of records already migrated.
+    pub migrated: usize,
+}
+
+/// A record of a model to migrate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordToMigrate {
+    /// The keys of the record.
+    pub keys: Vec<Felt>,
+    /// The address of the model contract the values of the record have been written with.
+    pub model_address: Felt,
+}
+
+/// The records of a model written before its last upgrade.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordsToMigrate {
+    /// Whether the model has been upgraded to the current model contract.
+    pub upgraded: bool,
+    /// The records to migrate, by entity id.
+    pub records: BTreeMap<Felt, RecordToMigrate>,
+}
+
+/// Replays the world events of a model to find the records written before the model has been
+/// upgraded to the contract at `model_address`.
+///
+/// Each record is tracked with the model contract it has been written with, since the model
+/// may have been upgraded several times without migrating the records in between. The records
+/// deleted, or fully written again after the upgrade, are not migrated.
+pub fn records_to_migrate(
+    events: &[WorldEvent],
+    model_selector: Felt,
+    model_address: Felt,
+) -> RecordsToMigrate {
+    let mut result = RecordsToMigrate::default();
+
+    // The model contract active when the event is emitted, unknown until the first upgrade
+    // since the registration of the model is not keyed by its selector.
+    let mut current_address = None;
+    // The records written before the first upgrade, with the contract it upgraded from.
+    let mut first_records = vec![];
+
+    for event in events {
+        match event {
+            WorldEvent::ModelUpgraded(e) if e.selector == model_selector => {
+                if current_address.is_none() {
+                    for entity_id in first_records.drain(..) {
+                        if let Some(record) = result.records.get_mut(&entity_id) {
+                            record.model_address = e.prev_address.0;
+                        }
+                    }
+                }
+
+                current_address = Some(e.address.0);
+                result.upgraded |= e.address.0 == model_address;
+            }
+            WorldEvent::StoreSetRecord(e) if e.selector == model_selector => {
+                if current_address == Some(model_address) {
+                    result.records.remove(&e.entity_id);
+                    continue;
+                }
+
+                let record = RecordToMigrate {
+                    keys: e.keys.clone(),
+                    model_address: current_address.unwrap_or_default(),
+                };
+                result.records.insert(e.entity_id, record);
+
+                if current_address.is_none() {
+                    first_records.push(e.entity_id);
+                }
+            }
+            WorldEvent::StoreUpdateRecord(e) if e.selector == model_selector => {
+                if current_address == Some(model_address) {
+                    result.records.remove(&e.entity_id);
+                } else if let (Some(address), Some(record)) =
+                    (current_address, result.records.get_mut(&e.entity_id))
+                {
+                    // All the values are written again with the current contract.
+                    record.model_address = address;
+                }
+            }
+            WorldEvent::StoreDelRecord(e) if e.selector == model_selector => {
+                result.records.remove(&e.entity_id);
+            }
+            _ => {}
+        }
+    }
+
+    if !result.upgraded {
+        result.records.clear();
+    }
+
+    result
+}
+
+/// Migrates the records of the model `tag` written before its last upgrade, by batches of
+/// `batch_size` records. The models with `#[index]` members are refused.
+///
+/// The progress is saved into `progress_path` once the transaction of each batch has succeeded,
+/// and the migration is resumed from it if it matches the current model. The file is removed
+/// once all the records are migrated.
+///
+/// Returns the number of migrated records.
+#[allow(clippy::too_many_arguments)]
+pub async fn migrate_model_records<A>(
+    tag: &str,
+    world: &WorldContract<A>,
+    txn_config: TxnConfig,
+    from_block: u64,
+    max_block_range: u64,
+    batch_size: usize,
+    progress_path: &Path,
+) -> Result<usize>
+where
+    A: ConnectedAccount + Sync + Send,
+{
+    let provider = world.account.provider();
+    // A batch is only saved as migrated once its transaction has succeeded.
+    let txn_config = TxnConfig { wait: true, receipt: true, ..txn_config };
+
+    let world_reader = WorldContractReader::new(world.address, provider);
+    let model = world_reader.model_reader_with_tag(tag).await?;
+
+    // The records are written back by the world, which doesn't maintain the index models.
+    let index_models = model.index_models().await?;
+    if !index_models.is_empty() {
+        bail!(
+            "The records of {tag} can't be migrated, since its indexes ({}) would not be updated.",
+            index_models.join(", ")
+        );
+    }
+
+    let model_address = model.contract_address();
+    let layout = model.world_layout().await?;
+
+    let to_version = model_version(provider, model_address).await?;
+
+    let events =
+        fetch_model_events(provider, world.address, model.selector(), from_block, max_block_range)
+            .await?;
+    let to_migrate = records_to_migrate(&events, model.selector(), model_address);
+
+    if !to_migrate.upgraded {
+        println!("The model {tag} has not been upgraded, there is nothing to migrate.");
+        return Ok(0);
+    }
+
+    // The version and the layout of each previous model contract the records were written with.
+    let mut prev_models = HashMap::new();
+    for record in to_migrate.records.values() {
+        if let Entry::Vacant(entry) = prev_models.entry(record.model_address) {
+            let version = model_version(provider, record.model_address).await?;
+            let raw_layout = ModelContractReader::new(record.model_address, provider)
+                .layout()
+                .raw_call()
+                .await?;
+            entry.insert((version, Layout::cairo_deserialize(&raw_layout, 0)?));
+        }
+    }
+
+    let mut progress = match load_progress(progress_path)? {
+        Some(p) if p.tag == tag && p.model_address == model_address => {
+            println!("Resuming the migration of {tag} after {} migrated records.", p.migrated);
+            p
+        }
+        _ => MigrationProgress {
+            tag: tag.to_string(),
+            model_address,
+            to_version,
+            last_entity_id: None,
+            migrated: 0,
+        },
+    };
+
+    // The records written by a contract of the same version have nothing to migrate.
+    let records = to_migrate
+        .records
+        .into_iter()
+        .filter(|(id, _)| progress.last_entity_id.is_none_or(|last| *id > last))
+        .filter(|(_, record)| prev_models[&record.model_address].0 < to_version)
+        .collect::<Vec<_>>();
+
+    if records.is_empty() && progress.migrated == 0 {
+        println!(
+            "The records of {tag} are all at the version {to_version}, there is nothing to \
+             migrate."
+        );
+        return Ok(0);
+    }
+
+    let total = progress.migrated + records.len();
+    let from_versions =
+        records.iter().map(|(_, r)| prev_models[&r.model_address].0).collect::<BTreeSet<_>>();
+
+    println!(
+        "Migrating {} records of {tag} from version(s) {} to version {to_version}.",
+        records.len(),
+        from_versions.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+    );
+
+    for batch in records.chunks(batch_size.max(1)) {
+        let mut invoker = Invoker::new(&world.account, txn_config);
+
+        for (entity_id, record) in batch {
+            // The records are read with the layout of the model version they were written with.
+            let (from_version, prev_layout) = &prev_models[&record.model_address];
+
+            let index = ModelIndex::Keys(record.keys.clone());
+            let values = world.entity(&model.selector(), &index, prev_layout).call().await?;
+
+            let values =
+                migrate_values(provider, model_address, *from_version, &record.keys, &values)
+                    .await
+                    .with_context(|| format!("Failed to migrate the record {entity_id:#x}."))?;
+
+            invoker.add_call(world.set_entity_getcall(&model.selector(), &index, &values, &layout));
+        }
+
+        let res = invoker.multicall().await?;
+        trace!(%res, "Migrated batch of records.");
+
+        if let TransactionResult::HashReceipt(hash, receipt) = &res {
+            if let ExecutionResult::Reverted { reason } =
+                execution_status_from_receipt(&receipt.receipt)
+            {
+                bail!("The transaction {hash:#x} migrating a batch of records reverted: {reason}");
+            }
+        }
+
+        progress.last_entity_id = batch.last().map(|(id, _)| *id);
+        progress.migrated += batch.len();
+        save_progress(progress_path, &progress)?;
+
+        println!("[{}/{}] records migrated.", progress.migrated, total);
+    }
+
+    if progress_path.exists() {
+        fs::remove_file(progress_path)?;
+    }
+
+    Ok(progress.migrated)
+}
+
+/// Returns the version of the model deployed at `model_address`.
+///
+/// Models compiled before the introduction of model versions don't expose their version, and
+/// are considered at version 1.
+async fn model_version<P>(provider: P, model_address: Felt) -> Result<u32>
+where
+    P: Provider + Sync + Send,
+{
+    let call = FunctionCall {
+        contract_address: model_address,
+        entry_point_selector: get_selector_from_name("version")?,
+        calldata: vec![],
+    };
+
+    match provider.call(call, BlockId::Tag(BlockTag::Pending)).await {
+        Ok(res) => Ok(u32::cairo_deserialize(&res, 0)?),
+        Err(e) if is_entrypoint_not_found(&e) => {
+            trace!(?e, %model_address, "Model without version.");
+            Ok(UNVERSIONED_MODEL_VERSION)
+        }
+        Err(e) => Err(e).with_context(|| {
+            format!("Failed to read the version of the model {model_address:#x}.")
+        }),
+    }
+}
+
+/// Transforms the values of a record written by the version `from_version` of the model, with
+/// the model contract at `model_address`.
+async fn migrate_values<P>(
+    provider: P,
+    model_address: Felt,
+    from_version: u32,
+    keys: &[Felt],
+    values: &[Felt],
+) -> Result<Vec<Felt>>
+where
+    P: Provider + Sync + Send,
+{
+    let mut calldata = u32::cairo_serialize(&from_version);
+    calldata.extend(Vec::<Felt>::cairo_serialize(&keys.to_vec()));
+    calldata.extend(Vec::<Felt>::cairo_serialize(&values.to_vec()));
+
+    let call = FunctionCall {
+        contract_address: model_address,
+        entry_point_selector: get_selector_from_name("migrate_values")?,
+        calldata,
+    };
+
+    let res = provider.call(call, BlockId::Tag(BlockTag::Pending)).await?;
+
+    Ok(Vec::<Felt>::cairo_deserialize(&res, 0)?)
+}
+
+/// Fetches the world events related to the records and the upgrades of a model.
+async fn fetch_model_events<P>(
+    provider: P,
+    world_address: Felt,
+    model_selector: Felt,
+    from_block: u64,
+    max_block_range: u64,
+) -> Result<Vec<WorldEvent>>
+where
+    P: Provider + Sync + Send,
+{
+    let keys = vec![
+        vec![
+            world::ModelUpgraded::event_selector(),
+            world::StoreSetRecord::event_selector(),
+            world::StoreUpdateRecord::event_selector(),
+            world::StoreDelRecord::event_selector(),
+        ],
+        vec![model_selector],
+    ];
+
+    let fetcher =
+        WorldEventFetcher::new(&provider, world_address, EVENTS_CHUNK_SIZE, max_block_range)
+            .await?;
+    let to_block = provider.block_number().await?;
+
+    let events = fetcher
+        .fetch_world_events(Some(keys), from_block, to_block)
+        .await?
+        .into_iter()
+        .map(|(event, _)| event)
+        .collect::<Vec<_>>();
+
+    trace!(
+        events_count = events.len(),
+        model_selector = format!("{:#066x}", model_selector),
+        "Fetched model events."
+    );
+
+    Ok(events)
+}
+
+fn load_progress(path: &Path) -> Result<Option<MigrationProgress>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+fn save_progress(path: &Path, progress: &MigrationProgress) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_json::to_string_pretty(progress)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cainome::cairo_serde::{ClassHash, ContractAddress};
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn set_record(selector: Felt, entity_id: Felt, keys: Vec<Felt>) -> WorldEvent {
+        WorldEvent::StoreSetRecord(world::StoreSetRecord {
+            selector,
+            entity_id,
+            keys,
+            values: vec![],
+        })
+    }
+
+    fn upgraded(selector: Felt, prev_address: Felt, address: Felt) -> WorldEvent {
+        WorldEvent::ModelUpgraded(world::ModelUpgraded {
+            selector,
+            class_hash: ClassHash(Felt::ZERO),
+            address: ContractAddress(address),
+            prev_address: ContractAddress(prev_address),
+        })
+    }
+
+    fn record(keys: Vec<Felt>, model_address: Felt) -> RecordToMigrate {
+        RecordToMigrate { keys, model_address }
+    }
+
+    #[test]
+    fn test_records_to_migrate() {
+        let model = felt!("0xaa");
+        let other = felt!("0xbb");
+
+        let events = vec![
+            set_record(model, Felt::ONE, vec![felt!("0x10")]),
+            set_record(model, Felt::TWO, vec![felt!("0x20")]),
+            set_record(model, Felt::THREE, vec![felt!("0x30")]),
+            set_record(other, felt!("0x4"), vec![felt!("0x40")]),
+            WorldEvent::StoreDelRecord(world::StoreDelRecord {
+                selector: model,
+                entity_id: Felt::TWO,
+            }),
+            upgraded(model, felt!("0x100"), felt!("0x200")),
+            // Written with the new version, no need to migrate it.
+            set_record(model, Felt::THREE, vec![felt!("0x30")]),
+            set_record(model, felt!("0x5"), vec![felt!("0x50")]),
+        ];
+
+        let result = records_to_migrate(&events, model, felt!("0x200"));
+
+        assert!(result.upgraded);
+        assert_eq!(
+            result.records,
+            BTreeMap::from([(Felt::ONE, record(vec![felt!("0x10")], felt!("0x100")))])
+        );
+
+        // The model has not been upgraded to this contract.
+        let result = records_to_migrate(&events, model, felt!("0x300"));
+        assert!(!result.upgraded);
+        assert!(result.records.is_empty());
+    }
+
+    #[test]
+    fn test_records_to_migrate_after_several_upgrades() {
+        let model = felt!("0xaa");
+
+        let events = vec![
+            set_record(model, Felt::ONE, vec![felt!("0x10")]),
+            set_record(model, Felt::TWO, vec![felt!("0x20")]),
+            upgraded(model, felt!("0x100"), felt!("0x200")),
+            set_record(model, Felt::THREE, vec![felt!("0x30")]),
+            // The values are all written again with the second contract.
+            WorldEvent::StoreUpdateRecord(world::StoreUpdateRecord {
+                selector: model,
+                entity_id: Felt::TWO,
+                values: vec![],
+            }),
+            upgraded(model, felt!("0x200"), felt!("0x300")),
+            set_record(model, felt!("0x4"), vec![felt!("0x40")]),
+        ];
+
+        let result = records_to_migrate(&events, model, felt!("0x300"));
+
+        assert_eq!(
+            result.records,
+            BTreeMap::from([
+                (Felt::ONE, record(vec![felt!("0x10")], felt!("0x100"))),
+                (Felt::TWO, record(vec![felt!("0x20")], felt!("0x200"))),
+                (Felt::THREE, record(vec![felt!("0x30")], felt!("0x200"))),
+            ])
+        );
+    }
+}
@@ -0,0 +1,557 @@
+//! Scenarios, declarative sequences of steps executed against a world.
+//!
+//! A scenario is a TOML file whose steps execute systems, call views, read model records,
+//! assert on the values read, capture values into variables, switch accounts and wait for
+//! blocks. It is meant for end-to-end smoke tests against Katana.
+//!
+//! ```toml
+//! [variables]
+//! player = "0x127fd5f1fe78a71f8bcd1fec63e3fe2f0486b6ecd5c86a0466c3a21fa5cfcec"
+//!
+//! [accounts.bob]
+//! address = "0x13d9ee239f33fea4f8785b9e3870ade909e20a9599ae7cd62c1c292b73af1b7"
+//! private_key = "0x1c9053c053edf324aec366a34c6901b1095b07af69495bffec7d7fe21effb1b"
+//!
+//! [[steps]]
+//! step = "execute"
+//! contract = "ns-actions"
+//! entrypoint = "spawn"
+//!
+//! [[steps]]
+//! step = "model"
+//! tag = "ns-Position"
+//! keys = ["$player"]
+//! capture = "position"
+//!
+//! [[steps]]
+//! step = "assert"
+//! value = "$position.vec.x"
+//! eq = "10"
+//! ```
+//!
+//! Calldata, keys and expected values are typed with the calldata decoder prefixes. Any of them
+//! starting with `$` refers to a variable: `$name` is replaced by the serialized value of the
+//! variable, and `$name.vec.0` by the serialized value of a struct member, an enum variant or an
+//! item of a tuple or an array.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::Ty;
+use dojo_utils::{Invoker, TransactionResult, TxnConfig};
+use dojo_world::config::calldata_decoder::decode_single_calldata;
+use dojo_world::contracts::ContractInfo;
+use serde::Deserialize;
+use starknet::accounts::{Account, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::{BlockId, BlockTag, Call, Felt, FunctionCall};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::Provider;
+use starknet::signers::{LocalWallet, SigningKey};
+
+use crate::model::model_get;
+
+/// The name of the account given to `sozo run`, to switch back to it.
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// The interval at which the block number is polled while waiting for blocks.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of seconds a `wait` step waits for the blocks, if no timeout is given.
+const DEFAULT_WAIT_TIMEOUT: u64 = 60;
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// The initial variables, typed with the calldata decoder prefixes.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// The accounts the steps can switch to, by name.
+    #[serde(default)]
+    pub accounts: HashMap<String, ScenarioAccount>,
+    /// The steps, executed in order.
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioAccount {
+    pub address: String,
+    pub private_key: String,
+}
+
+/// A step of a scenario.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(tag = "step", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// Executes a system in a transaction, waiting for its receipt.
+    Execute {
+        contract: String,
+        entrypoint: String,
+        #[serde(default)]
+        calldata: Vec<String>,
+    },
+    /// Calls a view, the returned values being captured as a tuple of felts.
+    Call {
+        contract: String,
+        entrypoint: String,
+        #[serde(default)]
+        calldata: Vec<String>,
+        capture: Option<String>,
+    },
+    /// Reads a model record, captured as typed by the schema of the model.
+    Model { tag: String, keys: Vec<String>, capture: Option<String> },
+    /// Asserts that a value is equal to the expected one. An enum is compared to the name of
+    /// its variant.
+    Assert { value: String, eq: String },
+    /// Switches to an account of the scenario, or back to the `default` one.
+    Account { name: String },
+    /// Waits for the given number of blocks to be produced, failing after `timeout` seconds (60
+    /// by default).
+    Wait { blocks: u64, timeout: Option<u64> },
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Execute { contract, entrypoint, .. } => {
+                write!(f, "execute {contract}::{entrypoint}")
+            }
+            Step::Call { contract, entrypoint, .. } => write!(f, "call {contract}::{entrypoint}"),
+            Step::Model { tag, keys, .. } => write!(f, "model {tag} [{}]", keys.join(", ")),
+            Step::Assert { value, eq } => write!(f, "assert {value} == {eq}"),
+            Step::Account { name } => write!(f, "account {name}"),
+            Step::Wait { blocks, .. } => write!(f, "wait {blocks} block(s)"),
+        }
+    }
+}
+
+impl Scenario {
+    /// Loads a scenario from a TOML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the scenario {}.", path.display()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse the scenario {}.", path.display()))
+    }
+}
+
+/// Runs the steps of the scenario in order, stopping at the first failing step.
+///
+/// The contracts are resolved by tag from `contracts`, or can be given as addresses.
+pub async fn run_scenario<A>(
+    scenario: &Scenario,
+    account: &A,
+    world_address: Felt,
+    contracts: &HashMap<String, ContractInfo>,
+    txn_config: TxnConfig,
+) -> Result<()>
+where
+    A: ConnectedAccount + Send + Sync,
+{
+    let mut runner = ScenarioRunner {
+        account,
+        accounts: HashMap::new(),
+        current_account: None,
+        world_address,
+        contracts,
+        // The next steps must observe the state written by a transaction.
+        txn_config: TxnConfig { wait: true, ..txn_config },
+        variables: Variables::default(),
+    };
+
+    for (name, value) in &scenario.variables {
+        let felts = decode_single_calldata(value)
+            .with_context(|| format!("Invalid value of the variable `{name}`."))?;
+        runner.variables.set(name, felts_to_ty(felts));
+    }
+
+    for (name, config) in &scenario.accounts {
+        let address = Felt::from_hex(&config.address)
+            .map_err(|_| anyhow!("Invalid address of the account `{name}`."))?;
+        let private_key = Felt::from_hex(&config.private_key)
+            .map_err(|_| anyhow!("Invalid private key of the account `{name}`."))?;
+
+        let signer = LocalWallet::from(SigningKey::from_secret_scalar(private_key));
+        let mut scenario_account = SingleOwnerAccount::new(
+            account.provider(),
+            signer,
+            address,
+            account.chain_id(),
+            ExecutionEncoding::New,
+        );
+        scenario_account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+        runner.accounts.insert(name.clone(), scenario_account);
+    }
+
+    let total = scenario.steps.len();
+
+    for (i, step) in scenario.steps.iter().enumerate() {
+        println!("[{}/{total}] {step}", i + 1);
+
+        runner.run_step(step).await.with_context(|| format!("Step #{} ({step}) failed.", i + 1))?;
+    }
+
+    println!("Scenario passed: {total} steps.");
+
+    Ok(())
+}
+
+/// The state of a running scenario.
+struct ScenarioRunner<'a, A>
+where
+    A: ConnectedAccount + Send + Sync,
+{
+    account: &'a A,
+    accounts: HashMap<String, SingleOwnerAccount<&'a A::Provider, LocalWallet>>,
+    /// The account of the scenario in use, `None` for the default account.
+    current_account: Option<String>,
+    world_address: Felt,
+    contracts: &'a HashMap<String, ContractInfo>,
+    txn_config: TxnConfig,
+    variables: Variables,
+}
+
+impl<A> ScenarioRunner<'_, A>
+where
+    A: ConnectedAccount + Send + Sync,
+{
+    async fn run_step(&mut self, step: &Step) -> Result<()> {
+        match step {
+            Step::Execute { contract, entrypoint, calldata } => {
+                let call = Call {
+                    to: self.contract_address(contract)?,
+                    selector: get_selector_from_name(entrypoint)?,
+                    calldata: self.variables.calldata(calldata)?,
+                };
+
+                let result = match &self.current_account {
+                    Some(name) => invoke(&self.accounts[name], self.txn_config, call).await?,
+                    None => invoke(self.account, self.txn_config, call).await?,
+                };
+
+                println!("      {result}");
+            }
+            Step::Call { contract, entrypoint, calldata, capture } => {
+                let output = self
+                    .account
+                    .provider()
+                    .call(
+                        FunctionCall {
+                            contract_address: self.contract_address(contract)?,
+                            entry_point_selector: get_selector_from_name(entrypoint)?,
+                            calldata: self.variables.calldata(calldata)?,
+                        },
+                        BlockId::Tag(BlockTag::Pending),
+                    )
+                    .await?;
+
+                println!(
+                    "      [ {} ]",
+                    output.iter().map(|o| format!("{:#x}", o)).collect::<Vec<_>>().join(" ")
+                );
+
+                if let Some(name) = capture {
+                    let values = output.into_iter().map(felt_ty).collect();
+                    self.variables.set(name, Ty::Tuple(values));
+                }
+            }
+            Step::Model { tag, keys, capture } => {
+                let keys = self.variables.calldata(keys)?;
+
+                let (record, mut schema, values) = model_get(
+                    tag.clone(),
+                    keys.clone(),
+                    self.world_address,
+                    self.account.provider(),
+                    BlockId::Tag(BlockTag::Pending),
+                )
+                .await?;
+
+                println!("{record}");
+
+                if let Some(name) = capture {
+                    let Ty::Struct(record) = &mut schema else {
+                        bail!("The schema of a model must be a struct.");
+                    };
+
+                    record.deserialize_members(&keys, &values)?;
+                    self.variables.set(name, schema);
+                }
+            }
+            Step::Assert { value, eq } => self.variables.check(value, eq)?,
+            Step::Account { name } => {
+                if name == DEFAULT_ACCOUNT {
+                    self.current_account = None;
+                } else if self.accounts.contains_key(name) {
+                    self.current_account = Some(name.clone());
+                } else {
+                    bail!("The account `{name}` is not declared in the scenario.");
+                }
+            }
+            Step::Wait { blocks, timeout } => {
+                let provider = self.account.provider();
+                let target = provider.block_number().await? + blocks;
+                let timeout = timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT);
+
+                let wait = async {
+                    while provider.block_number().await? < target {
+                        tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+                    }
+
+                    anyhow::Ok(())
+                };
+
+                // Katana with instant mining only produces a block per transaction.
+                tokio::time::timeout(Duration::from_secs(timeout), wait).await.map_err(|_| {
+                    anyhow!(
+                        "No block {target} after {timeout} seconds. If Katana mines the blocks \
+                         instantly, run it with `--block-time` for the blocks to be produced \
+                         without transactions."
+                    )
+                })??;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the address of a contract, given by tag or by address.
+    fn contract_address(&self, contract: &str) -> Result<Felt> {
+        if let Some(info) = self.contracts.get(contract) {
+            return Ok(info.address);
+        }
+
+        if contract.starts_with("0x") {
+            return Felt::from_hex(contract).map_err(|_| anyhow!("Invalid address `{contract}`."));
+        }
+
+        bail!("The contract `{contract}` is not found.")
+    }
+}
+
+/// The variables of a scenario, captured by the steps.
+#[derive(Debug, Default)]
+struct Variables(HashMap<String, Ty>);
+
+impl Variables {
+    fn set(&mut self, name: &str, value: Ty) {
+        self.0.insert(name.to_string(), value);
+    }
+
+    /// Decodes the calldata, replacing the variables by their serialized value.
+    fn calldata(&self, calldata: &[String]) -> Result<Vec<Felt>> {
+        let mut felts = vec![];
+
+        for arg in calldata {
+            if arg.starts_with('$') {
+                felts.extend(self.variable(arg)?.serialize()?);
+            } else {
+                felts.extend(decode_single_calldata(arg)?);
+            }
+        }
+
+        Ok(felts)
+    }
+
+    /// Returns the value referenced by `$name`, followed by an optional path of members.
+    fn variable(&self, reference: &str) -> Result<&Ty> {
+        let mut path = reference.trim_start_matches('$').split('.');
+        let name = path.next().unwrap_or_default();
+
+        let mut ty = self.0.get(name).ok_or_else(|| anyhow!("The variable `{name}` is not set."))?;
+
+        for segment in path {
+            ty = member(ty, segment)
+                .ok_or_else(|| anyhow!("`{segment}` is not found in `{reference}`."))?;
+        }
+
+        Ok(ty)
+    }
+
+    /// Checks that the value referenced by `value` is equal to `expected`.
+    fn check(&self, value: &str, expected: &str) -> Result<()> {
+        let actual = self.variable(value)?;
+
+        // An enum is serialized with the values of all its variants, hence the comparison with
+        // the name of the variant.
+        if let (Ty::Enum(e), false) = (actual, expected.starts_with('$')) {
+            let variant = &e.option()?.name;
+
+            if variant != expected {
+                bail!("Assertion failed: `{value}` is `{variant}`, expected `{expected}`.");
+            }
+
+            return Ok(());
+        }
+
+        let actual = actual.serialize()?;
+        let expected_felts = self.calldata(&[expected.to_string()])?;
+
+        if actual != expected_felts {
+            bail!(
+                "Assertion failed: `{value}` is [{}], expected `{expected}` [{}].",
+                format_felts(&actual),
+                format_felts(&expected_felts)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Invokes a single call with the given account.
+async fn invoke<A>(account: &A, txn_config: TxnConfig, call: Call) -> Result<TransactionResult>
+where
+    A: ConnectedAccount + Send + Sync,
+{
+    let mut invoker = Invoker::new(account, txn_config);
+    invoker.add_call(call);
+
+    invoker.multicall().await.map_err(|e| anyhow!(e.to_string()))
+}
+
+/// Returns the member of a struct, the variant of an enum if it is the one set, or the item of
+/// a tuple or an array.
+fn member<'a>(ty: &'a Ty, segment: &str) -> Option<&'a Ty> {
+    match ty {
+        Ty::Struct(s) => s.get(segment),
+        Ty::Enum(e) => e.option().ok().filter(|o| o.name == segment).map(|o| &o.ty),
+        Ty::Tuple(items) | Ty::Array(items) | Ty::FixedArray(items, _) => {
+            segment.parse::<usize>().ok().and_then(|i| items.get(i))
+        }
+        _ => None,
+    }
+}
+
+fn felt_ty(felt: Felt) -> Ty {
+    Ty::Primitive(Primitive::Felt252(Some(felt)))
+}
+
+/// Types decoded felts, a single felt being kept as is.
+fn felts_to_ty(felts: Vec<Felt>) -> Ty {
+    if felts.len() == 1 {
+        felt_ty(felts[0])
+    } else {
+        Ty::Tuple(felts.into_iter().map(felt_ty).collect())
+    }
+}
+
+fn format_felts(felts: &[Felt]) -> String {
+    felts.iter().map(|f| format!("{:#x}", f)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::{Enum, EnumOption, Member, Struct};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_scenario() {
+        let scenario: Scenario = toml::from_str(
+            r#"
+            [variables]
+            player = "0x1"
+
+            [accounts.bob]
+            address = "0x2"
+            private_key = "0x3"
+
+            [[steps]]
+            step = "execute"
+            contract = "ns-actions"
+            entrypoint = "spawn"
+
+            [[steps]]
+            step = "model"
+            tag = "ns-Position"
+            keys = ["$player"]
+            capture = "position"
+
+            [[steps]]
+            step = "assert"
+            value = "$position.vec.x"
+            eq = "10"
+
+            [[steps]]
+            step = "account"
+            name = "bob"
+
+            [[steps]]
+            step = "wait"
+            blocks = 2
+            timeout = 30
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.variables["player"], "0x1");
+        assert_eq!(scenario.accounts["bob"].private_key, "0x3");
+        assert_eq!(scenario.steps.len(), 5);
+        assert_eq!(
+            scenario.steps[0],
+            Step::Execute {
+                contract: "ns-actions".to_string(),
+                entrypoint: "spawn".to_string(),
+                calldata: vec![]
+            }
+        );
+        assert_eq!(scenario.steps[4], Step::Wait { blocks: 2, timeout: Some(30) });
+        assert_eq!(scenario.steps[2].to_string(), "assert $position.vec.x == 10");
+    }
+
+    #[test]
+    fn test_variables_and_assertions() {
+        let position = Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![
+                Member {
+                    name: "vec".to_string(),
+                    ty: Ty::Tuple(vec![
+                        Ty::Primitive(Primitive::U32(Some(10))),
+                        Ty::Primitive(Primitive::U32(Some(20))),
+                    ]),
+                    key: false,
+                    packed: false,
+                },
+                Member {
+                    name: "direction".to_string(),
+                    ty: Ty::Enum(Enum {
+                        name: "Direction".to_string(),
+                        option: Some(1),
+                        options: vec![
+                            EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Right".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                    key: false,
+                    packed: false,
+                },
+            ],
+        });
+
+        let mut variables = Variables::default();
+        variables.set("position", position);
+        variables.set("ten", felt_ty(Felt::from(10)));
+
+        let calldata =
+            variables.calldata(&["$position.vec".to_string(), "0x1".to_string()]).unwrap();
+        assert_eq!(calldata, [Felt::from(10), Felt::from(20), Felt::ONE]);
+
+        assert!(variables.check("$position.vec.0", "10").is_ok());
+        assert!(variables.check("$position.vec.0", "$ten").is_ok());
+        assert!(variables.check("$position.vec.1", "10").is_err());
+        assert!(variables.check("$position.direction", "Right").is_ok());
+        assert!(variables.check("$position.direction", "Left").is_err());
+        assert!(variables.check("$position.unknown", "10").is_err());
+        assert!(variables.check("$unknown", "10").is_err());
+    }
+}
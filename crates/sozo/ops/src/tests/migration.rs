@@ -5,8 +5,8 @@ use anyhow::Result;
 use dojo_test_utils::compiler::CompilerTestSetup;
 use dojo_test_utils::migration::copy_spawn_and_move_db;
 use dojo_utils::TxnConfig;
-use dojo_world::config::ResourceConfig;
-use dojo_world::contracts::WorldContract;
+use dojo_world::config::{ResourceConfig, SeedConfig};
+use dojo_world::contracts::{WorldContract, WorldContractReader};
 use dojo_world::diff::WorldDiff;
 use dojo_world::services::MockUploadService;
 use katana_runner::RunnerCtx;
@@ -16,7 +16,7 @@ use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 use starknet_crypto::Felt;
 
-use crate::migrate::{Migration, MigrationResult};
+use crate::migrate::{FeeReport, Migration, MigrationResult, MigrationStep};
 use crate::migration_ui::MigrationUi;
 
 /// Sets up the world diff from the environment and returns the world diff used to create a
@@ -87,19 +87,28 @@ async fn migrate_spawn_and_move(sequencer: &RunnerCtx, with_metadata: bool) -> M
 #[tokio::test(flavor = "multi_thread")]
 #[katana_runner::test(accounts = 10)]
 async fn migrate_from_local(sequencer: &RunnerCtx) {
-    let MigrationResult { manifest, has_changes } = migrate_spawn_and_move(sequencer, false).await;
+    let MigrationResult { manifest, has_changes, fee_report } =
+        migrate_spawn_and_move(sequencer, false).await;
 
     assert!(has_changes);
     assert_eq!(manifest.contracts.len(), 4);
+
+    let steps = fee_report.steps().into_iter().map(|(step, _)| step).collect::<Vec<_>>();
+    assert!(steps.contains(&MigrationStep::World));
+    assert!(steps.contains(&MigrationStep::Declare));
+    assert!(steps.contains(&MigrationStep::Resources));
+    assert!(fee_report.totals().l2_gas > 0);
 }
 
 #[tokio::test(flavor = "multi_thread")]
 #[katana_runner::test(accounts = 10, db_dir = copy_spawn_and_move_db().as_str())]
 async fn migrate_no_change(sequencer: &RunnerCtx) {
-    let MigrationResult { manifest, has_changes } = migrate_spawn_and_move(sequencer, false).await;
+    let MigrationResult { manifest, has_changes, fee_report } =
+        migrate_spawn_and_move(sequencer, false).await;
 
     assert!(!has_changes);
     assert_eq!(manifest.contracts.len(), 4);
+    assert!(fee_report.is_empty());
 }
 
 // helper to check metadata of a list of resources
@@ -164,3 +173,81 @@ async fn upload_metadata(sequencer: &RunnerCtx) {
     check_resources(&world_diff, profile_config.models, 3, &is_set);
     check_resources(&world_diff, profile_config.events, 1, &is_set);
 }
+
+/// Applies the given seeds to the migrated spawn-and-move world.
+async fn seed_spawn_and_move(
+    sequencer: &RunnerCtx,
+    seeds: Vec<SeedConfig>,
+) -> Result<(usize, FeeReport)> {
+    let account = sequencer.account(0);
+    let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(sequencer.url())));
+
+    let world_diff = setup_migration("spawn-and-move", Profile::DEV, provider).await?;
+
+    let world_address = world_diff.world_info.address;
+    let mut profile_config = world_diff.profile_config.clone();
+    profile_config.seed = Some(seeds);
+
+    let migration = Migration::new(
+        world_diff,
+        WorldContract::new(world_address, &account),
+        TxnConfig::init_wait(),
+        profile_config,
+        sequencer.url().to_string(),
+        false,
+    );
+
+    let mut ui = MigrationUi::new(None).with_silent();
+
+    migration.seed(&mut ui).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[katana_runner::test(accounts = 10, db_dir = copy_spawn_and_move_db().as_str())]
+async fn seed_records(sequencer: &RunnerCtx) {
+    let moves = |values: &[&str]| SeedConfig {
+        model: Some("ns-Moves".to_string()),
+        keys: vec!["0x1".to_string()],
+        values: Some(values.iter().map(|v| v.to_string()).collect()),
+        ..Default::default()
+    };
+
+    // The second seed has the same guard, and is skipped even if the record is not written yet.
+    let (applied, fee_report) =
+        seed_spawn_and_move(sequencer, vec![moves(&["3", "1"]), moves(&["5", "2"])])
+            .await
+            .expect("Seeding failed.");
+
+    assert_eq!(applied, 1);
+    assert!(!fee_report.is_empty());
+
+    let provider = JsonRpcClient::new(HttpTransport::new(sequencer.url()));
+    let world_address = setup_migration("spawn-and-move", Profile::DEV, Arc::new(provider.clone()))
+        .await
+        .expect("Failed to setup migration")
+        .world_info
+        .address;
+
+    let world_reader = WorldContractReader::new(world_address, provider);
+    let model = world_reader.model_reader_with_tag("ns-Moves").await.unwrap();
+    assert_eq!(
+        model.entity_storage(&[Felt::ONE]).await.unwrap(),
+        vec![Felt::from(3), Felt::ONE]
+    );
+
+    // The record exists, the seeds are not applied again.
+    let (applied, fee_report) =
+        seed_spawn_and_move(sequencer, vec![moves(&["5", "2"])]).await.expect("Seeding failed.");
+
+    assert_eq!(applied, 0);
+    assert!(fee_report.is_empty());
+
+    let unknown_contract = SeedConfig {
+        contract: Some("ns-unknown".to_string()),
+        entrypoint: Some("spawn".to_string()),
+        ..Default::default()
+    };
+
+    let err = seed_spawn_and_move(sequencer, vec![unknown_contract]).await.unwrap_err();
+    assert_eq!(err.to_string(), "The contract `ns-unknown` of seed #1 is not found.");
+}
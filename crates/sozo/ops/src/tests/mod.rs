@@ -5,3 +5,4 @@
 // mod utils;
 
 mod migration;
+mod storage;
@@ -106,11 +106,13 @@ async fn test_model_ops(sequencer: &RunnerCtx) {
                     dojo_types::primitive::Primitive::ContractAddress(None),
                 ),
                 key: true,
+                packed: false,
             },
             dojo_types::schema::Member {
                 name: "remaining".to_string(),
                 ty: dojo_types::schema::Ty::Primitive(dojo_types::primitive::Primitive::U8(None)),
                 key: false,
+                packed: false,
             },
             dojo_types::schema::Member {
                 name: "last_direction".to_string(),
@@ -141,6 +143,7 @@ async fn test_model_ops(sequencer: &RunnerCtx) {
                     ],
                 }),
                 key: false,
+                packed: false,
             },
         ],
     });
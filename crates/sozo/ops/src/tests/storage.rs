@@ -0,0 +1,68 @@
+use cainome::cairo_serde::{ByteArray, CairoSerde};
+use dojo_test_utils::compiler::CompilerTestSetup;
+use dojo_test_utils::migration::copy_spawn_and_move_db;
+use dojo_utils::{TransactionExt, TxnConfig};
+use dojo_world::contracts::abigen::world::Resource;
+use dojo_world::contracts::naming::compute_selector_from_tag;
+use dojo_world::contracts::WorldContractReader;
+use katana_runner::RunnerCtx;
+use scarb::compiler::Profile;
+use sozo_scarbext::WorkspaceExt;
+use starknet::accounts::Account;
+use starknet::core::types::{BlockId, BlockTag, Call, Felt};
+use starknet::core::utils::get_selector_from_name;
+
+/// Checks that the entities read from the storage slots of the world are the same as the ones
+/// returned by the world itself, for all the kinds of layouts used by spawn-and-move.
+#[tokio::test(flavor = "multi_thread")]
+#[katana_runner::test(accounts = 10, db_dir = copy_spawn_and_move_db().as_str())]
+async fn entity_storage_from_slots_matches_world(sequencer: &RunnerCtx) {
+    let setup = CompilerTestSetup::from_examples("../../dojo/core", "../../../examples/");
+    let config = setup.build_test_config("spawn-and-move", Profile::DEV);
+    let ws = scarb::ops::read_workspace(config.manifest_path(), &config).unwrap();
+    let world_address = ws.load_world_local().unwrap().deterministic_world_address().unwrap();
+
+    let account = sequencer.account(0);
+    let provider = sequencer.provider();
+
+    let mut world = WorldContractReader::new(world_address, provider);
+    world.set_block(BlockId::Tag(BlockTag::Pending));
+
+    let Resource::Contract((actions_address, _)) =
+        world.resource(&compute_selector_from_tag("ns-actions")).call().await.unwrap()
+    else {
+        panic!("No actions contract found in world");
+    };
+
+    let name = ByteArray::from_string("a name longer than a single bytes31 word").unwrap();
+    let calls = vec![
+        Call {
+            to: actions_address.into(),
+            selector: get_selector_from_name("spawn").unwrap(),
+            calldata: vec![],
+        },
+        Call {
+            to: actions_address.into(),
+            selector: get_selector_from_name("move").unwrap(),
+            calldata: vec![Felt::TWO],
+        },
+        Call {
+            to: actions_address.into(),
+            selector: get_selector_from_name("set_player_config").unwrap(),
+            calldata: ByteArray::cairo_serialize(&name),
+        },
+    ];
+
+    account.execute_v3(calls).send_with_cfg(&TxnConfig::init_wait()).await.unwrap();
+
+    let keys = vec![account.address()];
+
+    for tag in ["ns-Position", "ns-Moves", "ns-PlayerConfig"] {
+        let model = world.model_reader_with_tag(tag).await.unwrap();
+
+        let expected = model.entity_storage(&keys).await.unwrap();
+        let values = model.entity_storage_from_slots(&keys).await.unwrap();
+
+        assert_eq!(values, expected, "Mismatch for {tag}.");
+    }
+}
@@ -31,6 +31,8 @@ pub trait WorkspaceExt {
     fn write_manifest_profile(&self, manifest: impl Serialize) -> Result<()>;
     /// Reads the manifest for the current profile.
     fn read_manifest_profile(&self) -> Result<Option<Manifest>>;
+    /// Writes the migration fee report for the current profile, next to the manifest.
+    fn write_fee_report_profile(&self, fee_report: impl Serialize) -> Result<()>;
 }
 
 impl WorkspaceExt for Workspace<'_> {
@@ -156,4 +158,17 @@ impl WorkspaceExt for Workspace<'_> {
 
         Ok(Some(serde_json::from_reader(file.deref_mut())?))
     }
+
+    fn write_fee_report_profile(&self, fee_report: impl Serialize) -> Result<()> {
+        let profile_name = self.current_profile()?.to_string();
+        let fee_report_name = format!("fee_report_{}.json", &profile_name);
+
+        let manifest_dir = self.manifest_path().parent().unwrap();
+        let manifest_dir = Filesystem::new(manifest_dir.into());
+
+        let mut file =
+            manifest_dir.create_rw(fee_report_name, "Dojo fee report file", self.config())?;
+
+        Ok(serde_json::to_writer_pretty(file.deref_mut(), &fee_report)?)
+    }
 }
@@ -0,0 +1,45 @@
+#!/bin/bash
+#
+# Execute this script being into `examples/spawn-and-move`.
+# And then -> `./scripts/sponsored_spawn.sh`
+#
+# The player (first Katana account) signs the `spawn` call as a SNIP-9 outside execution,
+# and the paymaster (second Katana account) relays it and pays the fees.
+#
+set -euo pipefail
+pushd $(dirname "$0")/..
+
+export RPC_URL="http://localhost:5050";
+
+ACCOUNTS=$(curl -s -X POST -H "Content-Type: application/json" \
+    -d '{"jsonrpc":"2.0","method":"dev_predeployedAccounts","params":[],"id":1}' $RPC_URL)
+
+PLAYER_ADDRESS=$(echo $ACCOUNTS | jq -r '.result[0].address')
+PLAYER_PRIVATE_KEY=$(echo $ACCOUNTS | jq -r '.result[0].privateKey')
+PAYMASTER_ADDRESS=$(echo $ACCOUNTS | jq -r '.result[1].address')
+PAYMASTER_PRIVATE_KEY=$(echo $ACCOUNTS | jq -r '.result[1].privateKey')
+
+PAYLOAD=$(mktemp)
+
+echo "---------------------------------------------------------------------------"
+echo player : $PLAYER_ADDRESS
+echo " "
+echo paymaster : $PAYMASTER_ADDRESS
+echo "---------------------------------------------------------------------------"
+
+# The player only signs, no transaction is sent from its account.
+sozo execute dojo_examples-actions spawn \
+    --account-address $PLAYER_ADDRESS \
+    --private-key $PLAYER_PRIVATE_KEY \
+    --paymaster.address $PAYMASTER_ADDRESS \
+    --outside-execution.output $PAYLOAD
+
+# The paymaster relays the signed payload.
+sozo execute --relay $PAYLOAD \
+    --account-address $PAYMASTER_ADDRESS \
+    --private-key $PAYMASTER_PRIVATE_KEY \
+    --wait
+
+rm $PAYLOAD
+
+echo "Sponsored spawn has been successfully relayed."